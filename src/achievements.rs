@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Exercises, UserDataPack, tracking_for};
+use crate::stats::DateRange;
+use crate::streaks::{DayMark, Streak};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Rule {
+    // any completed workout or cardio session counts
+    Workouts { count: u32 },
+    // heaviest working set on the exercise, in kg
+    LiftWeight { exercise: Exercises, weight: f32 },
+    // total volume of a single monday to sunday week
+    WeekVolume { kg: f32 },
+    // days the water goal was met, not necessarily in a row
+    WaterGoalDays { days: u32 },
+    // consecutive days with at least `meals` meals logged
+    MealDays { days: u32, meals: u32 },
+    PlanStreak { days: u32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub rule: Rule,
+}
+
+impl Achievement {
+    fn new(id: &'static str, name: &'static str, description: &'static str, rule: Rule) -> Self {
+        Self { id, name, description, rule }
+    }
+}
+
+pub fn catalog() -> Vec<Achievement> {
+    vec![
+        Achievement::new("first_workout", "first step", "finish your first workout", Rule::Workouts { count: 1 }),
+        Achievement::new("fifty_workouts", "regular", "finish 50 workouts", Rule::Workouts { count: 50 }),
+        Achievement::new("bench_100", "first 100 kg bench", "bench press 100 kg for a working set", Rule::LiftWeight { exercise: Exercises::BenchPress, weight: 100.0 }),
+        Achievement::new("squat_140", "140 kg squat", "squat 140 kg for a working set", Rule::LiftWeight { exercise: Exercises::Squat, weight: 140.0 }),
+        Achievement::new("deadlift_180", "180 kg deadlift", "deadlift 180 kg for a working set", Rule::LiftWeight { exercise: Exercises::Deadlift, weight: 180.0 }),
+        Achievement::new("week_10000", "10,000 kg week", "lift 10,000 kg in a single week", Rule::WeekVolume { kg: 10_000.0 }),
+        Achievement::new("water_30", "30 days water goal", "meet your water goal on 30 days", Rule::WaterGoalDays { days: 30 }),
+        Achievement::new("meals_month", "every meal for a month", "log three meals a day for 30 days in a row", Rule::MealDays { days: 30, meals: 3 }),
+        Achievement::new("plan_streak_14", "two weeks on plan", "follow your plan 14 days in a row", Rule::PlanStreak { days: 14 }),
+    ]
+}
+
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub achievement: Achievement,
+    pub progress: f32,
+    pub target: f32,
+    pub unlocked: Option<NaiveDate>,
+}
+
+impl Badge {
+    pub fn fraction(&self) -> f32 {
+        if self.unlocked.is_some() { 1.0 } else { (self.progress / self.target).clamp(0.0, 1.0) }
+    }
+}
+
+// every rule returns its progress, target and the first day the target was reached,
+// plan rules read the streak the caller already has instead of walking the history again
+fn evaluate(rule: &Rule, data: &UserDataPack, plan: &Streak) -> (f32, f32, Option<NaiveDate>) {
+    let workouts = &data.all_workout_data.workouts;
+
+    match rule {
+        Rule::Workouts { count } => {
+            let mut dates: Vec<NaiveDate> = workouts.iter().map(|workout| workout.date)
+                .chain(data.all_workout_data.cardio.iter().map(|session| session.date))
+                .collect();
+            dates.sort();
+            (dates.len() as f32, *count as f32, dates.get(count.saturating_sub(1) as usize).copied())
+        }
+        Rule::LiftWeight { exercise, weight } => {
+            // scored like prs, so a bodyweight lift counts the body and the added load
+            let mode = tracking_for(exercise);
+            let body_weight = data.user_information.body_weight();
+            let mut lifts: Vec<(NaiveDate, f32)> = workouts.iter()
+                .flat_map(|workout| workout.exercises.iter().filter(|done| &done.exercise == exercise).map(move |done| (workout.date, done)))
+                .map(|(date, done)| (date, done.working_sets().map(|set| set.score(mode, body_weight)).fold(0.0, f32::max)))
+                .collect();
+            lifts.sort_by_key(|(date, _)| *date);
+            let best = lifts.iter().map(|(_, lifted)| *lifted).fold(0.0, f32::max);
+            (best, *weight, lifts.iter().find(|(_, lifted)| lifted >= weight).map(|(date, _)| *date))
+        }
+        Rule::WeekVolume { kg } => {
+            let body_weight = data.user_information.body_weight();
+            let mut sessions: Vec<(NaiveDate, f32)> = workouts.iter()
+                .map(|workout| (workout.date, workout.exercises.iter().map(|done| done.volume(body_weight)).sum()))
+                .collect();
+            sessions.sort_by_key(|(date, _)| *date);
+
+            let mut weeks: BTreeMap<NaiveDate, f32> = BTreeMap::new();
+            let mut unlocked = None;
+            for (date, volume) in sessions {
+                let week = weeks.entry(DateRange::week_of(date).from).or_default();
+                *week += volume;
+                if unlocked.is_none() && *week >= *kg {
+                    unlocked = Some(date);
+                }
+            }
+            (weeks.values().copied().fold(0.0, f32::max), *kg, unlocked)
+        }
+        Rule::WaterGoalDays { days } => {
+            let goal = data.water_data.water_goal;
+            let met: BTreeSet<NaiveDate> = data.water_data.water_history.iter()
+                .filter(|(_, drinks)| !drinks.is_empty() && drinks.iter().map(|drink| drink.beverage.amount).sum::<u32>() >= goal)
+                .map(|(date, _)| *date)
+                .collect();
+            (met.len() as f32, *days as f32, met.iter().nth(days.saturating_sub(1) as usize).copied())
+        }
+        Rule::MealDays { days, meals } => {
+            let logged: BTreeSet<NaiveDate> = data.macro_data.meal_history.iter()
+                .filter(|(_, eats)| eats.len() as u32 >= *meals)
+                .map(|(date, _)| *date)
+                .collect();
+            run_of(logged.into_iter(), *days)
+        }
+        Rule::PlanStreak { days } => {
+            // frozen and neutral days neither extend nor break the run, same as the streak itself
+            let mut run = 0;
+            let mut best = 0;
+            let mut unlocked = None;
+            for (date, mark) in plan.days.iter() {
+                match mark {
+                    DayMark::Hit => run += 1,
+                    DayMark::Missed => run = 0,
+                    _ => {}
+                }
+                best = best.max(run);
+                if unlocked.is_none() && run >= *days {
+                    unlocked = Some(*date);
+                }
+            }
+            (best as f32, *days as f32, unlocked)
+        }
+    }
+}
+
+// longest run of consecutive dates and the day it first reached `days`
+fn run_of(dates: impl Iterator<Item = NaiveDate>, days: u32) -> (f32, f32, Option<NaiveDate>) {
+    let mut previous: Option<NaiveDate> = None;
+    let mut run = 0;
+    let mut best = 0;
+    let mut unlocked = None;
+    for date in dates {
+        run = if previous == Some(date - Duration::days(1)) { run + 1 } else { 1 };
+        best = best.max(run);
+        if unlocked.is_none() && run >= days {
+            unlocked = Some(date);
+        }
+        previous = Some(date);
+    }
+    (best as f32, days as f32, unlocked)
+}
+
+pub fn evaluate_all(data: &UserDataPack, plan: &Streak) -> Vec<Badge> {
+    catalog()
+        .into_iter()
+        .map(|achievement| {
+            let (progress, target, unlocked) = evaluate(&achievement.rule, data, plan);
+            Badge { achievement, progress, target, unlocked }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExerciseDone, SetDone, WorkoutDone};
+
+    fn bench(date: NaiveDate, weight: f32) -> WorkoutDone {
+        let sets = vec![SetDone { weight, reps: 5, ..SetDone::default() }, SetDone { weight: 140.0, reps: 5, warmup: true, ..SetDone::default() }];
+        WorkoutDone { date, exercises: vec![ExerciseDone { exercise: Exercises::BenchPress, sets, notes: String::new() }], ..WorkoutDone::default() }
+    }
+
+    #[test]
+    fn lifts_unlock_on_the_first_working_set_that_reaches_them() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut data = UserDataPack::default();
+        data.all_workout_data.workouts = vec![bench(start + Duration::days(7), 102.5), bench(start, 90.0), bench(start + Duration::days(3), 100.0)];
+
+        let rule = Rule::LiftWeight { exercise: Exercises::BenchPress, weight: 100.0 };
+        assert_eq!(evaluate(&rule, &data, &Streak::default()), (102.5, 100.0, Some(start + Duration::days(3))));
+        assert_eq!(evaluate(&Rule::Workouts { count: 2 }, &data, &Streak::default()).2, Some(start + Duration::days(3)));
+        assert_eq!(evaluate(&Rule::Workouts { count: 5 }, &data, &Streak::default()).2, None);
+    }
+
+    #[test]
+    fn plan_streaks_count_the_given_days() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let marks = [DayMark::Hit, DayMark::Frozen, DayMark::Hit, DayMark::Missed, DayMark::Hit];
+        let plan = Streak { days: marks.into_iter().enumerate().map(|(offset, mark)| (start + Duration::days(offset as i64), mark)).collect(), ..Streak::default() };
+        let data = UserDataPack::default();
+        assert_eq!(evaluate(&Rule::PlanStreak { days: 2 }, &data, &plan), (2.0, 2.0, Some(start + Duration::days(2))));
+    }
+
+    #[test]
+    fn runs_restart_after_a_gap() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let dates = [0, 1, 2, 4, 5, 6, 7].map(|offset| start + Duration::days(offset));
+        assert_eq!(run_of(dates.into_iter(), 4), (4.0, 4.0, Some(start + Duration::days(7))));
+        assert_eq!(run_of(dates.into_iter(), 5).2, None);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{collections::HashMap, fmt};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::models::{Equipment, Exercises, MovementPattern, Muscle, Prescription, TargetLoad, WorkoutTemplate, equipment_for, movement_for, muscles_for, tracking_for};
+
+// rough seconds under load per set, plus walking over and setting up each exercise
+const WORK_SECS: u32 = 45;
+const SETUP_SECS: u32 = 120;
+// a second exercise on the same pattern only counts this much
+const REPEATED_PATTERN: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum Experience {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl fmt::Display for Experience {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Experience::Beginner => "beginner",
+            Experience::Intermediate => "intermediate",
+            Experience::Advanced => "advanced",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    pub muscles: Vec<Muscle>,
+    pub minutes: u32,
+    pub equipment: Vec<Equipment>,
+    pub experience: Experience,
+}
+
+impl GeneratorSettings {
+    pub fn default() -> Self {
+        Self {
+            muscles: Vec::new(),
+            minutes: 45,
+            equipment: Equipment::iter().collect(),
+            experience: Experience::Intermediate,
+        }
+    }
+}
+
+fn is_compound(exercise: &Exercises) -> bool {
+    muscles_for(exercise).0.len() > 1 || matches!(movement_for(exercise), MovementPattern::Squat | MovementPattern::Hinge)
+}
+
+// compounds go first while fresh, isolation after and core or grip work last
+fn order(exercise: &Exercises) -> u8 {
+    match movement_for(exercise) {
+        MovementPattern::Core | MovementPattern::Grip => 3,
+        _ if tracking_for(exercise).is_timed() => 3,
+        MovementPattern::KneeExtension | MovementPattern::KneeFlexion => 2,
+        _ if is_compound(exercise) => 0,
+        _ => 1,
+    }
+}
+
+pub fn prescription_for(exercise: &Exercises, experience: Experience) -> Prescription {
+    let compound = is_compound(exercise);
+    let (sets, min_reps, max_reps, load) = match (experience, compound) {
+        (Experience::Beginner, _) => (3, 8, 12, None),
+        (Experience::Intermediate, true) => (3, 6, 10, Some(TargetLoad::Rpe(8.0))),
+        (Experience::Intermediate, false) => (3, 10, 15, Some(TargetLoad::Rpe(8.0))),
+        (Experience::Advanced, true) => (4, 5, 8, Some(TargetLoad::Rpe(8.5))),
+        (Experience::Advanced, false) => (4, 8, 12, Some(TargetLoad::Rpe(9.0))),
+    };
+    let rest_secs = match (experience, compound) {
+        (Experience::Advanced, true) => 180,
+        (_, true) => 150,
+        (_, false) => 90,
+    };
+
+    Prescription { sets, min_reps, max_reps, load, rest_secs, ..Prescription::default() }
+}
+
+pub fn estimated_secs(prescription: &Prescription) -> u32 {
+    prescription.sets * (WORK_SECS + prescription.rest_secs) + SETUP_SECS
+}
+
+// how much of each target muscle an exercise trains, a secondary muscle counts the given share
+fn coverage(exercise: &Exercises, targets: &[Muscle], secondary_share: f32) -> HashMap<Muscle, f32> {
+    let (primary, secondary) = muscles_for(exercise);
+    targets
+        .iter()
+        .filter_map(|muscle| {
+            if primary.contains(muscle) {
+                Some((muscle.clone(), 1.0))
+            } else if secondary.contains(muscle) {
+                Some((muscle.clone(), secondary_share))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// greedy pick of the exercise that covers the most still uncovered target muscles,
+// until the time runs out. once every target is covered the next round starts over
+pub fn generate(settings: &GeneratorSettings, name: String, secondary_share: f32) -> WorkoutTemplate {
+    let budget = settings.minutes * 60;
+    let mut candidates: Vec<Exercises> = Exercises::iter()
+        .filter(|exercise| settings.equipment.contains(&equipment_for(exercise)))
+        .filter(|exercise| !coverage(exercise, &settings.muscles, secondary_share).is_empty())
+        .collect();
+
+    let mut need: HashMap<Muscle, f32> = settings.muscles.iter().map(|muscle| (muscle.clone(), 1.0)).collect();
+    let mut chosen: Vec<(Exercises, Prescription)> = Vec::new();
+    let mut used = 0;
+
+    loop {
+        if need.values().all(|left| *left <= 0.0) {
+            need.values_mut().for_each(|left| *left = 1.0);
+        }
+
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, exercise)| {
+                let gain: f32 = coverage(exercise, &settings.muscles, secondary_share).iter().map(|(muscle, weight)| weight.min(need[muscle].max(0.0))).sum();
+                let repeated = chosen.iter().any(|(other, _)| movement_for(other) == movement_for(exercise));
+                let bonus = if is_compound(exercise) { 0.1 } else { 0.0 };
+                (index, if repeated { gain * REPEATED_PATTERN } else { gain } + bonus)
+            })
+            .filter(|(_, gain)| *gain > 0.1)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let Some((index, _)) = best else { break; };
+        let exercise = candidates.remove(index);
+        let prescription = prescription_for(&exercise, settings.experience);
+        let secs = estimated_secs(&prescription);
+        if used + secs > budget && !chosen.is_empty() {
+            break;
+        }
+
+        for (muscle, weight) in coverage(&exercise, &settings.muscles, secondary_share) {
+            *need.entry(muscle).or_default() -= weight;
+        }
+        used += secs;
+        chosen.push((exercise, prescription));
+    }
+
+    chosen.sort_by_key(|(exercise, _)| order(exercise));
+
+    let mut template = WorkoutTemplate::new(name, chosen.iter().map(|(exercise, _)| exercise.clone()).collect());
+    for (index, (_, prescription)) in chosen.into_iter().enumerate() {
+        template.set_prescription(index, Some(prescription));
+    }
+    template
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SECONDARY_SHARE;
+
+    fn settings(muscles: Vec<Muscle>, minutes: u32, equipment: Vec<Equipment>) -> GeneratorSettings {
+        GeneratorSettings { muscles, minutes, equipment, experience: Experience::Intermediate }
+    }
+
+    #[test]
+    fn covers_every_target_within_the_budget() {
+        let settings = settings(vec![Muscle::LowerChest, Muscle::Quads, Muscle::Lats], 60, Equipment::iter().collect());
+        let template = generate(&settings, String::from("full"), SECONDARY_SHARE);
+
+        for muscle in settings.muscles.iter() {
+            assert!(template.exercises.iter().any(|exercise| coverage(exercise, &[muscle.clone()], SECONDARY_SHARE).contains_key(muscle)), "{muscle} is not trained");
+        }
+        let secs: u32 = (0..template.exercises.len()).map(|index| estimated_secs(&template.prescription(index).unwrap())).sum();
+        assert!(secs <= 60 * 60);
+    }
+
+    #[test]
+    fn keeps_to_the_equipment_and_puts_compounds_first() {
+        let equipment = vec![Equipment::Dumbbell, Equipment::Bodyweight];
+        let template = generate(&settings(vec![Muscle::UpperChest, Muscle::Biceps, Muscle::Abs], 45, equipment.clone()), String::from("home"), SECONDARY_SHARE);
+
+        assert!(!template.exercises.is_empty());
+        assert!(template.exercises.iter().all(|exercise| equipment.contains(&equipment_for(exercise))));
+        assert!(template.exercises.windows(2).all(|pair| order(&pair[0]) <= order(&pair[1])));
+    }
+
+    #[test]
+    fn a_short_budget_still_gets_one_exercise() {
+        let template = generate(&settings(vec![Muscle::Hamstrings], 1, Equipment::iter().collect()), String::from("quick"), SECONDARY_SHARE);
+        assert_eq!(template.exercises.len(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::UserDataPack;
+
+// meals past this count on one day earn nothing, so logging snacks is not farmable
+const MEALS_PER_DAY: u32 = 6;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct XpSettings {
+    pub workout: u32,
+    pub pr: u32,
+    pub meal: u32,
+    pub water_goal: u32,
+    // xp needed to go from level 1 to 2, every next level needs `growth` times more
+    pub base: u32,
+    pub growth: f32,
+}
+
+impl Default for XpSettings {
+    fn default() -> Self {
+        Self {
+            workout: 100,
+            pr: 50,
+            meal: 10,
+            water_goal: 25,
+            base: 300,
+            growth: 1.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XpBreakdown {
+    pub workouts: u32,
+    pub prs: u32,
+    pub meals: u32,
+    pub water: u32,
+}
+
+impl XpBreakdown {
+    pub fn total(&self) -> u32 {
+        self.workouts + self.prs + self.meals + self.water
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LevelProgress {
+    pub level: u32,
+    pub xp: u32,
+    pub into_level: u32,
+    pub needed: u32,
+}
+
+impl LevelProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.needed == 0 { 0.0 } else { self.into_level as f32 / self.needed as f32 }
+    }
+}
+
+impl XpSettings {
+    pub fn needed_for(&self, level: u32) -> u32 {
+        (self.base.max(1) as f32 * self.growth.max(1.0).powi(level.saturating_sub(1) as i32)).round() as u32
+    }
+
+    pub fn progress(&self, xp: u32) -> LevelProgress {
+        let mut level = 1;
+        let mut left = xp;
+        while left >= self.needed_for(level) {
+            left -= self.needed_for(level);
+            level += 1;
+        }
+        LevelProgress { level, xp, into_level: left, needed: self.needed_for(level) }
+    }
+
+    // everything is derived from the history, nothing is stored incrementally
+    pub fn breakdown(&self, data: &UserDataPack) -> XpBreakdown {
+        let workouts = data.all_workout_data.workouts.len() as u32 + data.all_workout_data.cardio.len() as u32;
+        let prs = data.all_workout_data.prs(data.user_information.body_weight()).iter().map(|prs| prs.len() as u32).sum::<u32>();
+        let meals: u32 = data.macro_data.meal_history.values().map(|eats| (eats.len() as u32).min(MEALS_PER_DAY)).sum();
+        let water_days = data.water_data.water_history
+            .values()
+            .filter(|drinks| drinks.iter().map(|drink| drink.beverage.amount).sum::<u32>() >= data.water_data.water_goal)
+            .count() as u32;
+
+        XpBreakdown {
+            workouts: workouts * self.workout,
+            prs: prs * self.pr,
+            meals: meals * self.meal,
+            water: water_days * self.water_goal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::models::{ExerciseDone, Exercises, SetDone, WorkoutDone};
+
+    #[test]
+    fn every_level_needs_more_xp() {
+        let settings = XpSettings::default();
+        assert_eq!((settings.needed_for(1), settings.needed_for(2)), (300, 345));
+
+        let progress = settings.progress(299);
+        assert_eq!((progress.level, progress.into_level, progress.needed), (1, 299, 300));
+        let progress = settings.progress(300);
+        assert_eq!((progress.level, progress.into_level), (2, 0));
+        assert_eq!(settings.progress(645).level, 3);
+    }
+
+    #[test]
+    fn xp_comes_from_the_history() {
+        let mut data = UserDataPack::default();
+        // the defaults log a day of meals and drinks
+        data.macro_data.meal_history.clear();
+        data.water_data.water_history.clear();
+        let bench = |day, weight| {
+            let done = ExerciseDone { exercise: Exercises::BenchPress, sets: vec![SetDone { weight, reps: 5, ..SetDone::default() }], notes: String::new() };
+            WorkoutDone { date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(), exercises: vec![done], ..WorkoutDone::default() }
+        };
+        // the stored count is stale on purpose, prs are worked out from the sets
+        data.all_workout_data.workouts = vec![WorkoutDone { prs: 3, ..bench(5, 90.0) }, bench(3, 100.0)];
+
+        let breakdown = XpSettings::default().breakdown(&data);
+        assert_eq!((breakdown.workouts, breakdown.prs, breakdown.total()), (200, 50, 250));
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::models::{UserDataPack, WorkoutDone};
+
+const ACUTE_DAYS: i64 = 7;
+const CHRONIC_DAYS: i64 = 28;
+// ratios above this are the usual injury risk zone
+pub const RISK_RATIO: f32 = 1.5;
+pub const CAUTION_RATIO: f32 = 1.3;
+pub const LOW_RATIO: f32 = 0.8;
+// monotony above this means the same hard load day after day
+pub const MONOTONY_LIMIT: f32 = 2.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum LoadMethod {
+    SessionRpe,
+    Volume,
+}
+
+impl fmt::Display for LoadMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoadMethod::SessionRpe => "rpe x minutes",
+            LoadMethod::Volume => "volume",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LoadSettings {
+    pub method: LoadMethod,
+    // used when neither the session nor its sets have an rpe
+    pub default_rpe: f32,
+    // kg of volume that make one load unit
+    pub volume_per_unit: f32,
+}
+
+impl Default for LoadSettings {
+    fn default() -> Self {
+        Self {
+            method: LoadMethod::SessionRpe,
+            default_rpe: 7.0,
+            volume_per_unit: 20.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadZone {
+    Low,
+    Sweet,
+    Caution,
+    Risk,
+}
+
+impl fmt::Display for LoadZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoadZone::Low => "below your usual load",
+            LoadZone::Sweet => "in the sweet spot",
+            LoadZone::Caution => "climbing fast",
+            LoadZone::Risk => "high injury risk",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    // daily load for the last 28 days, oldest first
+    pub daily: Vec<(NaiveDate, f32)>,
+    pub acute: f32,
+    pub chronic: f32,
+    pub ratio: Option<f32>,
+    pub monotony: f32,
+    pub strain: f32,
+    pub deload: bool,
+}
+
+impl LoadSettings {
+    pub fn session_load(&self, workout: &WorkoutDone, body_weight: f32) -> f32 {
+        match self.method {
+            LoadMethod::Volume => workout.exercises.iter().map(|done| done.volume(body_weight)).sum::<f32>() / self.volume_per_unit.max(1.0),
+            LoadMethod::SessionRpe => {
+                let rpes: Vec<f32> = workout.exercises.iter().flat_map(|done| done.working_sets()).filter_map(|set| set.rpe).collect();
+                let rpe = workout.rpe.unwrap_or(if rpes.is_empty() { self.default_rpe } else { rpes.iter().sum::<f32>() / rpes.len() as f32 });
+                // short sessions logged without a timer still count a few minutes per set
+                let sets = workout.exercises.iter().map(|done| done.set_count()).sum::<usize>() as u32;
+                let minutes = if workout.length > 0 { workout.length } else { sets * 3 };
+                rpe * minutes as f32
+            }
+        }
+    }
+
+    fn daily_load(&self, data: &UserDataPack, date: NaiveDate) -> f32 {
+        let body_weight = data.user_information.body_weight();
+        let lifting: f32 = data.all_workout_data.workouts.iter().filter(|workout| workout.date == date).map(|workout| self.session_load(workout, body_weight)).sum();
+        // cardio has no sets, its intensity comes from the met value of the activity
+        let cardio: f32 = data.all_workout_data.cardio
+            .iter()
+            .filter(|session| session.date == date)
+            .map(|session| (session.kind.met(session.speed_kmh()) * 0.8).clamp(3.0, 9.0) * session.active_secs() as f32 / 60.0)
+            .sum();
+        lifting + cardio
+    }
+
+    pub fn report(&self, data: &UserDataPack, today: NaiveDate) -> LoadReport {
+        // two extra weeks so the ratio can be followed back over the last days
+        let history: Vec<(NaiveDate, f32)> = (0..CHRONIC_DAYS + 14)
+            .rev()
+            .map(|back| today - Duration::days(back))
+            .map(|date| (date, self.daily_load(data, date)))
+            .collect();
+        let ratio_on = |end: usize| {
+            let acute = mean(&history[end + 1 - ACUTE_DAYS as usize..=end]);
+            let chronic = mean(&history[end + 1 - CHRONIC_DAYS as usize..=end]);
+            if chronic > 0.0 { Some(acute / chronic) } else { None }
+        };
+
+        let last = history.len() - 1;
+        let week = &history[last + 1 - ACUTE_DAYS as usize..];
+        let previous_week = &history[last + 1 - 2 * ACUTE_DAYS as usize..last + 1 - ACUTE_DAYS as usize];
+        let (monotony, strain) = monotony_strain(week);
+        let (previous_monotony, _) = monotony_strain(previous_week);
+
+        // fatigue counts as sustained when the ratio stayed high for a whole week
+        // or two weeks in a row were hard and monotonous
+        let high_week = (0..ACUTE_DAYS as usize).all(|back| ratio_on(last - back).is_some_and(|ratio| ratio >= CAUTION_RATIO));
+        let monotonous = monotony >= MONOTONY_LIMIT && previous_monotony >= MONOTONY_LIMIT;
+
+        LoadReport {
+            daily: history[history.len() - CHRONIC_DAYS as usize..].to_vec(),
+            acute: mean(week),
+            chronic: mean(&history[last + 1 - CHRONIC_DAYS as usize..]),
+            ratio: ratio_on(last),
+            monotony,
+            strain,
+            deload: high_week || monotonous,
+        }
+    }
+}
+
+impl LoadReport {
+    pub fn zone(&self) -> Option<LoadZone> {
+        self.ratio.map(|ratio| {
+            if ratio > RISK_RATIO {
+                LoadZone::Risk
+            } else if ratio > CAUTION_RATIO {
+                LoadZone::Caution
+            } else if ratio < LOW_RATIO {
+                LoadZone::Low
+            } else {
+                LoadZone::Sweet
+            }
+        })
+    }
+
+    pub fn warning(&self) -> bool {
+        matches!(self.zone(), Some(LoadZone::Risk)) || self.deload
+    }
+}
+
+fn mean(days: &[(NaiveDate, f32)]) -> f32 {
+    if days.is_empty() { 0.0 } else { days.iter().map(|(_, load)| load).sum::<f32>() / days.len() as f32 }
+}
+
+// foster's monotony is mean over standard deviation of the daily load, strain is weekly load times monotony
+fn monotony_strain(days: &[(NaiveDate, f32)]) -> (f32, f32) {
+    let average = mean(days);
+    let variance = days.iter().map(|(_, load)| (load - average).powi(2)).sum::<f32>() / days.len().max(1) as f32;
+    let deviation = variance.sqrt();
+    let monotony = if deviation > 0.0 { average / deviation } else { 0.0 };
+    let total: f32 = days.iter().map(|(_, load)| load).sum();
+    (monotony, total * monotony)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExerciseDone, Exercises, SetDone};
+
+    fn trained_on(days: impl Iterator<Item = i64>, today: NaiveDate) -> UserDataPack {
+        let mut data = UserDataPack::default();
+        data.all_workout_data.workouts = days.map(|back| WorkoutDone { date: today - Duration::days(back), length: 60, rpe: Some(5.0), ..WorkoutDone::default() }).collect();
+        data
+    }
+
+    #[test]
+    fn session_load_falls_back_to_set_rpe_and_set_count() {
+        let settings = LoadSettings::default();
+        let sets = vec![SetDone { weight: 100.0, reps: 5, rpe: Some(9.0), ..SetDone::default() }; 4];
+        let mut workout = WorkoutDone { exercises: vec![ExerciseDone { exercise: Exercises::Squat, sets, notes: String::new() }], ..WorkoutDone::default() };
+        assert_eq!(settings.session_load(&workout, 80.0), 108.0);
+
+        workout.rpe = Some(8.0);
+        workout.length = 60;
+        assert_eq!(settings.session_load(&workout, 80.0), 480.0);
+
+        let volume = LoadSettings { method: LoadMethod::Volume, ..LoadSettings::default() };
+        assert_eq!(volume.session_load(&workout, 80.0), 100.0);
+    }
+
+    #[test]
+    fn steady_training_sits_in_the_sweet_spot() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        let report = LoadSettings::default().report(&trained_on(0..42, today), today);
+        assert_eq!(report.ratio, Some(1.0));
+        assert_eq!(report.zone(), Some(LoadZone::Sweet));
+        assert_eq!(report.daily.len(), 28);
+        assert!(!report.warning());
+    }
+
+    #[test]
+    fn a_sudden_week_of_training_is_flagged() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        let report = LoadSettings::default().report(&trained_on(0..7, today), today);
+        assert_eq!(report.ratio, Some(4.0));
+        assert_eq!(report.zone(), Some(LoadZone::Risk));
+        assert!(report.deload && report.warning());
+
+        let empty = LoadSettings::default().report(&trained_on(0..0, today), today);
+        assert_eq!(empty.zone(), None);
+    }
+}
@@ -6,6 +6,16 @@ mod ui;
 mod models;
 mod muscles;
 mod tools;
+mod sharing;
+mod tracks;
+mod stats;
+mod streaks;
+mod levels;
+mod achievements;
+mod load;
+mod recovery;
+mod substitutes;
+mod generator;
 
 fn main() -> Result<(), eframe::Error> {
         let options = eframe::NativeOptions {
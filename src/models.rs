@@ -2,8 +2,14 @@ use std::{collections::HashMap, option, fmt};
 use egui::{Context, ImageSource, TextureHandle, ahash::HashSet, include_image};
 use serde::{Deserialize, Serialize};
 use crate::tools::{load_png, weekday_iso};
+use crate::sharing::{CollisionPolicy, ImportPreview};
+use crate::streaks::{StreakKind, StreakSettings};
+use crate::levels::XpSettings;
+use crate::load::LoadSettings;
+use crate::generator::GeneratorSettings;
+use crate::stats::DateRange;
 use time::OffsetDateTime;
-use chrono::{Local, NaiveDate, NaiveTime};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
 use egui::ahash::HashSetExt;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -86,6 +92,9 @@ pub struct UserDataPack {
     pub planned_workout_data: WorkoutPlannedData,
     pub macro_data: MacroData,
     pub water_data: WaterData,
+    // bumped by every change the statistics are built from, cached aggregates rebuild when it moves
+    #[serde(skip)]
+    pub revision: u64,
 }
 
 impl UserDataPack {
@@ -98,10 +107,201 @@ impl UserDataPack {
             planned_workout_data: WorkoutPlannedData::default(),
             macro_data: MacroData::default(),
             water_data: WaterData::default(),
+            revision: 0,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct TemplateUsage {
+    pub planned: usize,
+    pub schedules: usize,
+    pub programs: Vec<String>,
+}
+
+impl TemplateUsage {
+    pub fn is_empty(&self) -> bool {
+        self.planned == 0 && self.schedules == 0 && self.programs.is_empty()
+    }
+}
+
+impl fmt::Display for TemplateUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.planned > 0 {
+            parts.push(format!("{} planned workouts", self.planned));
+        }
+        if self.schedules > 0 {
+            parts.push(format!("{} repeating schedules", self.schedules));
+        }
+        if !self.programs.is_empty() {
+            parts.push(format!("programs {}", self.programs.join(", ")));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl UserDataPack {
+    // call after editing anything the statistics read without going through a method that does it
+    pub fn touch(&mut self) {
+        self.revision += 1;
+    }
+
+    pub fn record_workout(&mut self, workout: WorkoutDone) {
+        self.all_workout_data.record_workout(workout, self.user_information.body_weight());
+        self.touch();
+    }
+
+    pub fn replace_workout(&mut self, index: usize, workout: WorkoutDone) {
+        self.all_workout_data.replace_workout(index, workout, self.user_information.body_weight());
+        self.touch();
+    }
+
+    pub fn record_cardio(&mut self, mut session: CardioSession) {
+        if session.calories == 0 {
+            session.calories = session.estimate_calories(self.user_information.body_weight());
+        }
+        let date = session.date;
+        self.all_workout_data.cardio.push(session);
+        self.refresh_burned(date);
+        self.touch();
+    }
+
+    // the planned workout it completed is open again once no other log of it is left that day
+    pub fn delete_workout(&mut self, index: usize) {
+        let Some(workout) = self.all_workout_data.workouts.get(index).cloned() else { return; };
+        self.all_workout_data.delete_workout(index, self.user_information.body_weight());
+        self.touch();
+
+        let name = &workout.template.workout_name;
+        let logged = self.all_workout_data.workouts.iter().any(|other| other.date == workout.date && &other.template.workout_name == name);
+        if logged {
+            return;
+        }
+        if let Some(planned) = self.planned_workout_data.workouts
+            .get_mut(&workout.date)
+            .and_then(|planned| planned.iter_mut().find(|planned| planned.completed && planned.cardio.is_none() && &planned.template.workout_name == name)) {
+            planned.completed = false;
+        }
+    }
+
+    pub fn refresh_burned(&mut self, date: NaiveDate) {
+        self.macro_data.calory_burned = self.all_workout_data.cardio
+            .iter()
+            .filter(|session| session.date == date)
+            .map(|session| session.calories)
+            .sum();
+    }
+
+    // upcoming planned workouts, active schedules and programs that point at `name`
+    pub fn template_usage(&self, name: &str, today: NaiveDate) -> TemplateUsage {
+        let planned = self.planned_workout_data.workouts
+            .iter()
+            .filter(|(date, _)| **date >= today)
+            .flat_map(|(_, workouts)| workouts)
+            .filter(|planned| !planned.completed && planned.template.workout_name == name)
+            .count();
+        let schedules = self.planned_workout_data.schedules
+            .iter()
+            .filter(|schedule| schedule.template == name && schedule.end.is_none_or(|end| end >= today))
+            .count();
+        let mut programs: Vec<String> = self.all_workout_data.programs
+            .values()
+            .filter(|program| program.templates().iter().any(|template| template == name))
+            .map(|program| program.name.clone())
+            .collect();
+        programs.sort();
+
+        TemplateUsage { planned, schedules, programs }
+    }
+
+    pub fn rename_template(&mut self, old: &str, new: &str) -> Result<(), String> {
+        let new = new.trim();
+        if new.is_empty() {
+            return Err(String::from("name can't be empty"));
+        }
+        if new == old {
+            return Ok(());
+        }
+        if self.all_workout_data.workout_templates.contains_key(new) {
+            return Err(format!("a template called {} already exists", new));
+        }
+        let mut template = self.all_workout_data.workout_templates
+            .remove(old)
+            .ok_or_else(|| format!("no template called {}", old))?;
+        template.workout_name = new.to_string();
+        self.all_workout_data.workout_templates.insert(new.to_string(), template);
+
+        for planned in self.planned_workout_data.workouts.values_mut().flatten() {
+            if planned.template.workout_name == old {
+                planned.template.workout_name = new.to_string();
+            }
+        }
+        for schedule in self.planned_workout_data.schedules.iter_mut() {
+            if schedule.template == old {
+                schedule.template = new.to_string();
+            }
+        }
+        for day in self.all_workout_data.programs.values_mut().flat_map(|program| program.weeks.iter_mut()).flat_map(|week| week.days.iter_mut()) {
+            if day.template == old {
+                day.template = new.to_string();
+            }
+        }
+        self.touch();
+        Ok(())
+    }
+
+    // `original` is the name the program was opened under, a changed name moves it to the new key
+    pub fn save_program(&mut self, original: Option<&str>, mut program: Program) -> Result<(), String> {
+        program.name = program.name.trim().to_string();
+        if program.name.is_empty() {
+            return Err(String::from("name can't be empty"));
+        }
+        if original != Some(program.name.as_str()) && self.all_workout_data.programs.contains_key(&program.name) {
+            return Err(format!("a program called {} already exists", program.name));
+        }
+        if let Some(old) = original.filter(|old| *old != program.name) {
+            self.all_workout_data.programs.remove(old);
+            for slot in self.planned_workout_data.workouts.values_mut().flatten().filter_map(|planned| planned.program.as_mut()) {
+                if slot.name == old {
+                    slot.name = program.name.clone();
+                }
+            }
+        }
+        self.all_workout_data.programs.insert(program.name.clone(), program);
+        Ok(())
+    }
+
+    // refuses while the template is still in use unless `force` is set, in which case
+    // upcoming occurrences are dropped, schedules end today and program days are removed
+    pub fn delete_template(&mut self, name: &str, today: NaiveDate, force: bool) -> Result<(), String> {
+        let usage = self.template_usage(name, today);
+        if !usage.is_empty() && !force {
+            return Err(format!("{} is used by {}", name, usage));
+        }
+        if self.all_workout_data.workout_templates.remove(name).is_none() {
+            return Err(format!("no template called {}", name));
+        }
+
+        for (date, workouts) in self.planned_workout_data.workouts.iter_mut() {
+            if *date >= today {
+                workouts.retain(|planned| planned.completed || planned.template.workout_name != name);
+            }
+        }
+        self.planned_workout_data.workouts.retain(|_, workouts| !workouts.is_empty());
+        for schedule in self.planned_workout_data.schedules.iter_mut() {
+            if schedule.template == name && schedule.end.is_none_or(|end| end >= today) {
+                schedule.end = Some(today - chrono::Duration::days(1));
+            }
+        }
+        for week in self.all_workout_data.programs.values_mut().flat_map(|program| program.weeks.iter_mut()) {
+            week.days.retain(|day| day.template != name);
+        }
+        self.touch();
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct UserInformation {
     pub name: String,
@@ -123,6 +323,11 @@ impl UserInformation {
             registration_date: String::new(),
         }
     }
+
+    // falls back to a typical adult weight until the profile is filled in
+    pub fn body_weight(&self) -> f32 {
+        if self.weight > 0 { self.weight as f32 } else { 70.0 }
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
@@ -130,6 +335,16 @@ pub struct AccountData {
     pub level: u32,
     pub xp: u32,
     pub current_streak: u32,
+    // kept here so the record survives deleted history
+    #[serde(default)]
+    pub longest_streak: u32,
+    #[serde(default)]
+    pub streak_settings: StreakSettings,
+    #[serde(default)]
+    pub xp_settings: XpSettings,
+    // unlock dates by achievement id, a badge stays earned even if its history is deleted
+    #[serde(default)]
+    pub badges: HashMap<String, NaiveDate>,
     pub lifted_weight: u32,
     pub registrated_cals: u32,
     pub registrated_meals: u32,
@@ -141,6 +356,10 @@ impl AccountData {
             level: 0,
             xp: 0,
             current_streak: 0,
+            longest_streak: 0,
+            streak_settings: StreakSettings::default(),
+            xp_settings: XpSettings::default(),
+            badges: HashMap::new(),
             lifted_weight: 0,
             registrated_cals: 0,
             registrated_meals: 0,
@@ -150,56 +369,412 @@ impl AccountData {
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct AllWorkoutData {
-    pub total_volume: u32,
-    pub total_sets: u32,
-    pub total_reps: u32,
-    pub total_time: u32,
-    pub worked_out: u32,
-    pub prs: u32,
-    pub week_volume: u32,
-    pub week_sets: u32,
-    pub week_reps: u32,
-    pub week_time: u32,
     pub workouts: Vec<WorkoutDone>,
     pub workout_templates: HashMap<String, WorkoutTemplate>,
+    #[serde(default)]
+    pub progression: ProgressionSettings,
+    #[serde(default)]
+    pub programs: HashMap<String, Program>,
+    #[serde(default)]
+    pub plates: PlateInventory,
+    #[serde(default)]
+    pub warmups: WarmupSettings,
+    #[serde(default)]
+    pub cardio: Vec<CardioSession>,
+    #[serde(default)]
+    pub load: LoadSettings,
+    #[serde(default)]
+    pub landmarks: VolumeLandmarks,
+    // equipment that is taken or missing, substitutes needing it rank last
+    #[serde(default)]
+    pub unavailable_equipment: Vec<Equipment>,
 }
 
 impl AllWorkoutData {
     pub fn default() -> Self {
         Self {
-            total_volume: 0,
-            total_sets: 0,
-            total_reps: 0,
-            total_time: 0,
-            worked_out: 0,
-            prs: 0,
-            week_volume: 0,
-            week_sets: 0,
-            week_reps: 0,
-            week_time: 0,
-            workouts: vec![WorkoutDone::default()],
+            workouts: Vec::new(),
             workout_templates: HashMap::from(
                 [
                 (String::from("leg day"), 
-                    WorkoutTemplate{workout_name: String::from("leg day"), 
-                    exercises: vec![Exercises::HackSquat, Exercises::LegExtension, Exercises::LegCurl]}
+                    WorkoutTemplate::new(String::from("leg day"), 
+                    vec![Exercises::HackSquat, Exercises::LegExtension, Exercises::LegCurl])
                 ),
                 (String::from("pull day"),
-                    WorkoutTemplate{workout_name: String::from("pull day"),
-                    exercises: vec![Exercises::BenchPress, Exercises::TricepDips]}
+                    WorkoutTemplate::new(String::from("pull day"),
+                    vec![Exercises::BenchPress, Exercises::TricepDips])
                 )
                 ]
-            )
+            ),
+            progression: ProgressionSettings::default(),
+            programs: HashMap::new(),
+            plates: PlateInventory::default(),
+            warmups: WarmupSettings::default(),
+            cardio: Vec::new(),
+            load: LoadSettings::default(),
+            landmarks: VolumeLandmarks::default(),
+            unavailable_equipment: Vec::new(),
         }
     }
 
     pub fn create_workout_template(&mut self, workout_name: String, exercises: Vec<Exercises>) {
-        self.workout_templates.entry(workout_name.clone()).insert_entry(WorkoutTemplate { workout_name, exercises });
+        self.workout_templates.entry(workout_name.clone()).insert_entry(WorkoutTemplate::new(workout_name, exercises));
+    }
+
+    pub fn duplicate_template(&mut self, name: &str) -> Option<String> {
+        let mut copy = self.workout_templates.get(name)?.clone();
+        let mut copy_name = format!("{} copy", name);
+        let mut n = 2;
+        while self.workout_templates.contains_key(&copy_name) {
+            copy_name = format!("{} copy {}", name, n);
+            n += 1;
+        }
+        copy.workout_name = copy_name.clone();
+        self.workout_templates.insert(copy_name.clone(), copy);
+        Some(copy_name)
+    }
+
+    // saves under the template's own name, numbered like copies when that name is taken
+    pub fn add_template(&mut self, mut template: WorkoutTemplate) -> String {
+        let name = template.workout_name.trim().to_string();
+        let mut unique = name.clone();
+        let mut n = 2;
+        while self.workout_templates.contains_key(&unique) {
+            unique = format!("{} {}", name, n);
+            n += 1;
+        }
+        template.workout_name = unique.clone();
+        self.workout_templates.insert(unique.clone(), template);
+        unique
+    }
+
+    pub fn template_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.workout_templates.values().flat_map(|template| template.tags.clone()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    // sorted by name, optionally only the ones carrying `tag`
+    pub fn templates_tagged(&self, tag: Option<&str>) -> Vec<&WorkoutTemplate> {
+        let mut templates: Vec<&WorkoutTemplate> = self.workout_templates
+            .values()
+            .filter(|template| tag.is_none_or(|tag| template.tags.iter().any(|t| t == tag)))
+            .collect();
+        templates.sort_by(|a, b| a.workout_name.cmp(&b.workout_name));
+        templates
+    }
+
+    pub fn last_performance(&self, exercise: &Exercises) -> Option<&ExerciseDone> {
+        self.workouts
+            .iter()
+            .rev()
+            .flat_map(|workout| workout.exercises.iter())
+            .find(|done| &done.exercise == exercise && done.working_sets().next().is_some())
+    }
+
+    pub fn suggest(&self, exercise: &Exercises) -> Option<SetSuggestion> {
+        let last = self.last_performance(exercise)?;
+        match tracking_for(exercise) {
+            TrackingMode::WeightReps | TrackingMode::BodyweightPlus => Some(self.progression.next(last)),
+            // one more rep on the best set, there's no load to progress
+            TrackingMode::RepsOnly => Some(SetSuggestion::new(0.0, last.working_sets().map(|set| set.reps).max().unwrap_or(0) + 1, last.set_count() as u32)),
+            TrackingMode::TimeOnly | TrackingMode::DistanceTime => None,
+        }
+    }
+
+    // epley estimate from the best set on record
+    pub fn estimated_one_rm(&self, exercise: &Exercises) -> Option<f32> {
+        self.workouts
+            .iter()
+            .flat_map(|workout| workout.exercises.iter())
+            .filter(|done| &done.exercise == exercise)
+            .flat_map(|done| done.working_sets())
+            .filter(|set| set.weight > 0.0 && set.reps > 0)
+            .map(|set| set.weight * (1.0 + set.reps as f32 / 30.0))
+            .reduce(f32::max)
+    }
+
+    pub fn suggest_prescribed(&self, exercise: &Exercises, prescription: Option<&Prescription>, slot: Option<&ProgramSlot>) -> Option<SetSuggestion> {
+        let Some(prescription) = prescription else {
+            return self.suggest_planned(exercise, slot);
+        };
+
+        let settings = self.progression.with_prescription(prescription);
+        let base = self.last_performance(exercise).map(|last| settings.next(last));
+        let increment = self.progression.weight_increment;
+
+        let weight = match prescription.load {
+            Some(TargetLoad::Absolute(weight)) => Some(weight),
+            Some(TargetLoad::PercentOneRm(percent)) => self.estimated_one_rm(exercise).map(|one_rm| ((one_rm * percent / 100.0) / increment).round() * increment),
+            _ => base.as_ref().map(|base| base.weight),
+        }?;
+
+        let reps = base.map_or(prescription.min_reps, |base| base.reps);
+        let suggestion = SetSuggestion::new(weight, reps, prescription.sets);
+
+        match slot {
+            Some(slot) => Some(suggestion.scaled(slot.intensity, slot.volume, increment)),
+            None => Some(suggestion),
+        }
+    }
+
+    pub fn suggest_planned(&self, exercise: &Exercises, slot: Option<&ProgramSlot>) -> Option<SetSuggestion> {
+        let suggestion = self.suggest(exercise)?;
+        match slot {
+            Some(slot) => Some(suggestion.scaled(slot.intensity, slot.volume, self.progression.weight_increment)),
+            None => Some(suggestion),
+        }
+    }
+
+    // left vs right per session for a unilateral exercise, oldest first
+    pub fn side_balance(&self, exercise: &Exercises, body_weight: f32) -> Vec<SideBalance> {
+        self.workouts
+            .iter()
+            .flat_map(|workout| workout.exercises.iter().map(move |done| (workout.date, done)))
+            .filter(|(_, done)| &done.exercise == exercise)
+            .map(|(date, done)| {
+                let (left, right) = (done.side(Side::Left), done.side(Side::Right));
+                SideBalance {
+                    date,
+                    left_volume: left.volume(body_weight),
+                    right_volume: right.volume(body_weight),
+                    left_best: left.best(body_weight),
+                    right_best: right.best(body_weight),
+                }
+            })
+            .filter(|balance| balance.left_best > 0.0 || balance.right_best > 0.0)
+            .collect()
+    }
+
+    // every logged session of the exercise, newest first
+    pub fn exercise_history(&self, exercise: &Exercises) -> Vec<(&WorkoutDone, &ExerciseDone)> {
+        let mut history: Vec<(&WorkoutDone, &ExerciseDone)> = self.workouts
+            .iter()
+            .flat_map(|workout| workout.exercises.iter().filter(|done| &done.exercise == exercise).map(move |done| (workout, done)))
+            .collect();
+        history.sort_by_key(|entry| std::cmp::Reverse(entry.0.date));
+        history
+    }
+
+    // session, exercise and set notes containing the query, newest first. an empty query matches every note
+    pub fn search_notes(&self, query: &str) -> Vec<NoteHit> {
+        let query = query.trim().to_lowercase();
+        let mut hits = Vec::new();
+        for (index, workout) in self.workouts.iter().enumerate() {
+            let mut found = |exercise: Option<&Exercises>, text: &str| {
+                if !text.is_empty() && text.to_lowercase().contains(&query) {
+                    hits.push(NoteHit { index, date: workout.date, workout: workout.template.workout_name.clone(), exercise: exercise.cloned(), text: text.to_string() });
+                }
+            };
+            found(None, &workout.notes);
+            for done in workout.exercises.iter() {
+                found(Some(&done.exercise), &done.notes);
+                for set in done.sets.iter() {
+                    found(Some(&done.exercise), &set.note);
+                }
+            }
+        }
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.date));
+        hits
+    }
+
+    // indices into `workouts` matching the filter, newest first
+    pub fn history(&self, filter: &HistoryFilter) -> Vec<usize> {
+        let mut found: Vec<usize> = (0..self.workouts.len()).filter(|index| filter.matches(&self.workouts[*index])).collect();
+        found.sort_by(|a, b| self.workouts[*b].date.cmp(&self.workouts[*a].date).then(b.cmp(a)));
+        found
+    }
+
+    // exercises each workout beat every earlier session on, indexed like `workouts`;
+    // one pass by date, same-day sessions count in the order they were logged
+    pub fn prs(&self, body_weight: f32) -> Vec<Vec<Exercises>> {
+        let mut order: Vec<usize> = (0..self.workouts.len()).collect();
+        order.sort_by_key(|index| (self.workouts[*index].date, *index));
+
+        let mut best: HashMap<Exercises, f32> = HashMap::new();
+        let mut prs = vec![Vec::new(); self.workouts.len()];
+        for index in order {
+            let exercises = &self.workouts[index].exercises;
+            prs[index] = exercises
+                .iter()
+                .filter(|done| {
+                    let score = done.best(body_weight);
+                    score > 0.0 && score > best.get(&done.exercise).copied().unwrap_or(0.0)
+                })
+                .map(|done| done.exercise.clone())
+                .collect();
+            for done in exercises.iter() {
+                let score = best.entry(done.exercise.clone()).or_insert(0.0);
+                *score = score.max(done.best(body_weight));
+            }
+        }
+        prs
+    }
+
+    pub fn prs_in(&self, index: usize, body_weight: f32) -> Vec<Exercises> {
+        let mut prs = self.prs(body_weight);
+        if index < prs.len() { prs.swap_remove(index) } else { Vec::new() }
+    }
+
+    // edits change what later sessions count as prs, so every record is recounted
+    pub fn replace_workout(&mut self, index: usize, mut workout: WorkoutDone, body_weight: f32) {
+        let Some(current) = self.workouts.get_mut(index) else { return; };
+        workout.exercises.retain(|done| !done.sets.is_empty());
+        workout.volume = workout.exercises.iter().map(|done| done.volume(body_weight)).sum::<f32>() as u32;
+        *current = workout;
+        self.recount_prs(body_weight);
+    }
+
+    pub fn delete_workout(&mut self, index: usize, body_weight: f32) {
+        if index < self.workouts.len() {
+            self.workouts.remove(index);
+            self.recount_prs(body_weight);
+        }
+    }
+
+    fn recount_prs(&mut self, body_weight: f32) {
+        let prs = self.prs(body_weight);
+        for (workout, prs) in self.workouts.iter_mut().zip(prs) {
+            workout.prs = prs.len() as u32;
+        }
+    }
+
+    // a back-dated log can take prs from later sessions, so they are recounted with the same rule as edits
+    pub fn record_workout(&mut self, workout: WorkoutDone, body_weight: f32) {
+        self.workouts.push(workout);
+        self.recount_prs(body_weight);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum ProgressionRule {
+    Linear,
+    Double,
+    Rpe,
+}
+
+impl fmt::Display for ProgressionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProgressionRule::Linear => "linear",
+            ProgressionRule::Double => "double progression",
+            ProgressionRule::Rpe => "rpe based",
+        };
+        write!(f, "{name}")
     }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProgressionSettings {
+    pub rule: ProgressionRule,
+    pub weight_increment: f32,
+    pub min_reps: u32,
+    pub max_reps: u32,
+    pub target_rpe: f32,
+}
+
+impl Default for ProgressionSettings {
+    fn default() -> Self {
+        Self {
+            rule: ProgressionRule::Double,
+            weight_increment: 2.5,
+            min_reps: 8,
+            max_reps: 12,
+            target_rpe: 8.0,
+        }
+    }
+}
+
+impl ProgressionSettings {
+    pub fn with_prescription(&self, prescription: &Prescription) -> Self {
+        let mut settings = Self {
+            min_reps: prescription.min_reps,
+            max_reps: prescription.max_reps.max(prescription.min_reps),
+            ..self.clone()
+        };
+        if let Some(TargetLoad::Rpe(rpe)) = prescription.load {
+            settings.rule = ProgressionRule::Rpe;
+            settings.target_rpe = rpe;
+        }
+        settings
+    }
+
+    // suggestion for the next session, built from the working sets of the last one
+    pub fn next(&self, last: &ExerciseDone) -> SetSuggestion {
+        let sets = last.set_count() as u32;
+        let top = last.working_sets().fold(SetDone::default(), |best, set| if set.weight > best.weight { set.clone() } else { best });
+        let all_reached = |reps: u32| last.working_sets().all(|set| set.reps >= reps);
+
+        match self.rule {
+            ProgressionRule::Linear => {
+                if all_reached(self.max_reps) {
+                    SetSuggestion::new(top.weight + self.weight_increment, self.max_reps, sets)
+                } else {
+                    SetSuggestion::new(top.weight, self.max_reps, sets)
+                }
+            }
+            ProgressionRule::Double => {
+                if all_reached(self.max_reps) {
+                    SetSuggestion::new(top.weight + self.weight_increment, self.min_reps, sets)
+                } else {
+                    let lowest = last.working_sets().map(|set| set.reps).min().unwrap_or(self.min_reps);
+                    SetSuggestion::new(top.weight, (lowest + 1).clamp(self.min_reps, self.max_reps), sets)
+                }
+            }
+            ProgressionRule::Rpe => {
+                if let Some(rpe) = top.rpe {
+                    // roughly 2.5% of load per rpe point, snapped to the increment
+                    let adjusted = top.weight * (1.0 + 0.025 * (self.target_rpe - rpe));
+                    let weight = ((adjusted / self.weight_increment).round() * self.weight_increment).max(0.0);
+                    SetSuggestion::new(weight, top.reps, sets)
+                } else {
+                    let linear = Self { rule: ProgressionRule::Linear, ..self.clone() };
+                    linear.next(last)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct SetSuggestion {
+    pub weight: f32,
+    pub reps: u32,
+    pub sets: u32,
+}
+
+impl SetSuggestion {
+    pub fn new(weight: f32, reps: u32, sets: u32) -> Self {
+        Self {
+            weight,
+            reps,
+            sets,
+        }
+    }
+}
+
+impl SetSuggestion {
+    pub fn scaled(&self, intensity: f32, volume: f32, increment: f32) -> Self {
+        Self {
+            weight: ((self.weight * intensity) / increment).round() * increment,
+            reps: self.reps,
+            sets: ((self.sets as f32 * volume).round() as u32).max(1),
+        }
+    }
+}
 
+impl fmt::Display for SetSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weight > 0.0 {
+            write!(f, "{} x {} kg x {}", self.sets, self.weight, self.reps)
+        } else {
+            write!(f, "{} x {} reps", self.sets, self.reps)
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone, EnumIter)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash, EnumIter)]
 pub enum Exercises {
     #[default] BenchPress,
     TricepDips,
@@ -208,7 +783,16 @@ pub enum Exercises {
     HackSquat,
     LegPress,
     LegExtension,
-    LegCurl
+    LegCurl,
+    PullUp,
+    PushUp,
+    Plank,
+    WallSit,
+    DeadHang,
+    FarmersWalk,
+    BulgarianSplitSquat,
+    SingleArmRow,
+    SingleLegPress,
 }
 
 impl fmt::Display for Exercises {
@@ -222,11 +806,28 @@ impl fmt::Display for Exercises {
             Exercises::LegPress => "Leg Press",
             Exercises::LegExtension => "Leg Extension",
             Exercises::LegCurl => "Leg Curl",
+            Exercises::PullUp => "Pull Up",
+            Exercises::PushUp => "Push Up",
+            Exercises::Plank => "Plank",
+            Exercises::WallSit => "Wall Sit",
+            Exercises::DeadHang => "Dead Hang",
+            Exercises::FarmersWalk => "Farmers Walk",
+            Exercises::BulgarianSplitSquat => "Bulgarian Split Squat",
+            Exercises::SingleArmRow => "Single Arm Row",
+            Exercises::SingleLegPress => "Single Leg Press",
         };
         write!(f, "{name}")
     }
 }
 
+impl Exercises {
+    // accepts both the display name ("Bench Press") and the variant name ("BenchPress")
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted: String = name.chars().filter(|c| !c.is_whitespace() && *c != '_' && *c != '-').collect::<String>().to_lowercase();
+        Exercises::iter().find(|exercise| exercise.to_string().replace(' ', "").to_lowercase() == wanted)
+    }
+}
+
 pub fn muscles_for(ex: &Exercises) -> (Vec<Muscle>, Vec<Muscle>) {
     match ex {
         Exercises::BenchPress => (
@@ -261,6 +862,42 @@ pub fn muscles_for(ex: &Exercises) -> (Vec<Muscle>, Vec<Muscle>) {
             vec![Muscle::Hamstrings],
             vec![Muscle::Hips],
         ),
+        Exercises::PullUp => (
+            vec![Muscle::Lats, Muscle::Biceps],
+            vec![Muscle::RearDelt, Muscle::Forearms],
+        ),
+        Exercises::PushUp => (
+            vec![Muscle::LowerChest, Muscle::Triceps],
+            vec![Muscle::FrontDelt, Muscle::Abs],
+        ),
+        Exercises::Plank => (
+            vec![Muscle::Abs],
+            vec![Muscle::FrontDelt, Muscle::LowerBack],
+        ),
+        Exercises::WallSit => (
+            vec![Muscle::Quads],
+            vec![Muscle::Glutes],
+        ),
+        Exercises::DeadHang => (
+            vec![Muscle::Forearms],
+            vec![Muscle::Lats],
+        ),
+        Exercises::FarmersWalk => (
+            vec![Muscle::Forearms, Muscle::Traps],
+            vec![Muscle::Abs, Muscle::Calfs],
+        ),
+        Exercises::BulgarianSplitSquat => (
+            vec![Muscle::Quads, Muscle::Glutes],
+            vec![Muscle::Hamstrings, Muscle::Adductors],
+        ),
+        Exercises::SingleArmRow => (
+            vec![Muscle::Lats],
+            vec![Muscle::RearDelt, Muscle::Biceps],
+        ),
+        Exercises::SingleLegPress => (
+            vec![Muscle::Quads],
+            vec![Muscle::Glutes, Muscle::Hamstrings],
+        ),
     }
 }
 
@@ -277,102 +914,1519 @@ pub fn muscle_for_workout(exercises: &Vec<Exercises>) -> (Vec<Muscle>, Vec<Muscl
     (primary_muscle.into_iter().collect::<Vec<Muscle>>(), secondary_muscle.into_iter().collect::<Vec<Muscle>>())
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum Equipment {
+    Barbell,
+    Dumbbell,
+    Machine,
+    Cable,
+    Bodyweight,
+}
+
+impl fmt::Display for Equipment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Equipment::Barbell => "barbell",
+            Equipment::Dumbbell => "dumbbell",
+            Equipment::Machine => "machine",
+            Equipment::Cable => "cable",
+            Equipment::Bodyweight => "bodyweight",
+        };
+        write!(f, "{name}")
+    }
+}
+
+pub fn equipment_for(ex: &Exercises) -> Equipment {
+    match ex {
+        Exercises::BenchPress | Exercises::Deadlift | Exercises::Squat => Equipment::Barbell,
+        Exercises::TricepDips | Exercises::PullUp | Exercises::PushUp | Exercises::Plank | Exercises::WallSit | Exercises::DeadHang => Equipment::Bodyweight,
+        Exercises::HackSquat | Exercises::LegPress | Exercises::LegExtension | Exercises::LegCurl => Equipment::Machine,
+        Exercises::FarmersWalk | Exercises::BulgarianSplitSquat | Exercises::SingleArmRow => Equipment::Dumbbell,
+        Exercises::SingleLegPress => Equipment::Machine,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum MovementPattern {
+    HorizontalPush,
+    VerticalPush,
+    HorizontalPull,
+    VerticalPull,
+    Squat,
+    Hinge,
+    Lunge,
+    KneeExtension,
+    KneeFlexion,
+    Core,
+    Grip,
+}
+
+impl fmt::Display for MovementPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MovementPattern::HorizontalPush => "horizontal push",
+            MovementPattern::VerticalPush => "vertical push",
+            MovementPattern::HorizontalPull => "horizontal pull",
+            MovementPattern::VerticalPull => "vertical pull",
+            MovementPattern::Squat => "squat",
+            MovementPattern::Hinge => "hinge",
+            MovementPattern::Lunge => "lunge",
+            MovementPattern::KneeExtension => "knee extension",
+            MovementPattern::KneeFlexion => "knee flexion",
+            MovementPattern::Core => "core",
+            MovementPattern::Grip => "grip / carry",
+        };
+        write!(f, "{name}")
+    }
+}
+
+pub fn movement_for(ex: &Exercises) -> MovementPattern {
+    match ex {
+        Exercises::BenchPress | Exercises::PushUp => MovementPattern::HorizontalPush,
+        Exercises::TricepDips => MovementPattern::VerticalPush,
+        Exercises::SingleArmRow => MovementPattern::HorizontalPull,
+        Exercises::PullUp => MovementPattern::VerticalPull,
+        Exercises::Squat | Exercises::HackSquat | Exercises::LegPress | Exercises::WallSit | Exercises::SingleLegPress => MovementPattern::Squat,
+        Exercises::Deadlift => MovementPattern::Hinge,
+        Exercises::BulgarianSplitSquat => MovementPattern::Lunge,
+        Exercises::LegExtension => MovementPattern::KneeExtension,
+        Exercises::LegCurl => MovementPattern::KneeFlexion,
+        Exercises::Plank => MovementPattern::Core,
+        Exercises::DeadHang | Exercises::FarmersWalk => MovementPattern::Grip,
+    }
+}
+
+pub fn is_unilateral(ex: &Exercises) -> bool {
+    matches!(ex, Exercises::BulgarianSplitSquat | Exercises::SingleArmRow | Exercises::SingleLegPress)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SideBalance {
+    pub date: NaiveDate,
+    pub left_volume: f32,
+    pub right_volume: f32,
+    pub left_best: f32,
+    pub right_best: f32,
+}
+
+impl SideBalance {
+    // percent the weaker side trails the stronger one by
+    pub fn imbalance(left: f32, right: f32) -> f32 {
+        let strong = left.max(right);
+        if strong <= 0.0 { 0.0 } else { (left - right).abs() / strong * 100.0 }
+    }
+
+    pub fn volume_imbalance(&self) -> f32 {
+        Self::imbalance(self.left_volume, self.right_volume)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Side::Left => "L",
+            Side::Right => "R",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum TrackingMode {
+    WeightReps,
+    RepsOnly,
+    TimeOnly,
+    DistanceTime,
+    // weight on the set is what's added on top of the body
+    BodyweightPlus,
+}
+
+impl fmt::Display for TrackingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TrackingMode::WeightReps => "weight and reps",
+            TrackingMode::RepsOnly => "reps only",
+            TrackingMode::TimeOnly => "time",
+            TrackingMode::DistanceTime => "distance and time",
+            TrackingMode::BodyweightPlus => "bodyweight plus added weight",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl TrackingMode {
+    pub fn is_timed(&self) -> bool {
+        matches!(self, TrackingMode::TimeOnly | TrackingMode::DistanceTime)
+    }
+}
+
+pub fn tracking_for(ex: &Exercises) -> TrackingMode {
+    match ex {
+        Exercises::TricepDips | Exercises::PullUp => TrackingMode::BodyweightPlus,
+        Exercises::PushUp => TrackingMode::RepsOnly,
+        Exercises::Plank | Exercises::WallSit | Exercises::DeadHang => TrackingMode::TimeOnly,
+        Exercises::FarmersWalk => TrackingMode::DistanceTime,
+        _ => TrackingMode::WeightReps,
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum WeightUnit {
+    #[default] Kg,
+    Lb,
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            WeightUnit::Kg => "kg",
+            WeightUnit::Lb => "lb",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl WeightUnit {
+    pub fn from_kg(&self, kg: f32) -> f32 {
+        match self {
+            WeightUnit::Kg => kg,
+            WeightUnit::Lb => kg * 2.20462,
+        }
+    }
+
+    pub fn to_kg(&self, weight: f32) -> f32 {
+        match self {
+            WeightUnit::Kg => weight,
+            WeightUnit::Lb => weight / 2.20462,
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
-pub struct WorkoutTemplate {
+pub struct PlateCount {
+    pub weight: f32,
+    // plates owned, a pair is needed to load one per side
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlateInventory {
+    pub unit: WeightUnit,
+    pub bar_weight: f32,
+    pub plates: Vec<PlateCount>,
+}
+
+impl Default for PlateInventory {
+    fn default() -> Self {
+        Self::standard(WeightUnit::Kg)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PlateLoad {
+    pub target: f32,
+    pub loaded: f32,
+    // heaviest first
+    pub per_side: Vec<f32>,
+}
+
+impl PlateInventory {
+    pub fn standard(unit: WeightUnit) -> Self {
+        let (bar_weight, weights): (f32, Vec<f32>) = match unit {
+            WeightUnit::Kg => (20.0, vec![25.0, 20.0, 15.0, 10.0, 5.0, 2.5, 1.25]),
+            WeightUnit::Lb => (45.0, vec![45.0, 35.0, 25.0, 10.0, 5.0, 2.5]),
+        };
+
+        Self {
+            unit,
+            bar_weight,
+            plates: weights.into_iter().map(|weight| PlateCount { weight, count: 4 }).collect(),
+        }
+    }
+
+    // closest weight the bar can actually be loaded to, ties go to the lighter load
+    pub fn load(&self, target: f32) -> PlateLoad {
+        let step = |weight: f32| (weight * 100.0).round() as usize;
+
+        let mut items: Vec<f32> = self.plates
+            .iter()
+            .filter(|plate| plate.weight > 0.0)
+            .flat_map(|plate| std::iter::repeat_n(plate.weight, plate.count as usize / 2))
+            .collect();
+        items.sort_by(|a, b| b.total_cmp(a));
+
+        // sums are counted in the largest unit every plate is a multiple of, and never
+        // past the target by more than one plate, which is as far as the closest load can be
+        let unit = items.iter().fold(0, |unit, weight| gcd(unit, step(*weight))).max(1);
+        let wanted = ((target - self.bar_weight) / 2.0).max(0.0) * 100.0 / unit as f32;
+        let heaviest = items.first().map_or(0, |weight| step(*weight) / unit);
+        let max = items.iter().map(|weight| step(*weight) / unit).sum::<usize>().min(wanted.ceil() as usize + heaviest);
+        // how each per-side total was first reached, heavier plates are tried first
+        let mut from: Vec<Option<(usize, f32)>> = vec![None; max + 1];
+        let mut reached = vec![false; max + 1];
+        reached[0] = true;
+
+        for weight in items.iter() {
+            let w = step(*weight) / unit;
+            for sum in (w..=max).rev() {
+                if reached[sum - w] && !reached[sum] {
+                    reached[sum] = true;
+                    from[sum] = Some((sum - w, *weight));
+                }
+            }
+        }
+
+        let best = (0..=max)
+            .filter(|sum| reached[*sum])
+            .min_by(|a, b| (*a as f32 - wanted).abs().total_cmp(&(*b as f32 - wanted).abs()))
+            .unwrap_or(0);
+
+        let mut per_side = Vec::new();
+        let mut sum = best;
+        while let Some((previous, weight)) = from[sum] {
+            per_side.push(weight);
+            sum = previous;
+        }
+        per_side.sort_by(|a, b| b.total_cmp(a));
+
+        PlateLoad {
+            target,
+            loaded: self.bar_weight + 2.0 * per_side.iter().sum::<f32>(),
+            per_side,
+        }
+    }
+
+    // same as `load` but for weights stored in kg, as the rest of the app does
+    pub fn load_kg(&self, kg: f32) -> PlateLoad {
+        self.load(self.unit.from_kg(kg))
+    }
+
+    pub fn round_kg(&self, kg: f32) -> f32 {
+        self.unit.to_kg(self.load_kg(kg).loaded)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub const DEFAULT_REST_SECS: u32 = 90;
+pub const WARMUP_REST_SECS: u32 = 60;
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WarmupStep {
+    // share of the first working set, `None` means the empty bar
+    pub percent: Option<f32>,
+    pub reps: u32,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WarmupScheme {
+    pub name: String,
+    pub steps: Vec<WarmupStep>,
+}
+
+impl WarmupScheme {
+    pub fn new(name: &str, steps: &[(Option<f32>, u32)]) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: steps.iter().map(|(percent, reps)| WarmupStep { percent: *percent, reps: *reps }).collect(),
+        }
+    }
+
+    // ramp up to `working` kg, snapped to what the plates allow; steps that would
+    // land on or above the working weight, or repeat the previous one, are dropped
+    pub fn sets(&self, working: f32, plates: &PlateInventory) -> Vec<SetDone> {
+        let bar = plates.unit.to_kg(plates.bar_weight);
+        let mut sets: Vec<SetDone> = Vec::new();
+
+        for step in self.steps.iter() {
+            let weight = match step.percent {
+                Some(percent) => plates.round_kg(working * percent / 100.0).max(bar),
+                None => bar,
+            };
+            if weight >= working - 0.01 || sets.last().is_some_and(|last| (last.weight - weight).abs() < 0.01 && step.percent.is_some()) {
+                continue;
+            }
+            sets.push(SetDone { weight, reps: step.reps, warmup: true, ..SetDone::default() });
+        }
+
+        sets
+    }
+}
+
+impl fmt::Display for WarmupScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self.steps
+            .iter()
+            .map(|step| match step.percent {
+                Some(percent) => format!("{}%x{}", percent, step.reps),
+                None => format!("barx{}", step.reps),
+            })
+            .collect();
+        write!(f, "{}: {}", self.name, steps.join(", "))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WarmupSettings {
+    pub enabled: bool,
+    pub scheme: usize,
+    pub schemes: Vec<WarmupScheme>,
+    pub exercises: Vec<Exercises>,
+}
+
+impl Default for WarmupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scheme: 0,
+            schemes: vec![
+                WarmupScheme::new("standard", &[(None, 10), (Some(40.0), 5), (Some(60.0), 3), (Some(80.0), 1)]),
+                WarmupScheme::new("short", &[(None, 8), (Some(50.0), 5), (Some(75.0), 2)]),
+                WarmupScheme::new("long", &[(None, 10), (Some(30.0), 8), (Some(50.0), 5), (Some(70.0), 3), (Some(85.0), 1), (Some(92.5), 1)]),
+            ],
+            exercises: vec![Exercises::Squat, Exercises::Deadlift, Exercises::BenchPress],
+        }
+    }
+}
+
+impl WarmupSettings {
+    pub fn current(&self) -> Option<&WarmupScheme> {
+        self.schemes.get(self.scheme).or(self.schemes.first())
+    }
+
+    pub fn sets_for(&self, exercise: &Exercises, working: f32, plates: &PlateInventory) -> Vec<SetDone> {
+        if !self.enabled || working <= 0.0 || !self.exercises.contains(exercise) {
+            return Vec::new();
+        }
+        self.current().map(|scheme| scheme.sets(working, plates)).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum GroupKind {
+    Superset,
+    GiantSet,
+    Circuit,
+}
+
+impl fmt::Display for GroupKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GroupKind::Superset => "superset",
+            GroupKind::GiantSet => "giant set",
+            GroupKind::Circuit => "circuit",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExerciseGroup {
+    pub id: u32,
+    pub kind: GroupKind,
+    pub rounds: u32,
+    pub rest_secs: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TargetLoad {
+    Absolute(f32),
+    PercentOneRm(f32),
+    Rpe(f32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Prescription {
+    pub sets: u32,
+    pub min_reps: u32,
+    pub max_reps: u32,
+    pub load: Option<TargetLoad>,
+    pub tempo: String,
+    pub rest_secs: u32,
+}
+
+impl Default for Prescription {
+    fn default() -> Self {
+        Self {
+            sets: 3,
+            min_reps: 8,
+            max_reps: 12,
+            load: None,
+            tempo: String::new(),
+            rest_secs: DEFAULT_REST_SECS,
+        }
+    }
+}
+
+impl Prescription {
+    pub fn short(&self) -> String {
+        let reps = if self.min_reps == self.max_reps { self.min_reps.to_string() } else { format!("{}-{}", self.min_reps, self.max_reps) };
+        match self.load {
+            Some(TargetLoad::Absolute(weight)) => format!("{}x{} @ {} kg", self.sets, reps, weight),
+            Some(TargetLoad::PercentOneRm(percent)) => format!("{}x{} @ {}% 1rm", self.sets, reps, percent),
+            Some(TargetLoad::Rpe(rpe)) => format!("{}x{} @ rpe {}", self.sets, reps, rpe),
+            None => format!("{}x{}", self.sets, reps),
+        }
+    }
+}
+
+impl fmt::Display for Prescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.short())?;
+        if !self.tempo.is_empty() {
+            write!(f, ", tempo {}", self.tempo)?;
+        }
+        write!(f, ", rest {} s", self.rest_secs)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ExerciseSlot {
+    pub group: Option<u32>,
+    pub prescription: Option<Prescription>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WorkoutTemplate {
     pub workout_name: String,
     pub exercises: Vec<Exercises>,
+    // one slot per exercise, same order as `exercises`
+    #[serde(default)]
+    pub slots: Vec<ExerciseSlot>,
+    #[serde(default)]
+    pub groups: Vec<ExerciseGroup>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     // pub rimary_muscles: Vec<Muscle>,
     // pub secondary_muscles: Vec<Muscle>,
 }
 
-impl WorkoutTemplate {
-    pub fn default() -> Self {
+impl WorkoutTemplate {
+    pub fn default() -> Self {
+        Self::new(
+            String::from("full body"),
+            vec![Exercises::BenchPress, Exercises::Deadlift, Exercises::Squat],
+            // primary_muscles: vec![Muscle::LowerChest, Muscle::Quads, Muscle::Hips, Muscle::Hamstrings, Muscle::Calfs],
+            // secondary_muscles: vec![Muscle::Forearms, Muscle::UpperChest, Muscle::LowerBack],
+        )
+    }
+
+    pub fn rest() -> Self {
+        Self::new(String::from("rest"), vec![])
+    }
+
+    pub fn new(name: String, exercises: Vec<Exercises>) -> Self {
+        Self {
+            workout_name: name,
+            slots: vec![ExerciseSlot::default(); exercises.len()],
+            exercises,
+            groups: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn legs() -> Self {
+        Self::new(
+            String::from("legs"),
+            vec![Exercises::Squat],
+            // primary_muscles: vec![Muscle::Quads, Muscle::Hips, Muscle::Hamstrings],
+            // secondary_muscles: vec![Muscle::Calfs],
+        )
+    }
+
+    pub fn slot(&self, index: usize) -> ExerciseSlot {
+        self.slots.get(index).cloned().unwrap_or_default()
+    }
+
+    pub fn prescription(&self, index: usize) -> Option<Prescription> {
+        self.slot(index).prescription
+    }
+
+    pub fn set_prescription(&mut self, index: usize, prescription: Option<Prescription>) {
+        self.slots.resize(self.exercises.len(), ExerciseSlot::default());
+        if let Some(slot) = self.slots.get_mut(index) {
+            slot.prescription = prescription;
+        }
+    }
+
+    pub fn group_of(&self, index: usize) -> Option<&ExerciseGroup> {
+        let id = self.slot(index).group?;
+        self.groups.iter().find(|group| group.id == id)
+    }
+
+    pub fn push_exercise(&mut self, exercise: Exercises) {
+        self.slots.resize(self.exercises.len(), ExerciseSlot::default());
+        self.exercises.push(exercise);
+        self.slots.push(ExerciseSlot::default());
+    }
+
+    pub fn remove_exercise(&mut self, index: usize) {
+        self.slots.resize(self.exercises.len(), ExerciseSlot::default());
+        self.exercises.remove(index);
+        self.slots.remove(index);
+        self.drop_empty_groups();
+    }
+
+    // swaps the exercise in place, its prescription and group stay with the slot
+    pub fn replace_exercise(&mut self, index: usize, exercise: Exercises) {
+        if let Some(current) = self.exercises.get_mut(index) {
+            *current = exercise;
+        }
+    }
+
+    pub fn move_exercise(&mut self, from: usize, to: usize) {
+        if from >= self.exercises.len() || to >= self.exercises.len() || from == to {
+            return;
+        }
+        self.slots.resize(self.exercises.len(), ExerciseSlot::default());
+        let exercise = self.exercises.remove(from);
+        let slot = self.slots.remove(from);
+        self.exercises.insert(to, exercise);
+        self.slots.insert(to, slot);
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !self.tags.contains(&tag) {
+            self.tags.push(tag);
+            self.tags.sort();
+        }
+    }
+
+    pub fn group_exercises(&mut self, indices: &[usize], kind: GroupKind) {
+        self.slots.resize(self.exercises.len(), ExerciseSlot::default());
+        let id = self.groups.iter().map(|group| group.id + 1).max().unwrap_or(0);
+
+        for index in indices {
+            if let Some(slot) = self.slots.get_mut(*index) {
+                slot.group = Some(id);
+            }
+        }
+
+        self.groups.push(ExerciseGroup {
+            id,
+            kind,
+            rounds: 3,
+            rest_secs: if kind == GroupKind::Circuit { 120 } else { DEFAULT_REST_SECS },
+        });
+        self.drop_empty_groups();
+    }
+
+    pub fn ungroup(&mut self, indices: &[usize]) {
+        for index in indices {
+            if let Some(slot) = self.slots.get_mut(*index) {
+                slot.group = None;
+            }
+        }
+        self.drop_empty_groups();
+    }
+
+    fn drop_empty_groups(&mut self) {
+        let used: Vec<u32> = self.slots.iter().filter_map(|slot| slot.group).collect();
+        self.groups.retain(|group| used.iter().filter(|id| **id == group.id).count() > 1);
+        let kept: Vec<u32> = self.groups.iter().map(|group| group.id).collect();
+        for slot in self.slots.iter_mut() {
+            if slot.group.is_some_and(|id| !kept.contains(&id)) {
+                slot.group = None;
+            }
+        }
+    }
+
+    // A1/A2 style labels, groups are lettered in order of their first exercise
+    pub fn labels(&self) -> Vec<String> {
+        let mut order: Vec<u32> = Vec::new();
+        let mut members: HashMap<u32, u32> = HashMap::new();
+
+        (0..self.exercises.len())
+            .map(|index| match self.slot(index).group {
+                Some(id) => {
+                    if !order.contains(&id) {
+                        order.push(id);
+                    }
+                    let letter = (b'A' + order.iter().position(|g| *g == id).unwrap() as u8 % 26) as char;
+                    let member = members.entry(id).or_insert(0);
+                    *member += 1;
+                    format!("{}{}", letter, member)
+                }
+                None => String::new(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WorkoutDone {
+    pub template: WorkoutTemplate,
+    pub date: NaiveDate,
+    pub volume: u32,
+    pub length: u32,
+    pub prs: u32,
+    pub exercises: Vec<ExerciseDone>,
+    // how hard the whole session felt, 1 to 10
+    #[serde(default)]
+    pub rpe: Option<f32>,
+    #[serde(default)]
+    pub notes: String,
+    // stars from 1 to 5
+    #[serde(default)]
+    pub rating: Option<u8>,
+    // how energetic it felt going in, 1 to 5
+    #[serde(default)]
+    pub energy: Option<u8>,
+}
+
+impl WorkoutDone {
+    pub fn default() -> Self {
+        Self {
+            template: WorkoutTemplate::default(),
+            date: NaiveDate::default(),
+            volume: 0,
+            length: 0,
+            prs: 0,
+            exercises: Vec::new(),
+            rpe: None,
+            notes: String::new(),
+            rating: None,
+            energy: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryFilter {
+    pub range: DateRange,
+    pub template: Option<String>,
+    pub exercise: Option<Exercises>,
+    pub muscle: Option<Muscle>,
+    // matched against the workout name, exercise names and notes
+    pub query: String,
+}
+
+impl HistoryFilter {
+    pub fn default() -> Self {
+        Self {
+            range: DateRange::all(),
+            template: None,
+            exercise: None,
+            muscle: None,
+            query: String::new(),
+        }
+    }
+
+    pub fn matches(&self, workout: &WorkoutDone) -> bool {
+        let query = self.query.trim().to_lowercase();
+        let text_match = query.is_empty()
+            || workout.template.workout_name.to_lowercase().contains(&query)
+            || workout.notes.to_lowercase().contains(&query)
+            || workout.exercises.iter().any(|done| {
+                done.exercise.to_string().to_lowercase().contains(&query)
+                    || done.notes.to_lowercase().contains(&query)
+                    || done.sets.iter().any(|set| set.note.to_lowercase().contains(&query))
+            });
+
+        self.range.contains(workout.date)
+            && self.template.as_ref().is_none_or(|name| &workout.template.workout_name == name)
+            && self.exercise.as_ref().is_none_or(|exercise| workout.exercises.iter().any(|done| &done.exercise == exercise))
+            && self.muscle.as_ref().is_none_or(|muscle| workout.exercises.iter().any(|done| {
+                let (primary, secondary) = muscles_for(&done.exercise);
+                primary.contains(muscle) || secondary.contains(muscle)
+            }))
+            && text_match
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteHit {
+    // index into `workouts`
+    pub index: usize,
+    pub date: NaiveDate,
+    pub workout: String,
+    // `None` for a note on the whole session
+    pub exercise: Option<Exercises>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ExerciseDone {
+    pub exercise: Exercises,
+    pub sets: Vec<SetDone>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl ExerciseDone {
+    pub fn working_sets(&self) -> impl Iterator<Item = &SetDone> {
+        self.sets.iter().filter(|set| !set.warmup)
+    }
+
+    // a left and right pair of a unilateral exercise is one set
+    pub fn set_count(&self) -> usize {
+        self.working_sets().filter(|set| set.side != Some(Side::Right)).count()
+    }
+
+    // timed and reps-only sets carry no load and add nothing
+    pub fn volume(&self, body_weight: f32) -> f32 {
+        match tracking_for(&self.exercise) {
+            TrackingMode::WeightReps => self.working_sets().map(|set| set.weight * set.reps as f32).sum(),
+            TrackingMode::BodyweightPlus => self.working_sets().map(|set| (body_weight + set.weight) * set.reps as f32).sum(),
+            _ => 0.0,
+        }
+    }
+
+    pub fn best(&self, body_weight: f32) -> f32 {
+        let mode = tracking_for(&self.exercise);
+        self.working_sets().map(|set| set.score(mode, body_weight)).fold(0.0, f32::max)
+    }
+
+    pub fn side(&self, side: Side) -> ExerciseDone {
+        ExerciseDone {
+            exercise: self.exercise.clone(),
+            sets: self.sets.iter().filter(|set| set.side == Some(side)).cloned().collect(),
+            notes: self.notes.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct SetDone {
+    pub weight: f32,
+    pub reps: u32,
+    pub rpe: Option<f32>,
+    // warm-ups never count toward volume, progression or prs
+    #[serde(default)]
+    pub warmup: bool,
+    #[serde(default)]
+    pub duration_secs: u32,
+    #[serde(default)]
+    pub distance_m: f32,
+    // only set for unilateral exercises
+    #[serde(default)]
+    pub side: Option<Side>,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl SetDone {
+    pub fn label(&self, mode: TrackingMode) -> String {
+        match mode {
+            TrackingMode::WeightReps => format!("{} kg x {}", self.weight, self.reps),
+            TrackingMode::RepsOnly => format!("{} reps", self.reps),
+            TrackingMode::TimeOnly => format!("{}:{:02}", self.duration_secs / 60, self.duration_secs % 60),
+            TrackingMode::DistanceTime => format!("{} m in {}:{:02}", self.distance_m, self.duration_secs / 60, self.duration_secs % 60),
+            TrackingMode::BodyweightPlus if self.weight > 0.0 => format!("bw +{} kg x {}", self.weight, self.reps),
+            TrackingMode::BodyweightPlus => format!("bw x {}", self.reps),
+        }
+    }
+
+    // what a pr is measured by for each tracking mode. bodyweight sets compare an epley
+    // estimate of the total load, so extra reps can beat a little added weight
+    pub fn score(&self, mode: TrackingMode, body_weight: f32) -> f32 {
+        match mode {
+            TrackingMode::WeightReps => self.weight,
+            TrackingMode::RepsOnly => self.reps as f32,
+            TrackingMode::TimeOnly => self.duration_secs as f32,
+            TrackingMode::DistanceTime => self.distance_m,
+            TrackingMode::BodyweightPlus => (body_weight + self.weight) * (1.0 + self.reps as f32 / 30.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionSet {
+    pub set: SetDone,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionExercise {
+    pub exercise: Exercises,
+    pub suggestion: Option<SetSuggestion>,
+    pub sets: Vec<SessionSet>,
+    pub notes: String,
+}
+
+impl SessionExercise {
+    // unilateral exercises log every set once per side, left first
+    pub fn sides(exercise: &Exercises, set: &SetDone) -> Vec<SetDone> {
+        if is_unilateral(exercise) {
+            [Side::Left, Side::Right].into_iter().map(|side| SetDone { side: Some(side), ..set.clone() }).collect()
+        } else {
+            vec![SetDone { side: None, ..set.clone() }]
+        }
+    }
+
+    // set indices per round, a left set and the right set after it are one round
+    pub fn rounds(&self) -> Vec<Vec<usize>> {
+        let mut rounds: Vec<Vec<usize>> = Vec::new();
+        for (index, row) in self.sets.iter().enumerate() {
+            let pairs = index > 0 && self.sets[index - 1].set.side == Some(Side::Left) && row.set.side == Some(Side::Right);
+            match rounds.last_mut() {
+                Some(round) if pairs => round.push(index),
+                _ => rounds.push(vec![index]),
+            }
+        }
+        rounds
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionStep {
+    pub exercise: usize,
+    pub set: usize,
+    pub rest_after: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetTimer {
+    pub exercise: usize,
+    pub set: usize,
+    pub started_at: DateTime<Local>,
+    // counts down from this many seconds, counts up when `None`
+    pub countdown: Option<u32>,
+}
+
+impl SetTimer {
+    pub fn elapsed_secs(&self) -> u32 {
+        (Local::now() - self.started_at).num_seconds().max(0) as u32
+    }
+
+    pub fn display_secs(&self) -> u32 {
+        match self.countdown {
+            Some(target) => target.saturating_sub(self.elapsed_secs()),
+            None => self.elapsed_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkoutSession {
+    pub template: WorkoutTemplate,
+    pub date: NaiveDate,
+    pub planned_index: Option<usize>,
+    pub started_at: DateTime<Local>,
+    pub exercises: Vec<SessionExercise>,
+    pub rest_until: Option<DateTime<Local>>,
+    pub timer: Option<SetTimer>,
+    pub rpe: Option<f32>,
+    pub slot: Option<ProgramSlot>,
+    pub notes: String,
+    pub rating: Option<u8>,
+    pub energy: Option<u8>,
+}
+
+impl WorkoutSession {
+    pub fn new(template: WorkoutTemplate, date: NaiveDate, planned_index: Option<usize>, slot: Option<&ProgramSlot>, data: &AllWorkoutData) -> Self {
+        let exercises = template.exercises
+            .iter()
+            .enumerate()
+            .map(|(index, exercise)| Self::plan_exercise(&template, index, exercise, slot, data))
+            .collect();
+
+        Self {
+            template,
+            date,
+            planned_index,
+            started_at: Local::now(),
+            exercises,
+            rest_until: None,
+            timer: None,
+            rpe: None,
+            slot: slot.cloned(),
+            notes: String::new(),
+            rating: None,
+            energy: None,
+        }
+    }
+
+    fn plan_exercise(template: &WorkoutTemplate, index: usize, exercise: &Exercises, slot: Option<&ProgramSlot>, data: &AllWorkoutData) -> SessionExercise {
+        let prescription = template.prescription(index);
+        let suggestion = data.suggest_prescribed(exercise, prescription.as_ref(), slot);
+        let mut set = match (&suggestion, &prescription) {
+            (Some(suggestion), _) => SetDone { weight: suggestion.weight, reps: suggestion.reps, rpe: None, ..SetDone::default() },
+            (None, Some(prescription)) => SetDone { weight: 0.0, reps: prescription.min_reps, rpe: None, ..SetDone::default() },
+            (None, None) => SetDone { weight: 0.0, reps: data.progression.min_reps, rpe: None, ..SetDone::default() },
+        };
+        let mode = tracking_for(exercise);
+        if mode.is_timed() {
+            match data.last_performance(exercise).and_then(|last| last.working_sets().next()) {
+                Some(last) => set = last.clone(),
+                None => set.duration_secs = 30,
+            }
+            set.rpe = None;
+            set.note.clear();
+        }
+        let count = match (template.group_of(index), &suggestion, &prescription) {
+            (Some(group), _, _) => group.rounds.max(1),
+            (None, Some(suggestion), _) => suggestion.sets.max(1),
+            (None, None, Some(prescription)) => prescription.sets.max(1),
+            (None, None, None) => 1,
+        };
+        let warmups = match template.group_of(index) {
+            Some(_) => Vec::new(),
+            None => data.warmups.sets_for(exercise, set.weight, &data.plates),
+        };
+        let sets = warmups
+            .into_iter()
+            .chain((0..count).flat_map(|_| SessionExercise::sides(exercise, &set)))
+            .map(|set| SessionSet { set, done: false })
+            .collect();
+
+        SessionExercise {
+            exercise: exercise.clone(),
+            suggestion,
+            sets,
+            notes: String::new(),
+        }
+    }
+
+    // replaces an exercise nothing was logged for yet, sets are suggested again for the new one
+    pub fn swap_exercise(&mut self, index: usize, exercise: Exercises, data: &AllWorkoutData) {
+        if self.exercises.get(index).is_none_or(|current| current.sets.iter().any(|set| set.done)) {
+            return;
+        }
+        self.template.replace_exercise(index, exercise.clone());
+        self.exercises[index] = Self::plan_exercise(&self.template, index, &exercise, self.slot.as_ref(), data);
+        if self.timer.as_ref().is_some_and(|timer| timer.exercise == index) {
+            self.timer = None;
+        }
+    }
+
+    // warm-ups ramp to the first working set, so they follow its weight until one of them is done
+    pub fn refresh_warmups(&mut self, index: usize, data: &AllWorkoutData) {
+        if self.template.group_of(index).is_some() {
+            return;
+        }
+        let Some(session_exercise) = self.exercises.get_mut(index) else { return; };
+        if session_exercise.sets.iter().any(|row| row.set.warmup && row.done) {
+            return;
+        }
+        let Some(working) = session_exercise.sets.iter().find(|row| !row.set.warmup).map(|row| row.set.weight) else { return; };
+
+        let before = session_exercise.sets.iter().filter(|row| row.set.warmup).count();
+        let warmups = data.warmups.sets_for(&session_exercise.exercise, working, &data.plates);
+        let shift = warmups.len() as isize - before as isize;
+        session_exercise.sets.retain(|row| !row.set.warmup);
+        session_exercise.sets.splice(0..0, warmups.into_iter().map(|set| SessionSet { set, done: false }));
+
+        if let Some(timer) = self.timer.as_mut().filter(|timer| timer.exercise == index) {
+            timer.set = timer.set.saturating_add_signed(shift);
+        }
+    }
+
+    pub fn start_timer(&mut self, exercise: usize, set: usize, countdown: bool) {
+        let target = self.exercises.get(exercise).and_then(|e| e.sets.get(set)).map_or(0, |s| s.set.duration_secs);
+        self.timer = Some(SetTimer {
+            exercise,
+            set,
+            started_at: Local::now(),
+            countdown: (countdown && target > 0).then_some(target),
+        });
+    }
+
+    // records the timed duration on the set and completes it
+    pub fn stop_timer(&mut self) {
+        let Some(timer) = self.timer.take() else { return; };
+        let secs = timer.countdown.map_or(timer.elapsed_secs(), |target| timer.elapsed_secs().min(target));
+        if let Some(session_set) = self.exercises.get_mut(timer.exercise).and_then(|e| e.sets.get_mut(timer.set)) {
+            session_set.set.duration_secs = secs;
+        }
+        self.complete_set(timer.exercise, timer.set);
+    }
+
+    // finishes a countdown once it runs out
+    pub fn tick(&mut self) {
+        if self.timer.as_ref().is_some_and(|timer| timer.countdown.is_some() && timer.display_secs() == 0) {
+            self.stop_timer();
+        }
+    }
+
+    // grouped exercises alternate round by round, rest only follows the last member of a round
+    pub fn steps(&self) -> Vec<SessionStep> {
+        let mut steps = Vec::new();
+        let mut emitted: Vec<u32> = Vec::new();
+
+        for (index, session_exercise) in self.exercises.iter().enumerate() {
+            match self.template.group_of(index) {
+                Some(group) => {
+                    if emitted.contains(&group.id) {
+                        continue;
+                    }
+                    emitted.push(group.id);
+
+                    let members: Vec<(usize, Vec<Vec<usize>>)> = (0..self.exercises.len())
+                        .filter(|i| self.template.slot(*i).group == Some(group.id))
+                        .map(|i| (i, self.exercises[i].rounds()))
+                        .collect();
+                    let rounds = members.iter().map(|(_, sets)| sets.len()).max().unwrap_or(0);
+
+                    for round in 0..rounds {
+                        let in_round: Vec<(usize, usize)> = members
+                            .iter()
+                            .filter_map(|(member, sets)| sets.get(round).map(|sets| (*member, sets)))
+                            .flat_map(|(member, sets)| sets.iter().map(move |set| (member, *set)))
+                            .collect();
+                        for (position, (member, set)) in in_round.iter().enumerate() {
+                            let last = position + 1 == in_round.len();
+                            steps.push(SessionStep { exercise: *member, set: *set, rest_after: if last { group.rest_secs } else { 0 } });
+                        }
+                    }
+                }
+                None => {
+                    let rest = self.template.prescription(index).map_or(DEFAULT_REST_SECS, |prescription| prescription.rest_secs);
+                    for (set, session_set) in session_exercise.sets.iter().enumerate() {
+                        let rest_after = if session_set.set.side == Some(Side::Left) {
+                            0
+                        } else if session_set.set.warmup {
+                            WARMUP_REST_SECS.min(rest)
+                        } else {
+                            rest
+                        };
+                        steps.push(SessionStep { exercise: index, set, rest_after });
+                    }
+                }
+            }
+        }
+
+        steps
+    }
+
+    pub fn current_step(&self) -> Option<SessionStep> {
+        self.steps().into_iter().find(|step| !self.exercises[step.exercise].sets[step.set].done)
+    }
+
+    pub fn complete_set(&mut self, exercise: usize, set: usize) {
+        let Some(session_set) = self.exercises.get_mut(exercise).and_then(|e| e.sets.get_mut(set)) else { return; };
+        session_set.done = true;
+
+        let rest = self.steps().into_iter().find(|step| step.exercise == exercise && step.set == set).map_or(0, |step| step.rest_after);
+        self.rest_until = if rest > 0 { Some(Local::now() + chrono::Duration::seconds(rest as i64)) } else { None };
+    }
+
+    pub fn rest_remaining(&self) -> i64 {
+        self.rest_until.map_or(0, |until| (until - Local::now()).num_seconds().max(0))
+    }
+
+    pub fn elapsed_secs(&self) -> i64 {
+        (Local::now() - self.started_at).num_seconds().max(0)
+    }
+
+    pub fn finish(&self, body_weight: f32) -> WorkoutDone {
+        let exercises: Vec<ExerciseDone> = self.exercises
+            .iter()
+            .map(|session_exercise| ExerciseDone {
+                exercise: session_exercise.exercise.clone(),
+                sets: session_exercise.sets.iter().filter(|s| s.done).map(|s| s.set.clone()).collect(),
+                notes: session_exercise.notes.trim().to_string(),
+            })
+            .filter(|done| !done.sets.is_empty())
+            .collect();
+
+        WorkoutDone {
+            template: self.template.clone(),
+            date: self.date,
+            volume: exercises.iter().map(|done| done.volume(body_weight)).sum::<f32>() as u32,
+            length: (self.elapsed_secs() / 60) as u32,
+            prs: 0,
+            exercises,
+            rpe: self.rpe,
+            notes: self.notes.trim().to_string(),
+            rating: self.rating,
+            energy: self.energy,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum CardioKind {
+    #[default] Running,
+    Cycling,
+    Rowing,
+    Swimming,
+    Walking,
+}
+
+impl fmt::Display for CardioKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CardioKind::Running => "running",
+            CardioKind::Cycling => "cycling",
+            CardioKind::Rowing => "rowing",
+            CardioKind::Swimming => "swimming",
+            CardioKind::Walking => "walking",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl CardioKind {
+    // compendium of physical activities values, picked by speed where it matters
+    pub fn met(&self, speed_kmh: Option<f32>) -> f32 {
+        match (self, speed_kmh) {
+            (CardioKind::Running, Some(speed)) if speed < 8.0 => 8.3,
+            (CardioKind::Running, Some(speed)) if speed < 9.7 => 9.8,
+            (CardioKind::Running, Some(speed)) if speed < 11.3 => 11.0,
+            (CardioKind::Running, Some(speed)) if speed < 12.9 => 11.8,
+            (CardioKind::Running, Some(_)) => 12.8,
+            (CardioKind::Running, None) => 9.8,
+            (CardioKind::Cycling, Some(speed)) if speed < 16.0 => 4.0,
+            (CardioKind::Cycling, Some(speed)) if speed < 19.0 => 6.8,
+            (CardioKind::Cycling, Some(speed)) if speed < 22.0 => 8.0,
+            (CardioKind::Cycling, Some(speed)) if speed < 25.0 => 10.0,
+            (CardioKind::Cycling, Some(_)) => 12.0,
+            (CardioKind::Cycling, None) => 6.8,
+            (CardioKind::Rowing, _) => 7.0,
+            (CardioKind::Swimming, _) => 8.0,
+            (CardioKind::Walking, Some(speed)) if speed < 4.0 => 2.8,
+            (CardioKind::Walking, Some(speed)) if speed < 5.5 => 3.5,
+            (CardioKind::Walking, _) => 5.0,
+        }
+    }
+
+    // distance the pace is quoted over, in km
+    pub fn pace_distance(&self) -> Option<f32> {
+        match self {
+            CardioKind::Running | CardioKind::Walking => Some(1.0),
+            CardioKind::Rowing => Some(0.5),
+            CardioKind::Swimming => Some(0.1),
+            CardioKind::Cycling => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct CardioPlan {
+    pub kind: CardioKind,
+    pub duration_mins: u32,
+    pub distance_km: Option<f32>,
+}
+
+impl fmt::Display for CardioPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} min {}", self.duration_mins, self.kind)?;
+        if let Some(distance) = self.distance_km {
+            write!(f, ", {:.1} km", distance)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct CardioSession {
+    pub kind: CardioKind,
+    pub date: NaiveDate,
+    pub duration_secs: u32,
+    pub distance_km: f32,
+    pub elevation_m: f32,
+    pub avg_hr: Option<u32>,
+    pub max_hr: Option<u32>,
+    pub calories: u32,
+    // filled in by file imports, zero when the session was typed in
+    #[serde(default)]
+    pub moving_secs: u32,
+    // seconds for each full kilometre
+    #[serde(default)]
+    pub splits: Vec<u32>,
+    // (latitude, longitude) pairs
+    #[serde(default)]
+    pub route: Vec<(f64, f64)>,
+}
+
+impl CardioSession {
+    pub fn new(kind: CardioKind, date: NaiveDate) -> Self {
+        Self { kind, date, duration_secs: 30 * 60, ..Self::default() }
+    }
+
+    pub fn from_plan(plan: &CardioPlan, date: NaiveDate) -> Self {
+        Self {
+            kind: plan.kind,
+            date,
+            duration_secs: plan.duration_mins * 60,
+            distance_km: plan.distance_km.unwrap_or(0.0),
+            ..Self::default()
+        }
+    }
+
+    // moving time when known, pauses at lights shouldn't slow the pace down
+    pub fn active_secs(&self) -> u32 {
+        if self.moving_secs > 0 { self.moving_secs } else { self.duration_secs }
+    }
+
+    pub fn speed_kmh(&self) -> Option<f32> {
+        (self.distance_km > 0.0 && self.active_secs() > 0).then(|| self.distance_km / (self.active_secs() as f32 / 3600.0))
+    }
+
+    // seconds per pace distance of the activity (km, 500 m or 100 m)
+    pub fn pace_secs(&self) -> Option<f32> {
+        let per = self.kind.pace_distance()?;
+        (self.distance_km > 0.0).then(|| self.active_secs() as f32 / (self.distance_km / per))
+    }
+
+    pub fn pace_label(&self) -> String {
+        match (self.kind.pace_distance(), self.pace_secs(), self.speed_kmh()) {
+            (Some(per), Some(pace), _) => {
+                let unit = if per >= 1.0 { String::from("km") } else { format!("{} m", (per * 1000.0) as u32) };
+                format!("{}:{:02} /{}", pace as u32 / 60, pace as u32 % 60, unit)
+            }
+            (None, _, Some(speed)) => format!("{:.1} km/h", speed),
+            _ => String::from("-"),
+        }
+    }
+
+    pub fn estimate_calories(&self, body_weight: f32) -> u32 {
+        (self.kind.met(self.speed_kmh()) * body_weight * self.active_secs() as f32 / 3600.0).round() as u32
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WorkoutPlanned {
+    pub template: WorkoutTemplate,
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub program: Option<ProgramSlot>,
+    #[serde(default)]
+    pub schedule: Option<u32>,
+    #[serde(default)]
+    pub cardio: Option<CardioPlan>,
+    // a missed workout the user chose to let go, kept for the record
+    #[serde(default)]
+    pub skipped: bool,
+    // a planned day off, nothing is due and training on it is fine
+    #[serde(default)]
+    pub rest: bool,
+}
+
+impl WorkoutPlanned {
+    pub fn cardio(plan: CardioPlan, date: NaiveDate) -> Self {
+        Self {
+            template: WorkoutTemplate::new(plan.kind.to_string(), vec![]),
+            cardio: Some(plan),
+            ..Self::new(WorkoutTemplate::rest(), date)
+        }
+    }
+
+    pub fn new(template: WorkoutTemplate, date: NaiveDate) -> Self {
+        Self {
+            template,
+            date,
+            completed: false,
+            program: None,
+            schedule: None,
+            cardio: None,
+            skipped: false,
+            rest: false,
+        }
+    }
+
+    pub fn rest(date: NaiveDate) -> Self {
+        Self {
+            template: WorkoutTemplate::rest(),
+            date,
+            completed: false,
+            program: None,
+            schedule: None,
+            cardio: None,
+            skipped: false,
+            rest: true,
+        }
+    }
+
+    pub fn leg_day(date: NaiveDate) -> Self {
+        Self {
+            template: WorkoutTemplate::legs(),
+            date,
+            completed: false,
+            program: None,
+            schedule: None,
+            cardio: None,
+            skipped: false,
+            rest: false,
+        }
+    }
+
+    pub fn is_rest(&self) -> bool {
+        self.rest
+    }
+
+    pub fn is_missed(&self, today: NaiveDate) -> bool {
+        self.date < today && !self.completed && !self.skipped && !self.is_rest()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum MissedAction {
+    Skip,
+    NextFreeDay,
+    ShiftSchedule,
+    ShiftToToday,
+}
+
+impl fmt::Display for MissedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MissedAction::Skip => "skip it",
+            MissedAction::NextFreeDay => "move to next free day",
+            MissedAction::ShiftSchedule => "shift remaining plan by a day",
+            MissedAction::ShiftToToday => "shift remaining plan to today",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ProgramSlot {
+    pub name: String,
+    pub week: u32,
+    pub intensity: f32,
+    pub volume: f32,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ProgramDay {
+    pub day: u8,
+    pub template: String,
+}
+
+pub const DELOAD_INTENSITY: f32 = 0.6;
+pub const DELOAD_VOLUME: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ProgramWeek {
+    pub days: Vec<ProgramDay>,
+    pub intensity: f32,
+    pub volume: f32,
+    pub deload: bool,
+}
+
+impl Default for ProgramWeek {
+    fn default() -> Self {
         Self {
-            workout_name: String::from("full body"),
-            exercises: vec![Exercises::BenchPress, Exercises::Deadlift, Exercises::Squat],
-            // primary_muscles: vec![Muscle::LowerChest, Muscle::Quads, Muscle::Hips, Muscle::Hamstrings, Muscle::Calfs],
-            // secondary_muscles: vec![Muscle::Forearms, Muscle::UpperChest, Muscle::LowerBack],
+            days: Vec::new(),
+            intensity: 1.0,
+            volume: 1.0,
+            deload: false,
         }
     }
+}
 
-    pub fn rest() -> Self {
-        Self {
-            workout_name: String::from("rest"),
-            exercises: vec![],
+impl ProgramWeek {
+    pub fn set_deload(&mut self, deload: bool) {
+        self.deload = deload;
+        if deload {
+            self.intensity = DELOAD_INTENSITY;
+            self.volume = DELOAD_VOLUME;
+        } else {
+            self.intensity = 1.0;
+            self.volume = 1.0;
         }
     }
+}
 
-    // pub fn new(name: String, exercises: Vec<Exercises>) -> Self {
-    //     Self {
-    //         workout_name: name,
-    //         exercises: exercises,
-    //     }
-    // }
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Program {
+    pub name: String,
+    pub weeks: Vec<ProgramWeek>,
+}
 
-    pub fn legs() -> Self {
+impl Program {
+    pub fn new(name: String, weeks: usize, days: Vec<ProgramDay>) -> Self {
         Self {
-            workout_name: String::from("legs"),
-            exercises: vec![Exercises::Squat],
-            // primary_muscles: vec![Muscle::Quads, Muscle::Hips, Muscle::Hamstrings],
-            // secondary_muscles: vec![Muscle::Calfs],
+            name,
+            weeks: (0..weeks).map(|_| ProgramWeek { days: days.clone(), ..ProgramWeek::default() }).collect(),
         }
     }
+
+    pub fn resize(&mut self, weeks: usize) {
+        let days = self.weeks.first().map(|week| week.days.clone()).unwrap_or_default();
+        self.weeks.resize_with(weeks, || ProgramWeek { days: days.clone(), ..ProgramWeek::default() });
+    }
+
+    pub fn templates(&self) -> Vec<String> {
+        let mut templates: Vec<String> = self.weeks.iter().flat_map(|week| week.days.iter().map(|day| day.template.clone())).collect();
+        templates.sort();
+        templates.dedup();
+        templates
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
-pub struct WorkoutDone {
-    pub template: WorkoutTemplate,
-    pub date: String,
-    pub volume: u32,
-    pub length: u32,
-    pub prs: u32,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RecurrenceRule {
+    EveryNDays(u32),
+    Weekdays(Vec<u8>),
+    EveryOtherWeek(Vec<u8>),
 }
 
-impl WorkoutDone {
-    pub fn default() -> Self {
-        Self {
-            template: WorkoutTemplate::default(),
-            date: String::new(),
-            volume: 0,
-            length: 0,
-            prs: 0,
-        }
+impl Default for RecurrenceRule {
+    fn default() -> Self {
+        RecurrenceRule::Weekdays(vec![0])
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
-pub struct WorkoutPlanned {
-    pub template: WorkoutTemplate,
-    pub date: NaiveDate,
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weekdays = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+        let days = |days: &Vec<u8>| days.iter().map(|day| weekdays[*day as usize % 7]).collect::<Vec<&str>>().join(", ");
+        match self {
+            RecurrenceRule::EveryNDays(n) => write!(f, "every {} days", n),
+            RecurrenceRule::Weekdays(list) => write!(f, "every {}", days(list)),
+            RecurrenceRule::EveryOtherWeek(list) => write!(f, "every other week on {}", days(list)),
+        }
+    }
 }
 
-impl WorkoutPlanned {
-    pub fn new(template: WorkoutTemplate, date: NaiveDate) -> Self {
-        Self {
-            template,
-            date,
+impl RecurrenceRule {
+    // weekdays are numbered from monday = 0, every other week counts from the week of `start`
+    pub fn matches(&self, start: NaiveDate, date: NaiveDate) -> bool {
+        if date < start {
+            return false;
         }
-    }
 
-    pub fn rest(date: NaiveDate) -> Self {
-        Self {
-            template: WorkoutTemplate::rest(),
-            date,
+        let weekday = date.weekday().num_days_from_monday() as u8;
+        match self {
+            RecurrenceRule::EveryNDays(n) => (date - start).num_days() % (*n).max(1) as i64 == 0,
+            RecurrenceRule::Weekdays(days) => days.contains(&weekday),
+            RecurrenceRule::EveryOtherWeek(days) => {
+                let week_start = |d: NaiveDate| d - chrono::Duration::days(d.weekday().num_days_from_monday() as i64);
+                let weeks = (week_start(date) - week_start(start)).num_days() / 7;
+                weeks % 2 == 0 && days.contains(&weekday)
+            }
         }
     }
+}
 
-    pub fn leg_day(date: NaiveDate) -> Self {
-        Self {
-            template: WorkoutTemplate::legs(),
-            date,
-        }
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct RecurringSchedule {
+    pub id: u32,
+    pub template: String,
+    pub rule: RecurrenceRule,
+    pub start: NaiveDate,
+    pub end: Option<NaiveDate>,
+    pub exceptions: Vec<NaiveDate>,
+}
+
+impl RecurringSchedule {
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        self.end.is_none_or(|end| date <= end)
+            && !self.exceptions.contains(&date)
+            && self.rule.matches(self.start, date)
     }
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct WorkoutPlannedData {
     pub workouts: HashMap<NaiveDate, Vec<WorkoutPlanned>>,
+    pub schedules: Vec<RecurringSchedule>,
 }
 
 // #[derive(Serialize, Deserialize, Default, Debug, Clone, Allocator)]
@@ -385,6 +2439,7 @@ impl WorkoutPlannedData {
         Self {
             workouts: HashMap::from([
             (NaiveDate::from_ymd(2025, 10,29), vec![WorkoutPlanned::new(WorkoutTemplate::default(), NaiveDate::from_ymd(2025, 10, 29))])]),
+            schedules: Vec::new(),
         }
     }
 
@@ -400,6 +2455,259 @@ impl WorkoutPlannedData {
         self.workouts.entry(date).or_default().push(WorkoutPlanned::rest(date));
     }
 
+    // week one is the monday to sunday week holding `start`, days before `start` are skipped.
+    // applying again replaces the program's workouts that are not done yet
+    pub fn apply_program(&mut self, program: &Program, templates: &HashMap<String, WorkoutTemplate>, start: NaiveDate) -> Result<usize, String> {
+        if let Some(missing) = program.templates().iter().find(|name| !templates.contains_key(*name)) {
+            return Err(format!("template \"{}\" does not exist", missing));
+        }
+
+        for (_, workouts) in self.workouts.iter_mut().filter(|(date, _)| **date >= start) {
+            workouts.retain(|workout| workout.completed || workout.program.as_ref().is_none_or(|slot| slot.name != program.name));
+        }
+
+        let monday = start - chrono::Duration::days(start.weekday().num_days_from_monday() as i64);
+        let mut added = 0;
+        for (week_index, week) in program.weeks.iter().enumerate() {
+            for day in &week.days {
+                let date = monday + chrono::Duration::days(week_index as i64 * 7 + day.day as i64 % 7);
+                if date < start {
+                    continue;
+                }
+                let mut planned = WorkoutPlanned::new(templates[&day.template].clone(), date);
+                planned.program = Some(ProgramSlot {
+                    name: program.name.clone(),
+                    week: week_index as u32 + 1,
+                    intensity: week.intensity,
+                    volume: week.volume,
+                });
+
+                let workouts = self.workouts.entry(date).or_default();
+                workouts.retain(|workout| !workout.is_rest());
+                workouts.push(planned);
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+
+    pub fn add_schedule(&mut self, template: String, rule: RecurrenceRule, start: NaiveDate) -> u32 {
+        let id = self.schedules.iter().map(|schedule| schedule.id + 1).max().unwrap_or(0);
+        self.schedules.push(RecurringSchedule {
+            id,
+            template,
+            rule,
+            start,
+            end: None,
+            exceptions: Vec::new(),
+        });
+        id
+    }
+
+    // fills the rolling window [from, to] with occurrences that are not planned yet
+    pub fn generate_occurrences(&mut self, templates: &HashMap<String, WorkoutTemplate>, from: NaiveDate, to: NaiveDate) {
+        for schedule in &self.schedules {
+            let Some(template) = templates.get(&schedule.template) else { continue; };
+
+            let mut date = from.max(schedule.start);
+            while date <= to {
+                if schedule.occurs_on(date) {
+                    let workouts = self.workouts.entry(date).or_default();
+                    if !workouts.iter().any(|workout| workout.schedule == Some(schedule.id)) {
+                        workouts.retain(|workout| !workout.is_rest());
+                        let mut planned = WorkoutPlanned::new(template.clone(), date);
+                        planned.schedule = Some(schedule.id);
+                        workouts.push(planned);
+                    }
+                }
+                date += chrono::Duration::days(1);
+            }
+        }
+    }
+
+    pub fn remove_occurrence(&mut self, date: NaiveDate, index: usize) {
+        let Some(workouts) = self.workouts.get_mut(&date) else { return; };
+        if index >= workouts.len() {
+            return;
+        }
+
+        let removed = workouts.remove(index);
+        if let Some(schedule) = self.schedules.iter_mut().find(|schedule| Some(schedule.id) == removed.schedule) {
+            schedule.exceptions.push(date);
+        }
+    }
+
+    pub fn remove_future_occurrences(&mut self, id: u32, date: NaiveDate) {
+        if let Some(schedule) = self.schedules.iter_mut().find(|schedule| schedule.id == id) {
+            schedule.end = Some(date - chrono::Duration::days(1));
+        }
+
+        for (day, workouts) in self.workouts.iter_mut() {
+            if *day >= date {
+                workouts.retain(|workout| workout.schedule != Some(id) || workout.completed);
+            }
+        }
+    }
+
+    pub fn change_occurrence(&mut self, date: NaiveDate, index: usize, template: WorkoutTemplate) {
+        let Some(planned) = self.workouts.get_mut(&date).and_then(|workouts| workouts.get_mut(index)) else { return; };
+
+        if let Some(id) = planned.schedule.take() {
+            if let Some(schedule) = self.schedules.iter_mut().find(|schedule| schedule.id == id) {
+                schedule.exceptions.push(date);
+            }
+        }
+        planned.template = template;
+    }
+
+    // splits the series at `date`: the old rule ends the day before and a new one carries on with the new template
+    pub fn change_future_occurrences(&mut self, id: u32, date: NaiveDate, templates: &HashMap<String, WorkoutTemplate>, template: String, until: NaiveDate) {
+        let Some(rule) = self.schedules.iter().find(|schedule| schedule.id == id).map(|schedule| schedule.rule.clone()) else { return; };
+
+        self.remove_future_occurrences(id, date);
+        self.add_schedule(template, rule, date);
+        self.generate_occurrences(templates, date, until);
+    }
+
+    // lightens the program workouts between the two dates, returns how many were changed
+    pub fn plan_deload(&mut self, from: NaiveDate, to: NaiveDate) -> usize {
+        let mut changed = 0;
+        for (_, workouts) in self.workouts.iter_mut().filter(|(date, _)| from <= **date && **date <= to) {
+            for slot in workouts.iter_mut().filter(|workout| !workout.completed).filter_map(|workout| workout.program.as_mut()) {
+                slot.intensity = slot.intensity.min(DELOAD_INTENSITY);
+                slot.volume = slot.volume.min(DELOAD_VOLUME);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    pub fn missed(&self, today: NaiveDate) -> Vec<(NaiveDate, usize)> {
+        let mut missed: Vec<(NaiveDate, usize)> = self.workouts
+            .iter()
+            .flat_map(|(date, workouts)| workouts.iter().enumerate().filter(|(_, workout)| workout.is_missed(today)).map(|(index, _)| (*date, index)))
+            .collect();
+        missed.sort();
+        missed
+    }
+
+    pub fn has_missed(&self, date: NaiveDate, today: NaiveDate) -> bool {
+        self.workouts.get(&date).is_some_and(|workouts| workouts.iter().any(|workout| workout.is_missed(today)))
+    }
+
+    // a free day has nothing planned at all, planned rest days are kept
+    pub fn next_free_day(&self, from: NaiveDate) -> NaiveDate {
+        let mut date = from;
+        while self.workouts.get(&date).is_some_and(|workouts| !workouts.is_empty()) {
+            date += chrono::Duration::days(1);
+        }
+        date
+    }
+
+    // occurrences leave their series, otherwise the rule would plan them again
+    fn take_workout(&mut self, date: NaiveDate, index: usize) -> Option<WorkoutPlanned> {
+        let workouts = self.workouts.get_mut(&date)?;
+        if index >= workouts.len() {
+            return None;
+        }
+        let mut workout = workouts.remove(index);
+        if let Some(id) = workout.schedule.take() {
+            if let Some(schedule) = self.schedules.iter_mut().find(|schedule| schedule.id == id) {
+                schedule.exceptions.push(date);
+            }
+        }
+        Some(workout)
+    }
+
+    fn put_workout(&mut self, date: NaiveDate, mut workout: WorkoutPlanned) {
+        workout.date = date;
+        let workouts = self.workouts.entry(date).or_default();
+        if workout.is_rest() {
+            if workouts.iter().any(|planned| !planned.is_rest()) {
+                return;
+            }
+        } else {
+            workouts.retain(|planned| !planned.is_rest());
+        }
+        workouts.push(workout);
+    }
+
+    // shifting moves the missed workout a day later, or onto today, together with everything planned
+    // after it: the rest of its program, or the one-off workouts and rest days when it is not part of one.
+    // recurring series keep following their rule and are left alone. a rest day that would land on a
+    // workout is not dropped quietly, the shift fails and the plan stays as it was
+    pub fn reschedule(&mut self, date: NaiveDate, index: usize, action: MissedAction, today: NaiveDate) -> Result<(), String> {
+        let Some(missed) = self.workouts.get(&date).and_then(|workouts| workouts.get(index)).cloned() else {
+            return Err(String::from("the workout is no longer planned"));
+        };
+        let before = self.clone();
+
+        match action {
+            MissedAction::Skip => {
+                if let Some(workout) = self.workouts.get_mut(&date).and_then(|workouts| workouts.get_mut(index)) {
+                    workout.skipped = true;
+                }
+            }
+            MissedAction::NextFreeDay => {
+                let target = self.next_free_day(today.max(date + chrono::Duration::days(1)));
+                if let Some(workout) = self.take_workout(date, index) {
+                    self.put_workout(target, workout);
+                }
+            }
+            MissedAction::ShiftSchedule | MissedAction::ShiftToToday => {
+                let days = match action {
+                    MissedAction::ShiftToToday => (today - date).num_days().max(1),
+                    _ => 1,
+                };
+                let program = missed.program.as_ref().map(|slot| slot.name.clone());
+                let follows = |workout: &WorkoutPlanned| match &program {
+                    Some(name) => workout.program.as_ref().is_some_and(|slot| &slot.name == name),
+                    None => workout.program.is_none() && workout.schedule.is_none(),
+                };
+
+                let mut moving: Vec<(NaiveDate, WorkoutPlanned)> = Vec::new();
+                for (day, workouts) in self.workouts.iter_mut() {
+                    if *day < date {
+                        continue;
+                    }
+                    let mut kept = Vec::new();
+                    for (position, workout) in workouts.drain(..).enumerate() {
+                        let is_missed = *day == date && position == index;
+                        if is_missed || (*day > date && !workout.completed && !workout.skipped && follows(&workout)) {
+                            moving.push((*day, workout));
+                        } else {
+                            kept.push(workout);
+                        }
+                    }
+                    *workouts = kept;
+                }
+
+                let mut rest_days = Vec::new();
+                for (day, mut workout) in moving {
+                    if let Some(id) = workout.schedule.take() {
+                        if let Some(schedule) = self.schedules.iter_mut().find(|schedule| schedule.id == id) {
+                            schedule.exceptions.push(day);
+                        }
+                    }
+                    let target = day + chrono::Duration::days(days);
+                    if workout.is_rest() {
+                        rest_days.push(target);
+                    }
+                    self.put_workout(target, workout);
+                }
+
+                if let Some(clash) = rest_days.into_iter().find(|day| !self.workouts.get(day).is_some_and(|workouts| workouts.iter().any(WorkoutPlanned::is_rest))) {
+                    *self = before;
+                    return Err(format!("the rest day would move onto a workout on {}, shift it by hand", clash.format("%b %e")));
+                }
+            }
+        }
+
+        self.workouts.retain(|_, workouts| !workouts.is_empty());
+        Ok(())
+    }
+
     pub fn remove_workout(&mut self, date: NaiveDate, index: usize) -> Result<(), String> {
         if let workouts = self.workouts.get_mut(&date).unwrap() {
             workouts.remove(index);
@@ -431,7 +2739,7 @@ impl WorkoutData {
     }
 }   
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq, EnumIter)]
 pub enum Muscle {
     Necks,
     Traps,
@@ -456,19 +2764,139 @@ pub enum Muscle {
     Hamstrings,
 }
 
+impl fmt::Display for Muscle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Muscle::Necks => "neck",
+            Muscle::Traps => "traps",
+            Muscle::UpperChest => "upper chest",
+            Muscle::LowerChest => "lower chest",
+            Muscle::FrontDelt => "front delts",
+            Muscle::SideDelt => "side delts",
+            Muscle::RearDelt => "rear delts",
+            Muscle::Biceps => "biceps",
+            Muscle::Triceps => "triceps",
+            Muscle::Forearms => "forearms",
+            Muscle::Abs => "abs",
+            Muscle::Hips => "hip flexors",
+            Muscle::Adductors => "adductors",
+            Muscle::Quads => "quads",
+            Muscle::ExtHips => "abductors",
+            Muscle::Calfs => "calves",
+            Muscle::Infraspinatus => "infraspinatus",
+            Muscle::Lats => "lats",
+            Muscle::LowerBack => "lower back",
+            Muscle::Glutes => "glutes",
+            Muscle::Hamstrings => "hamstrings",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// weekly hard sets: maintenance volume, minimum effective volume and maximum recoverable volume
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Landmarks {
+    pub mv: f32,
+    pub mev: f32,
+    pub mrv: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeZone {
+    Below,
+    Maintenance,
+    Productive,
+    Over,
+}
+
+impl fmt::Display for VolumeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VolumeZone::Below => "below maintenance",
+            VolumeZone::Maintenance => "maintaining",
+            VolumeZone::Productive => "growing",
+            VolumeZone::Over => "above recoverable",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Landmarks {
+    fn new(mv: f32, mev: f32, mrv: f32) -> Self {
+        Self { mv, mev, mrv }
+    }
+
+    pub fn zone(&self, sets: f32) -> VolumeZone {
+        if sets > self.mrv {
+            VolumeZone::Over
+        } else if sets >= self.mev {
+            VolumeZone::Productive
+        } else if sets >= self.mv {
+            VolumeZone::Maintenance
+        } else {
+            VolumeZone::Below
+        }
+    }
+}
+
+pub const SECONDARY_SHARE: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VolumeLandmarks {
+    // a set counts this much for the muscles it only works secondarily
+    pub secondary_share: f32,
+    pub targets: HashMap<Muscle, Landmarks>,
+}
+
+impl Default for VolumeLandmarks {
+    fn default() -> Self {
+        let targets = Muscle::iter()
+            .map(|muscle| {
+                let landmarks = match muscle {
+                    Muscle::Necks => Landmarks::new(0.0, 0.0, 12.0),
+                    Muscle::Traps => Landmarks::new(0.0, 4.0, 26.0),
+                    Muscle::UpperChest | Muscle::LowerChest => Landmarks::new(4.0, 6.0, 16.0),
+                    Muscle::FrontDelt => Landmarks::new(0.0, 0.0, 12.0),
+                    Muscle::SideDelt => Landmarks::new(6.0, 8.0, 26.0),
+                    Muscle::RearDelt => Landmarks::new(0.0, 6.0, 26.0),
+                    Muscle::Biceps => Landmarks::new(5.0, 8.0, 26.0),
+                    Muscle::Triceps => Landmarks::new(4.0, 6.0, 18.0),
+                    Muscle::Forearms => Landmarks::new(2.0, 4.0, 20.0),
+                    Muscle::Abs => Landmarks::new(0.0, 6.0, 25.0),
+                    Muscle::Quads => Landmarks::new(6.0, 8.0, 20.0),
+                    Muscle::Hamstrings => Landmarks::new(3.0, 6.0, 20.0),
+                    Muscle::Calfs => Landmarks::new(6.0, 8.0, 20.0),
+                    Muscle::Lats => Landmarks::new(6.0, 10.0, 22.0),
+                    Muscle::LowerBack => Landmarks::new(0.0, 4.0, 12.0),
+                    Muscle::Glutes | Muscle::Hips | Muscle::Adductors | Muscle::ExtHips | Muscle::Infraspinatus => Landmarks::new(0.0, 4.0, 16.0),
+                };
+                (muscle, landmarks)
+            })
+            .collect();
+
+        Self { secondary_share: SECONDARY_SHARE, targets }
+    }
+}
+
+impl VolumeLandmarks {
+    pub fn get(&self, muscle: &Muscle) -> Landmarks {
+        self.targets.get(muscle).copied().unwrap_or(Landmarks::new(0.0, 0.0, 20.0))
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct MacroData {
     pub meal_history: HashMap<NaiveDate, Vec<Eat>>,
 
     pub calory_goal: u32,
+    // cardio burn for the selected day, added on top of the goal when enabled
+    pub calory_burned: u32,
+    pub count_burned: bool,
     pub protein_goal: u32,
     pub carb_goal: u32,
     pub fat_goal: u32,
 
-    pub calory_registered: u32,
-    pub protein_registered: u32,
-    pub carb_registered: u32,
-    pub fat_registered: u32,
     pub meal_registered: u32,
 }
 
@@ -477,13 +2905,11 @@ impl MacroData {
         Self {
             meal_history: HashMap::from([(Local::now().date_naive(), vec![Eat::new(chrono::Local::now().time(), Meal::new(100, 100, 100, 100)), Eat::new(chrono::Local::now().time(), Meal::new(10, 10, 10, 10))])]),
             calory_goal: 1000,
+            calory_burned: 0,
+            count_burned: true,
             protein_goal: 200,
             carb_goal: 90,
             fat_goal: 50,
-            calory_registered: 0,
-            protein_registered: 0,
-            carb_registered: 0,
-            fat_registered: 0,
             meal_registered: 0,
         }
     }
@@ -503,11 +2929,8 @@ impl MacroData {
     //     }
     // }
 
-    pub fn update(&mut self, calory: &str, protein: &str, carb: &str, fat: &str) {
-        self.calory_registered += calory.trim().parse::<u32>().unwrap_or(0);
-        self.protein_registered += protein.trim().parse::<u32>().unwrap_or(0);
-        self.carb_registered += carb.trim().parse::<u32>().unwrap_or(0);
-        self.fat_registered += fat.trim().parse::<u32>().unwrap_or(0);
+    pub fn budget(&self) -> u32 {
+        if self.count_burned { self.calory_goal + self.calory_burned } else { self.calory_goal }
     }
 
     pub fn add_meal(&mut self, selected_date: NaiveDate, calory: &str, protein: &str, carb: &str, fat: &str) {
@@ -682,6 +3105,71 @@ pub struct States {
     pub current_exercise: Exercises,
     pub new_template_name: String,
     pub new_template_exercises: Vec<Exercises>,
+    pub session: Option<WorkoutSession>,
+    pub progression_window: bool,
+    pub programs_window: bool,
+    pub current_program: Option<Program>,
+    pub program_week: usize,
+    // name the open program was loaded under, `None` for a new one
+    pub program_original: Option<String>,
+    pub program_message: String,
+    pub schedule_window: bool,
+    pub schedule_rule: RecurrenceRule,
+    pub schedule_template: String,
+    pub occurrence_edit: Option<(NaiveDate, usize)>,
+    pub missed_edit: Option<(NaiveDate, usize)>,
+    pub missed_action: MissedAction,
+    pub generator_window: bool,
+    pub generator: GeneratorSettings,
+    pub generated: Option<WorkoutTemplate>,
+    pub generator_message: String,
+    pub note_query: String,
+    pub history_window: bool,
+    pub history_filter: HistoryFilter,
+    // days back for the date filter, `None` for all time
+    pub history_days: Option<i64>,
+    // index into `AllWorkoutData.workouts` and the copy being edited
+    pub history_selected: Option<usize>,
+    pub history_draft: Option<WorkoutDone>,
+    pub history_date: String,
+    pub history_confirm_delete: bool,
+    pub group_selection: Vec<usize>,
+    pub prescription_index: Option<usize>,
+    // exercise whose substitutes are listed, in the template editor and the live session
+    pub swap_index: Option<usize>,
+    pub session_swap: Option<usize>,
+    pub template_rename: String,
+    pub template_tag: String,
+    pub tag_filter: Option<String>,
+    pub confirm_delete: bool,
+    pub template_message: String,
+    pub sharing_window: bool,
+    pub sharing_path: String,
+    pub export_templates: Vec<String>,
+    pub export_programs: Vec<String>,
+    pub import_preview: Option<ImportPreview>,
+    pub collision_policy: CollisionPolicy,
+    pub sharing_message: String,
+    pub plates_window: bool,
+    pub cardio_window: bool,
+    pub cardio_draft: CardioSession,
+    // date and index of the planned workout being logged, the draft date can move on import
+    pub cardio_planned: Option<(NaiveDate, usize)>,
+    pub cardio_path: String,
+    pub cardio_message: String,
+    pub streak_kind: StreakKind,
+    // reached level and the time the animation started
+    pub level_up: Option<(u32, f64)>,
+    // data revision and day the account was last synced for
+    pub account_synced: Option<(u64, NaiveDate)>,
+    pub badges_window: bool,
+    pub load_message: String,
+    pub recovery_map: bool,
+    // weeks back from the current one in the muscle volume report
+    pub volume_week: i64,
+    pub plate_target: f32,
+    // session set the calculator writes back to
+    pub plate_set: Option<(usize, usize)>,
     // pub scroll_offset: f32,
     // pub velocity: f32,
     // pub dragging: bool,
@@ -724,6 +3212,62 @@ impl States {
             current_exercise: Exercises::default(),
             new_template_name: String::from("workout name"),
             new_template_exercises: Vec::new(),
+            session: None,
+            progression_window: false,
+            programs_window: false,
+            current_program: None,
+            program_week: 0,
+            program_message: String::new(),
+            program_original: None,
+            schedule_window: false,
+            schedule_rule: RecurrenceRule::default(),
+            schedule_template: String::new(),
+            occurrence_edit: None,
+            missed_edit: None,
+            missed_action: MissedAction::NextFreeDay,
+            generator_window: false,
+            generator: GeneratorSettings::default(),
+            generated: None,
+            generator_message: String::new(),
+            note_query: String::new(),
+            history_window: false,
+            history_filter: HistoryFilter::default(),
+            history_days: None,
+            history_selected: None,
+            history_draft: None,
+            history_date: String::new(),
+            history_confirm_delete: false,
+            group_selection: Vec::new(),
+            prescription_index: None,
+            swap_index: None,
+            session_swap: None,
+            template_rename: String::new(),
+            template_tag: String::new(),
+            tag_filter: None,
+            confirm_delete: false,
+            template_message: String::new(),
+            sharing_window: false,
+            sharing_path: String::from("templates.json"),
+            export_templates: Vec::new(),
+            export_programs: Vec::new(),
+            import_preview: None,
+            collision_policy: CollisionPolicy::KeepBoth,
+            sharing_message: String::new(),
+            plates_window: false,
+            cardio_window: false,
+            cardio_draft: CardioSession::default(),
+            cardio_planned: None,
+            cardio_path: String::new(),
+            cardio_message: String::new(),
+            streak_kind: StreakKind::Plan,
+            level_up: None,
+            account_synced: None,
+            badges_window: false,
+            load_message: String::new(),
+            recovery_map: false,
+            volume_week: 0,
+            plate_target: 60.0,
+            plate_set: None,
 
 
             // scroll_offset: 0.0,
@@ -754,6 +3298,13 @@ impl States {
         self.create_template = false;
         self.current_template = String::new();
         self.editable = false;
+        self.group_selection.clear();
+        self.prescription_index = None;
+        self.swap_index = None;
+        self.template_rename = String::new();
+        self.template_tag = String::new();
+        self.confirm_delete = false;
+        self.template_message = String::new();
     }
 
     pub fn reset_new_template_window(&mut self) {
@@ -769,34 +3320,6 @@ pub trait Summary {
     fn summarize(&mut self, selected_day: Option<NaiveDate>);
 }
 
-impl Summary for MacroData {
-    fn summarize(&mut self, selected_day: Option<NaiveDate>) {
-        if let Some(eats) = self.meal_history.get(&selected_day.unwrap()) {
-            let mut calories: u32 = 0;
-            let mut proteins: u32 = 0;
-            let mut carbs: u32 = 0;
-            let mut fats: u32 = 0;
-
-            for eat in eats {
-                calories += eat.meal.calory;
-                proteins += eat.meal.protein;
-                carbs += eat.meal.carb;
-                fats += eat.meal.fat;
-            }
-
-            self.calory_registered = calories;
-            self.protein_registered = proteins;
-            self.carb_registered = carbs;
-            self.fat_registered = fats;
-        } else {
-            self.calory_registered = 0;
-            self.protein_registered = 0;
-            self.carb_registered = 0;
-            self.fat_registered = 0;
-        }
-    }
-}
-
 impl Summary for WaterData {
     fn summarize(&mut self, selected_day: Option<NaiveDate>) {
         if let Some(drinks) = self.water_history.get(&selected_day.unwrap()) {
@@ -815,3 +3338,449 @@ impl Summary for WaterData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn squat_done(sets: &[(f32, u32, Option<f32>)]) -> ExerciseDone {
+        let sets = sets.iter().map(|(weight, reps, rpe)| SetDone { weight: *weight, reps: *reps, rpe: *rpe, ..SetDone::default() }).collect();
+        ExerciseDone { exercise: Exercises::Squat, sets, notes: String::new() }
+    }
+
+    #[test]
+    fn double_progression_adds_reps_then_weight() {
+        let settings = ProgressionSettings::default();
+        assert_eq!(settings.next(&squat_done(&[(100.0, 12, None), (100.0, 9, None)])), SetSuggestion::new(100.0, 10, 2));
+        assert_eq!(settings.next(&squat_done(&[(100.0, 12, None), (100.0, 12, None)])), SetSuggestion::new(102.5, 8, 2));
+    }
+
+    #[test]
+    fn linear_and_rpe_progression() {
+        let linear = ProgressionSettings { rule: ProgressionRule::Linear, ..ProgressionSettings::default() };
+        assert_eq!(linear.next(&squat_done(&[(100.0, 12, None)])), SetSuggestion::new(102.5, 12, 1));
+        assert_eq!(linear.next(&squat_done(&[(100.0, 10, None)])), SetSuggestion::new(100.0, 12, 1));
+
+        let rpe = ProgressionSettings { rule: ProgressionRule::Rpe, ..ProgressionSettings::default() };
+        // two points easier than the target, so about five percent more
+        assert_eq!(rpe.next(&squat_done(&[(100.0, 5, Some(6.0))])), SetSuggestion::new(105.0, 5, 1));
+        assert_eq!(rpe.next(&squat_done(&[(100.0, 5, Some(9.0))])), SetSuggestion::new(97.5, 5, 1));
+        // without a logged rpe it falls back to linear
+        assert_eq!(rpe.next(&squat_done(&[(100.0, 12, None)])), SetSuggestion::new(102.5, 12, 1));
+    }
+
+    fn program_planned(planned: &WorkoutPlannedData) -> Vec<NaiveDate> {
+        let mut dates: Vec<NaiveDate> = planned
+            .workouts
+            .iter()
+            .flat_map(|(date, workouts)| workouts.iter().filter(|workout| workout.program.is_some()).map(move |_| *date))
+            .collect();
+        dates.sort();
+        dates
+    }
+
+    #[test]
+    fn program_weeks_start_on_monday_and_reapply_replaces() {
+        let template = WorkoutTemplate::new(String::from("full"), vec![Exercises::Squat]);
+        let templates = HashMap::from([(String::from("full"), template)]);
+        let days = vec![ProgramDay { day: 0, template: String::from("full") }, ProgramDay { day: 4, template: String::from("full") }];
+        let program = Program::new(String::from("block"), 2, days);
+        let mut planned = WorkoutPlannedData { workouts: HashMap::new(), schedules: Vec::new() };
+        // a wednesday, so the first monday is already gone
+        let start = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let expected: Vec<NaiveDate> = [(3, 7), (3, 10), (3, 14)].iter().map(|(m, d)| NaiveDate::from_ymd_opt(2025, *m, *d).unwrap()).collect();
+
+        assert_eq!(planned.apply_program(&program, &templates, start), Ok(3));
+        assert_eq!(program_planned(&planned), expected);
+        assert_eq!(planned.apply_program(&program, &templates, start), Ok(3));
+        assert_eq!(program_planned(&planned), expected);
+    }
+
+    #[test]
+    fn renaming_a_program_keeps_its_planned_days() {
+        let mut data = UserDataPack::default();
+        let template = WorkoutTemplate::new(String::from("full"), vec![Exercises::Squat]);
+        data.all_workout_data.workout_templates.insert(String::from("full"), template);
+        let program = Program::new(String::from("block"), 1, vec![ProgramDay { day: 0, template: String::from("full") }]);
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        data.save_program(None, program.clone()).unwrap();
+        data.planned_workout_data.apply_program(&program, &data.all_workout_data.workout_templates, start).unwrap();
+
+        assert!(data.save_program(None, program.clone()).is_err());
+        let mut renamed = program;
+        renamed.name = String::from(" strength ");
+        data.save_program(Some("block"), renamed).unwrap();
+        assert!(!data.all_workout_data.programs.contains_key("block"));
+        assert!(data.all_workout_data.programs.contains_key("strength"));
+        let slot = data.planned_workout_data.workouts[&start].iter().find_map(|planned| planned.program.as_ref()).unwrap();
+        assert_eq!(slot.name, "strength");
+    }
+
+    fn planned_names(planned: &WorkoutPlannedData) -> Vec<(NaiveDate, String)> {
+        let mut names: Vec<(NaiveDate, String)> = planned
+            .workouts
+            .iter()
+            .flat_map(|(date, workouts)| workouts.iter().map(move |workout| (*date, workout.template.workout_name.clone())))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn removed_occurrences_stay_removed_and_series_split() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let day = |offset: i64| start + chrono::Duration::days(offset);
+        let templates = HashMap::from([
+            (String::from("a"), WorkoutTemplate::new(String::from("a"), Vec::new())),
+            (String::from("b"), WorkoutTemplate::new(String::from("b"), Vec::new())),
+        ]);
+        let mut planned = WorkoutPlannedData { workouts: HashMap::new(), schedules: Vec::new() };
+        let id = planned.add_schedule(String::from("a"), RecurrenceRule::EveryNDays(2), start);
+
+        planned.generate_occurrences(&templates, start, day(6));
+        planned.generate_occurrences(&templates, start, day(6));
+        assert_eq!(planned_names(&planned).len(), 4);
+
+        planned.remove_occurrence(day(2), 0);
+        planned.generate_occurrences(&templates, start, day(6));
+        assert!(planned.workouts[&day(2)].is_empty());
+
+        planned.change_future_occurrences(id, day(4), &templates, String::from("b"), day(6));
+        planned.workouts.retain(|_, workouts| !workouts.is_empty());
+        assert_eq!(planned_names(&planned), vec![(start, String::from("a")), (day(4), String::from("b")), (day(6), String::from("b"))]);
+    }
+
+    #[test]
+    fn recurrence_rules_match_their_days() {
+        // a monday
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let day = |offset: i64| start + chrono::Duration::days(offset);
+
+        let every_third = RecurrenceRule::EveryNDays(3);
+        assert!(every_third.matches(start, day(6)) && !every_third.matches(start, day(7)));
+        assert!(!every_third.matches(start, day(-3)));
+
+        let weekdays = RecurrenceRule::Weekdays(vec![0, 2]);
+        assert!(weekdays.matches(start, day(2)) && weekdays.matches(start, day(7)) && !weekdays.matches(start, day(1)));
+
+        let other_week = RecurrenceRule::EveryOtherWeek(vec![4]);
+        assert!(other_week.matches(start, day(4)) && !other_week.matches(start, day(11)) && other_week.matches(start, day(18)));
+    }
+
+    #[test]
+    fn supersets_alternate_and_rest_after_each_round() {
+        let data = AllWorkoutData::default();
+        let mut template = WorkoutTemplate::new(String::from("mixed"), vec![Exercises::BenchPress, Exercises::Squat, Exercises::Deadlift]);
+        template.group_exercises(&[0, 1], GroupKind::Superset);
+        let session = WorkoutSession::new(template, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(), None, None, &data);
+
+        let steps: Vec<(usize, usize, u32)> = session.steps().iter().map(|step| (step.exercise, step.set, step.rest_after)).collect();
+        assert_eq!(steps, vec![(0, 0, 0), (1, 0, 90), (0, 1, 0), (1, 1, 90), (0, 2, 0), (1, 2, 90), (2, 0, 90)]);
+    }
+
+    #[test]
+    fn prescriptions_set_the_load_and_the_rep_range() {
+        let mut data = AllWorkoutData::default();
+        let rx = |load| Prescription { sets: 4, min_reps: 5, max_reps: 8, load, ..Prescription::default() };
+
+        assert_eq!(data.suggest_prescribed(&Exercises::Squat, Some(&rx(Some(TargetLoad::Absolute(60.0)))), None), Some(SetSuggestion::new(60.0, 5, 4)));
+        assert_eq!(data.suggest_prescribed(&Exercises::Squat, Some(&rx(None)), None), None);
+
+        data.workouts.push(WorkoutDone { date: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(), exercises: vec![squat_done(&[(100.0, 10, None)])], ..WorkoutDone::default() });
+        // epley puts 100 x 10 at about 133 kg, three quarters of that snaps back to 100
+        assert_eq!(data.suggest_prescribed(&Exercises::Squat, Some(&rx(Some(TargetLoad::PercentOneRm(75.0)))), None), Some(SetSuggestion::new(100.0, 5, 4)));
+        assert_eq!(data.suggest_prescribed(&Exercises::Squat, Some(&rx(None)), None), Some(SetSuggestion::new(102.5, 5, 4)));
+    }
+
+    #[test]
+    fn renaming_and_deleting_templates_follow_their_usage() {
+        let mut data = UserDataPack::default();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        data.planned_workout_data = WorkoutPlannedData { workouts: HashMap::new(), schedules: Vec::new() };
+        for (name, exercise) in [("upper day", Exercises::BenchPress), ("lower day", Exercises::Squat)] {
+            data.all_workout_data.workout_templates.insert(String::from(name), WorkoutTemplate::new(String::from(name), vec![exercise]));
+        }
+        let _ = data.planned_workout_data.add_workout(today, WorkoutPlanned::new(WorkoutTemplate::new(String::from("upper day"), Vec::new()), today));
+        data.planned_workout_data.add_schedule(String::from("upper day"), RecurrenceRule::default(), today);
+
+        assert!(data.rename_template("upper day", "lower day").is_err());
+        data.rename_template("upper day", " push day ").unwrap();
+        assert!(data.all_workout_data.workout_templates.contains_key("push day") && !data.all_workout_data.workout_templates.contains_key("upper day"));
+        assert_eq!(data.planned_workout_data.workouts[&today][0].template.workout_name, "push day");
+        assert_eq!(data.planned_workout_data.schedules[0].template, "push day");
+
+        assert!(data.delete_template("push day", today, false).is_err());
+        data.delete_template("push day", today, true).unwrap();
+        assert!(data.planned_workout_data.workouts.is_empty());
+        assert_eq!(data.planned_workout_data.schedules[0].end, Some(today - chrono::Duration::days(1)));
+        assert!(data.delete_template("lower day", today, false).is_ok());
+    }
+
+    #[test]
+    fn plates_reach_the_closest_loadable_weight() {
+        let kg = PlateInventory::standard(WeightUnit::Kg);
+        let load = kg.load(101.0);
+        assert_eq!(load.loaded, 100.0);
+        assert_eq!(load.per_side.iter().sum::<f32>(), 40.0);
+        assert!(load.per_side.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert_eq!(kg.load(10.0).loaded, 20.0);
+
+        // one pair of twenties is all there is
+        let short = PlateInventory { plates: vec![PlateCount { weight: 20.0, count: 2 }, PlateCount { weight: 5.0, count: 1 }], ..kg.clone() };
+        assert_eq!(short.load(100.0).per_side, vec![20.0]);
+
+        // a deep stack of small plates only looks as far as the target
+        let stack = PlateInventory { plates: vec![PlateCount { weight: 0.5, count: 2000 }, PlateCount { weight: 1.25, count: 2 }], ..kg.clone() };
+        assert_eq!(stack.load(101.25).loaded, 101.0);
+        assert_eq!(stack.load(23.5).per_side, vec![1.25, 0.5]);
+
+        // 25 kg sits halfway between the bar and one pair of fives, the lighter load wins
+        let fives = PlateInventory { plates: vec![PlateCount { weight: 5.0, count: 2 }], ..kg };
+        assert_eq!(fives.load(25.0).loaded, 20.0);
+    }
+
+    #[test]
+    fn warmups_follow_the_first_working_set() {
+        let data = AllWorkoutData::default();
+        let template = WorkoutTemplate::new(String::from("bench"), vec![Exercises::BenchPress]);
+        let mut session = WorkoutSession::new(template, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(), None, None, &data);
+        assert!(session.exercises[0].sets.iter().all(|row| !row.set.warmup));
+
+        session.exercises[0].sets[0].set.weight = 100.0;
+        session.refresh_warmups(0, &data);
+        let warmups: Vec<f32> = session.exercises[0].sets.iter().filter(|row| row.set.warmup).map(|row| row.set.weight).collect();
+        assert_eq!(warmups.len(), 4);
+        assert!(warmups.windows(2).all(|pair| pair[0] < pair[1]) && warmups.iter().all(|weight| *weight < 100.0));
+
+        session.exercises[0].sets[4].set.weight = 30.0;
+        session.refresh_warmups(0, &data);
+        let count = session.exercises[0].sets.iter().filter(|row| row.set.warmup).count();
+        assert!(count < 4);
+        assert_eq!(session.exercises[0].sets[count].set.weight, 30.0);
+    }
+
+    #[test]
+    fn saves_from_before_the_new_fields_still_load() {
+        let text = r#"{"workouts": [], "workout_templates": {"legs": {"workout_name": "legs", "exercises": ["Squat"]}}}"#;
+        let data: AllWorkoutData = serde_json::from_str(text).unwrap();
+        assert!(data.cardio.is_empty() && data.programs.is_empty());
+        assert!(data.workout_templates["legs"].prescription(0).is_none());
+
+        let planned: WorkoutPlanned = serde_json::from_str(r#"{"template": {"workout_name": "legs", "exercises": []}, "date": "2025-03-03"}"#).unwrap();
+        assert!(!planned.completed && planned.cardio.is_none() && !planned.rest);
+    }
+
+    #[test]
+    fn cardio_pace_and_calories() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut run = CardioSession { distance_km: 10.0, duration_secs: 50 * 60, ..CardioSession::new(CardioKind::Running, date) };
+        assert_eq!(run.pace_label(), "5:00 /km");
+        assert_eq!(run.estimate_calories(80.0), 787);
+        run.distance_km = 0.0;
+        assert_eq!(run.pace_label(), "-");
+        assert_eq!(run.estimate_calories(80.0), 653);
+
+        let row = CardioSession { distance_km: 2.0, duration_secs: 8 * 60, ..CardioSession::new(CardioKind::Rowing, date) };
+        assert_eq!(row.pace_label(), "2:00 /500 m");
+        let ride = CardioSession { distance_km: 30.0, duration_secs: 3600, ..CardioSession::new(CardioKind::Cycling, date) };
+        assert_eq!(ride.pace_label(), "30.0 km/h");
+
+        let mut data = UserDataPack::default();
+        data.user_information.weight = 75;
+        data.record_cardio(ride);
+        assert_eq!(data.all_workout_data.cardio[0].calories, 900);
+    }
+
+    #[test]
+    fn bodyweight_reps_can_beat_a_little_added_weight() {
+        let pull_ups = |weight, reps| ExerciseDone { exercise: Exercises::PullUp, sets: vec![SetDone { weight, reps, ..SetDone::default() }], notes: String::new() };
+        assert!(pull_ups(0.0, 30).best(80.0) > pull_ups(5.0, 1).best(80.0));
+        assert!(pull_ups(20.0, 5).best(80.0) > pull_ups(0.0, 5).best(80.0));
+
+        let mut data = AllWorkoutData::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        data.record_workout(WorkoutDone { date, exercises: vec![pull_ups(0.0, 30)], ..WorkoutDone::default() }, 80.0);
+        data.record_workout(WorkoutDone { date: date + chrono::Duration::days(2), exercises: vec![pull_ups(5.0, 1)], ..WorkoutDone::default() }, 80.0);
+        assert_eq!(data.workouts[1].prs, 0);
+    }
+
+    fn unilateral_done(sets: u32) -> ExerciseDone {
+        let side = |side| SetDone { weight: 20.0, reps: 10, side: Some(side), ..SetDone::default() };
+        let sets = (0..sets).flat_map(|_| [side(Side::Left), side(Side::Right)]).collect();
+        ExerciseDone { exercise: Exercises::SingleArmRow, sets, notes: String::new() }
+    }
+
+    #[test]
+    fn left_and_right_count_as_one_set() {
+        let done = unilateral_done(3);
+        assert_eq!(done.set_count(), 3);
+        assert_eq!(ProgressionSettings::default().next(&done).sets, 3);
+    }
+
+    #[test]
+    fn unilateral_sides_share_a_round_in_a_superset() {
+        let mut data = AllWorkoutData::default();
+        data.warmups.enabled = false;
+        let mut template = WorkoutTemplate::new(String::from("pair"), vec![Exercises::SingleArmRow, Exercises::BenchPress]);
+        template.group_exercises(&[0, 1], GroupKind::Superset);
+        let session = WorkoutSession::new(template, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(), None, None, &data);
+
+        let steps: Vec<(usize, usize, u32)> = session.steps().iter().map(|step| (step.exercise, step.set, step.rest_after)).collect();
+        assert_eq!(steps, vec![(0, 0, 0), (0, 1, 0), (1, 0, 90), (0, 2, 0), (0, 3, 0), (1, 1, 90), (0, 4, 0), (0, 5, 0), (1, 2, 90)]);
+    }
+
+    #[test]
+    fn unilateral_sets_do_not_grow_between_sessions() {
+        let mut data = AllWorkoutData::default();
+        data.warmups.enabled = false;
+        let template = WorkoutTemplate::new(String::from("row"), vec![Exercises::SingleArmRow]);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+
+        for _ in 0..3 {
+            data.workouts.push(WorkoutDone { date, exercises: vec![unilateral_done(3)], ..WorkoutDone::default() });
+            let session = WorkoutSession::new(template.clone(), date, None, None, &data);
+            let rows: Vec<&SessionSet> = session.exercises[0].sets.iter().filter(|row| !row.set.warmup).collect();
+            assert_eq!(rows.len(), 6);
+            assert_eq!(rows.iter().filter(|row| row.set.side == Some(Side::Right)).count(), 3);
+        }
+    }
+
+    fn missed_plan(start: NaiveDate) -> WorkoutPlannedData {
+        let mut planned = WorkoutPlannedData { workouts: HashMap::new(), schedules: Vec::new() };
+        for (offset, name) in [(0, "push"), (1, "pull"), (3, "legs")] {
+            let date = start + chrono::Duration::days(offset);
+            let _ = planned.add_workout(date, WorkoutPlanned::new(WorkoutTemplate::new(String::from(name), Vec::new()), date));
+        }
+        planned
+    }
+
+    #[test]
+    fn shifting_moves_the_rest_of_the_plan() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let today = start + chrono::Duration::days(2);
+        let day = |offset: i64| start + chrono::Duration::days(offset);
+
+        let mut planned = missed_plan(start);
+        planned.reschedule(start, 0, MissedAction::ShiftSchedule, today).unwrap();
+        assert_eq!(planned_names(&planned), vec![(day(1), String::from("push")), (day(2), String::from("pull")), (day(4), String::from("legs"))]);
+
+        let mut planned = missed_plan(start);
+        planned.reschedule(start, 0, MissedAction::ShiftToToday, today).unwrap();
+        assert_eq!(planned_names(&planned), vec![(day(2), String::from("push")), (day(3), String::from("pull")), (day(5), String::from("legs"))]);
+
+        let mut planned = missed_plan(start);
+        planned.reschedule(start, 0, MissedAction::NextFreeDay, today).unwrap();
+        assert_eq!(planned_names(&planned), vec![(day(1), String::from("pull")), (day(2), String::from("push")), (day(3), String::from("legs"))]);
+
+        let mut planned = missed_plan(start);
+        planned.reschedule(start, 0, MissedAction::Skip, today).unwrap();
+        assert!(planned.workouts[&start][0].skipped && !planned.workouts[&start][0].is_missed(today));
+    }
+
+    #[test]
+    fn shifting_a_rest_day_onto_a_workout_fails_and_keeps_the_plan() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let day = |offset: i64| start + chrono::Duration::days(offset);
+
+        let mut planned = missed_plan(start);
+        planned.rest(day(2));
+        let mut recurring = WorkoutPlanned::new(WorkoutTemplate::new(String::from("run"), Vec::new()), day(3));
+        recurring.schedule = Some(0);
+        let _ = planned.add_workout(day(3), recurring);
+        let before = planned_names(&planned);
+
+        assert!(planned.reschedule(start, 0, MissedAction::ShiftSchedule, day(2)).is_err());
+        assert_eq!(planned_names(&planned), before);
+    }
+
+    #[test]
+    fn added_templates_never_overwrite() {
+        let mut data = AllWorkoutData::default();
+        let template = WorkoutTemplate::new(String::from(" legs "), vec![Exercises::Squat]);
+        assert_eq!(data.add_template(template.clone()), "legs");
+        assert_eq!(data.add_template(template.clone()), "legs 2");
+        assert_eq!(data.add_template(template), "legs 3");
+        assert_eq!(data.workout_templates["legs 2"].workout_name, "legs 2");
+    }
+
+    fn bench(date: NaiveDate, weight: f32) -> WorkoutDone {
+        let done = ExerciseDone { exercise: Exercises::BenchPress, sets: vec![SetDone { weight, reps: 5, ..SetDone::default() }], notes: String::new() };
+        WorkoutDone { template: WorkoutTemplate::new(String::from("push"), vec![Exercises::BenchPress]), date, exercises: vec![done], ..WorkoutDone::default() }
+    }
+
+    #[test]
+    fn note_search_points_back_at_the_workout() {
+        let mut data = AllWorkoutData::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut older = bench(date, 100.0);
+        older.notes = String::from("Slept badly");
+        let mut newer = bench(date + chrono::Duration::days(2), 100.0);
+        newer.exercises[0].sets.insert(0, SetDone { weight: 40.0, reps: 10, warmup: true, note: String::from("shoulder felt bad"), ..SetDone::default() });
+        data.workouts.extend([older, newer]);
+
+        let hits = data.search_notes("BAD");
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].index, hits[0].exercise.clone()), (1, Some(Exercises::BenchPress)));
+        assert_eq!((hits[1].index, hits[1].exercise.clone()), (0, None));
+        assert!(data.search_notes("knee").is_empty());
+    }
+
+    #[test]
+    fn recorded_and_edited_prs_follow_one_rule() {
+        let mut data = AllWorkoutData::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        data.record_workout(bench(date, 100.0), 80.0);
+        data.record_workout(bench(date + chrono::Duration::days(2), 105.0), 80.0);
+        // logged late for the day in between, it takes the pr from the session after it
+        data.record_workout(bench(date + chrono::Duration::days(1), 110.0), 80.0);
+
+        let prs: Vec<u32> = data.workouts.iter().map(|workout| workout.prs).collect();
+        assert_eq!(prs, vec![1, 0, 1]);
+        for index in 0..data.workouts.len() {
+            assert_eq!(data.prs_in(index, 80.0).len() as u32, data.workouts[index].prs);
+        }
+
+        data.delete_workout(2, 80.0);
+        assert_eq!(data.workouts[1].prs, 1);
+    }
+
+    #[test]
+    fn deleting_a_workout_reopens_its_plan() {
+        let mut data = UserDataPack::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut planned = WorkoutPlanned::new(WorkoutTemplate::new(String::from("push"), vec![Exercises::BenchPress]), date);
+        planned.completed = true;
+        data.planned_workout_data.workouts.insert(date, vec![planned]);
+        data.all_workout_data.record_workout(bench(date, 100.0), 80.0);
+        data.all_workout_data.record_workout(bench(date, 90.0), 80.0);
+
+        data.delete_workout(1);
+        assert!(data.planned_workout_data.workouts[&date][0].completed);
+        data.delete_workout(0);
+        assert!(!data.planned_workout_data.workouts[&date][0].completed);
+        assert!(data.all_workout_data.workouts.is_empty());
+    }
+
+    #[test]
+    fn history_filter_combines_every_field() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut workout = bench(date, 100.0);
+        workout.exercises[0].sets[0].note = String::from("Felt Heavy");
+
+        let mut filter = HistoryFilter::default();
+        assert!(filter.matches(&workout));
+        filter.query = String::from("heavy");
+        filter.muscle = Some(Muscle::Triceps);
+        filter.template = Some(String::from("push"));
+        assert!(filter.matches(&workout));
+
+        filter.range = DateRange { from: date + chrono::Duration::days(1), to: date + chrono::Duration::days(7) };
+        assert!(!filter.matches(&workout));
+        filter.range = DateRange::all();
+        filter.exercise = Some(Exercises::Squat);
+        assert!(!filter.matches(&workout));
+        filter.exercise = None;
+        filter.query = String::from("light");
+        assert!(!filter.matches(&workout));
+    }
+}
@@ -1,12 +1,72 @@
+use std::collections::HashMap;
 use eframe::egui;
 use eframe::egui::{vec2, Color32, Pos2, Shape, Stroke, Vec2};
 use serde::de;
 use crate::models::{Exercises, Muscle, muscle_for_workout};
+use crate::recovery::fatigue_color;
+
+pub enum MuscleShading {
+    Workout(Vec<Muscle>, Vec<Muscle>),
+    // fatigue between 0 and 1 for every muscle that is still recovering
+    Recovery(HashMap<Muscle, f32>),
+    // muscles left out are drawn grey
+    Colors(HashMap<Muscle, Color32>),
+}
+
+impl MuscleShading {
+    pub fn color(&self, muscle: Muscle) -> Color32 {
+        match self {
+            MuscleShading::Workout(primary_muscles, secondary_muscles) => ColorQualifier(muscle, primary_muscles, secondary_muscles),
+            MuscleShading::Recovery(fatigue) => fatigue_color(fatigue.get(&muscle).copied().unwrap_or(0.0)),
+            MuscleShading::Colors(colors) => colors.get(&muscle).copied().unwrap_or(Color32::GRAY),
+        }
+    }
+}
+
+// paints muscle polygons and remembers which one the click landed in
+struct MuscleShapes<'a> {
+    painter: &'a egui::Painter,
+    shading: &'a MuscleShading,
+    border: Stroke,
+    click: Option<Pos2>,
+    clicked: Option<Muscle>,
+}
+
+impl MuscleShapes<'_> {
+    fn fill(&mut self, points: Vec<Pos2>, muscle: Muscle) {
+        if self.click.is_some_and(|click| contains(&points, click)) {
+            self.clicked = Some(muscle.clone());
+        }
+        self.painter.add(Shape::convex_polygon(points, self.shading.color(muscle), self.border));
+    }
+}
+
+fn contains(points: &[Pos2], point: Pos2) -> bool {
+    let mut inside = false;
+    let mut previous = points.len().wrapping_sub(1);
+    for (current, a) in points.iter().enumerate() {
+        let b = points[previous];
+        if (a.y > point.y) != (b.y > point.y) && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
 
 pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size: Vec2, exercises: &Vec<Exercises>) {
     let (primary_muscles, secondary_muscles) = muscle_for_workout(exercises);
+    body_map_front(ctx, ui, size, &MuscleShading::Workout(primary_muscles, secondary_muscles));
+}
+
+pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, size: Vec2, exercises: &Vec<Exercises>) {
+    let (primary_muscles, secondary_muscles) = muscle_for_workout(exercises);
+    body_map_behind(ctx, ui, size, &MuscleShading::Workout(primary_muscles, secondary_muscles));
+}
 
-    let (rect, _response) = ui.allocate_exact_size(size, egui::Sense::hover());
+// returns the muscle that was clicked, if any
+pub fn body_map_front(ctx: &egui::Context, ui: &mut egui::Ui, size: Vec2, shading: &MuscleShading) -> Option<Muscle> {
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
 
     let default_size = Vec2::new(120.0, 270.0);
     let scale = (size.x * size.y) / (default_size.x * default_size.y);
@@ -52,9 +112,10 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
 
     let muscle_color = Color32::GRAY;
     let border = Stroke::new(0.0, Color32::GRAY);
+    let mut shapes = MuscleShapes { painter: &painter, shading, border, click: response.clicked().then(|| response.interact_pointer_pos()).flatten(), clicked: None };
 
-    painter.add(Shape::convex_polygon(T_left, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(T_right, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(T_left, Muscle::Traps);
+    shapes.fill(T_right, Muscle::Traps);
 
     //TOP CHEST (TC)
     let chest_center = neck_center + vec2(0.0, 9.0 * scale);
@@ -78,8 +139,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
 
     // let chest_color = Color32::from_rgb(0, 75, 141);
 
-    painter.add(Shape::convex_polygon(TC_left, ColorQualifier(Muscle::UpperChest, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(TC_right, ColorQualifier(Muscle::UpperChest, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(TC_left, Muscle::UpperChest);
+    shapes.fill(TC_right, Muscle::UpperChest);
     // Color32::from_rgb(0, 136, 255)
 
     //BOTTOM CHEST (BC)
@@ -97,8 +158,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let BC_left_bot_r = Pos2::new(BC_left_top_r.x + 1.5 * scale, BC_left_top_r.y + BC_chest_height);
     let BC_right = vec![BC_left_top_r, BC_right_top_r, BC_right_bot_r, BC_left_bot_r];
 
-    painter.add(Shape::convex_polygon(BC_left, ColorQualifier(Muscle::LowerChest, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(BC_right, ColorQualifier(Muscle::LowerChest, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(BC_left, Muscle::LowerChest);
+    shapes.fill(BC_right, Muscle::LowerChest);
     // painter.add(Shape::convex_polygon(BC_left, ColorQualifier(target_muscle.contains(&Muscle::LowerChest)), border));
     // painter.add(Shape::convex_polygon(BC_right, ColorQualifier(target_muscle.contains(&Muscle::LowerChest)), border));
 
@@ -114,8 +175,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let FD_bottom_r = Pos2::new(TC_right_bottom_r.x + offset, TC_right_bottom_r.y);
     let FD_right = vec![FD_left_top_r, FD_right_top_r, FD_bottom_r];
 
-    painter.add(Shape::convex_polygon(FD_left, ColorQualifier(Muscle::FrontDelt, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(FD_right, ColorQualifier(Muscle::FrontDelt, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(FD_left, Muscle::FrontDelt);
+    shapes.fill(FD_right, Muscle::FrontDelt);
     // painter.add(Shape::convex_polygon(FD_left, ColorQualifier(target_muscle.contains(&Muscle::FrontDelt)), border));
     // painter.add(Shape::convex_polygon(FD_right, ColorQualifier(target_muscle.contains(&Muscle::FrontDelt)), border));
 
@@ -132,8 +193,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let SD_right_bot_r = Pos2::new(FD_bottom_r.x + 10.0 * scale + offset, FD_bottom_r.y - 4.0 * scale);
     let SD_right= vec![SD_left_top_r, SD_right_top_r, SD_right_bot_r, SD_left_bot_r];
 
-    painter.add(Shape::convex_polygon(SD_left, ColorQualifier(Muscle::SideDelt, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(SD_right, ColorQualifier(Muscle::SideDelt, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(SD_left, Muscle::SideDelt);
+    shapes.fill(SD_right, Muscle::SideDelt);
     // painter.add(Shape::convex_polygon(SD_left, ColorQualifier(target_muscle.contains(&Muscle::SideDelt)), border));
     // painter.add(Shape::convex_polygon(SD_right, ColorQualifier(target_muscle.contains(&Muscle::SideDelt)), border));
 
@@ -154,8 +215,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let BIC_right_bot_r = Pos2::new(BIC_right_top_r.x, BIC_right_top_r.y + 31.0 * scale);
     let BIC_right = vec![BIC_left_top_r, BIC_right_top_r, BIC_right_bot_r, BIC_left_bot_r, BIC_left_med2_r, BIC_left_med1_r];
 
-    painter.add(Shape::convex_polygon(BIC_left, ColorQualifier(Muscle::Biceps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(BIC_right, ColorQualifier(Muscle::Biceps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(BIC_left, Muscle::Biceps);
+    shapes.fill(BIC_right, Muscle::Biceps);
     // painter.add(Shape::convex_polygon(BIC_left, ColorQualifier(target_muscle.contains(&Muscle::Biceps)), border));
     // painter.add(Shape::convex_polygon(BIC_right, ColorQualifier(target_muscle.contains(&Muscle::Biceps)), border));
 
@@ -170,8 +231,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let TRI_mid_r = Pos2::new(TRI_top_r.x + 5.0 * scale, (TRI_top_r.y + TRI_bot_r.y) / 2.0);
     let TRI_right = vec![TRI_top_r, TRI_bot_r, TRI_mid_r];
 
-    painter.add(Shape::convex_polygon(TRI_left, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(TRI_right, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(TRI_left, Muscle::Triceps);
+    shapes.fill(TRI_right, Muscle::Triceps);
     // painter.add(Shape::convex_polygon(TRI_left, ColorQualifier(target_muscle.contains(&Muscle::Triceps)), border));
     // painter.add(Shape::convex_polygon(TRI_right, ColorQualifier(target_muscle.contains(&Muscle::Triceps)), border));
 
@@ -202,10 +263,10 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let F_out_right_bot_r = Pos2::new(F_out_left_bot_r.x + 2.0 * scale, F_out_left_bot_r.y);
     let F_out_right = vec![F_out_right_top_r, F_out_left_top_r, F_out_left_bot_r, F_out_right_bot_r];
 
-    painter.add(Shape::convex_polygon(F_in_left, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_in_right, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_out_left, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_out_right, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(F_in_left, Muscle::Forearms);
+    shapes.fill(F_in_right, Muscle::Forearms);
+    shapes.fill(F_out_left, Muscle::Forearms);
+    shapes.fill(F_out_right, Muscle::Forearms);
     // painter.add(Shape::convex_polygon(F_in_left, ColorQualifier(target_muscle.contains(&Muscle::Forearms)), border));
     // painter.add(Shape::convex_polygon(F_in_right, ColorQualifier(target_muscle.contains(&Muscle::Forearms)), border));
     // painter.add(Shape::convex_polygon(F_out_left, ColorQualifier(target_muscle.contains(&Muscle::Forearms)), border));
@@ -238,8 +299,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let SA_bot_r = Pos2::new(SA_left_top_r.x, SA_left_top_r.y + 50.0 * scale);
     let SA_right = vec![SA_left_top_r, SA_right_top_r, SA_bot_r];
 
-    painter.add(Shape::convex_polygon(SA_left, ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(SA_right, ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(SA_left, Muscle::Abs);
+    shapes.fill(SA_right, Muscle::Abs);
 
     //ABS
     let abs_width = 15.0 * scale;
@@ -260,7 +321,7 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
         painter.rect_filled(
             abs_to_draw,
             0.0, 
-            ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles),
+            shading.color(Muscle::Abs),
         );
 
         abs_to_draw = egui::Rect::from_min_max(abs_min_r, abs_max_r);
@@ -268,7 +329,7 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
         painter.rect_filled(
             abs_to_draw,
             0.0, 
-            ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles),
+            shading.color(Muscle::Abs),
             // Color32::GRAY,
         );
 
@@ -291,8 +352,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let BA_left_bot_r = Pos2::new(BA_left_top_r.x, BA_left_top_r.y + 25.0 * scale);
     let BA_right = vec![BA_left_top_r, BA_right_top_r, BA_right_bot_r, BA_left_bot_r];
 
-    painter.add(Shape::convex_polygon(BA_left, ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(BA_right, ColorQualifier(Muscle::Abs, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(BA_left, Muscle::Abs);
+    shapes.fill(BA_right, Muscle::Abs);
     // painter.add(Shape::convex_polygon(BA_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(BA_right, muscle_color, border));
 
@@ -307,8 +368,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let HP_bot_r = Pos2::new(HP_right_top_r.x + 8.0 * scale, HP_right_top_r.y + 15.0 * scale);
     let HP_right = vec![HP_right_top_r, HP_left_top_r, HP_bot_r];
 
-    painter.add(Shape::convex_polygon(HP_left, ColorQualifier(Muscle::Hips, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(HP_right, ColorQualifier(Muscle::Hips, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(HP_left, Muscle::Hips);
+    shapes.fill(HP_right, Muscle::Hips);
 
     //ADDUCTORS
     let AD_left_top_l= Pos2::new(HP_right_top_l.x + offset, HP_right_top_l.y + 0.3 * scale);
@@ -321,8 +382,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let AD_bot_r = Pos2::new(AD_left_top_r.x, AD_left_top_r.y + 10.0 * scale);
     let AD_right = vec![AD_right_top_r, AD_left_top_r, AD_bot_r];
 
-    painter.add(Shape::convex_polygon(AD_left, ColorQualifier(Muscle::Adductors, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(AD_right, ColorQualifier(Muscle::Adductors, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(AD_left, Muscle::Adductors);
+    shapes.fill(AD_right, Muscle::Adductors);
     // painter.add(Shape::convex_polygon(AD_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(AD_right, muscle_color, border));
 
@@ -341,8 +402,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let QU_right_mid_r = Pos2::new(HP_bot_r.x, HP_bot_r.y + offset);
     let QU_right = vec![QU_top_r, QU_left_mid_r, QU_left_bot_r, QU_right_bot_r, QU_right_mid_r];
 
-    painter.add(Shape::convex_polygon(QU_left, ColorQualifier(Muscle::Quads, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(QU_right, ColorQualifier(Muscle::Quads, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(QU_left, Muscle::Quads);
+    shapes.fill(QU_right, Muscle::Quads);
     // painter.add(Shape::convex_polygon(QU_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(QU_right, muscle_color, border));
 
@@ -357,8 +418,8 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let EH_right_bot_r = Pos2::new(EH_left_bot_r.x + 5.0 * scale, EH_left_bot_r.y - 10.0 * scale);
     let EH_right = vec![EH_top_r, EH_left_bot_r, EH_right_bot_r];
 
-    painter.add(Shape::convex_polygon(EH_left, ColorQualifier(Muscle::ExtHips, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(EH_right, ColorQualifier(Muscle::ExtHips, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(EH_left, Muscle::ExtHips);
+    shapes.fill(EH_right, Muscle::ExtHips);
     // painter.add(Shape::convex_polygon(EH_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(EH_right, muscle_color, border));
 
@@ -412,10 +473,10 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
     let CF_in_right_bot_r = Pos2::new(CF_in_left_bot_r.x + 5.0 * scale, CF_in_left_bot_r.y);
     let CF_in_right = vec![CF_in_right_top_r, CF_in_left_top_r, CF_in_left_mid_r, CF_in_left_bot_r, CF_in_right_bot_r];
 
-    painter.add(Shape::convex_polygon(CF_out_left, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(CF_out_right, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(CF_in_left, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(CF_in_right, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(CF_out_left, Muscle::Calfs);
+    shapes.fill(CF_out_right, Muscle::Calfs);
+    shapes.fill(CF_in_left, Muscle::Calfs);
+    shapes.fill(CF_in_right, Muscle::Calfs);
     // painter.add(Shape::convex_polygon(CF_out_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(CF_out_right, muscle_color, border));
     // painter.add(Shape::convex_polygon(CF_in_left, muscle_color, border));
@@ -441,12 +502,12 @@ pub fn workout_tracker_widget_front(ctx: &egui::Context, ui: &mut egui::Ui, size
         0.0, 
         Color32::GRAY,
     );
-}
 
-pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, size: Vec2, exercises: &Vec<Exercises>) {
-    let (primary_muscles, secondary_muscles) = muscle_for_workout(exercises);
+    shapes.clicked
+}
 
-    let (rect, _response) = ui.allocate_exact_size(size, egui::Sense::hover());
+pub fn body_map_behind(ctx: &egui::Context, ui: &mut egui::Ui, size: Vec2, shading: &MuscleShading) -> Option<Muscle> {
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
     let default_size = Vec2::new(120.0, 270.0);
     let scale = (size.x * size.y) / (default_size.x * default_size.y);
 
@@ -455,6 +516,7 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let center = rect.center() - vec2(0.0, 120.0 * scale);
     let muscle_color = Color32::GRAY;
     let border = Stroke::new(0.0, Color32::GRAY);
+    let mut shapes = MuscleShapes { painter: &painter, shading, border, click: response.clicked().then(|| response.interact_pointer_pos()).flatten(), clicked: None };
     let offset = 1.5 * scale;
 
     painter.circle_filled(
@@ -478,8 +540,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let TT_right_bot_r = Pos2::new(TT_left_bot_r.x + top_trap_width, TT_left_bot_r.y);
     let TT_right = vec![TT_top_r, TT_left_bot_r, TT_right_bot_r];
 
-    painter.add(Shape::convex_polygon(TT_left, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(TT_right, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(TT_left, Muscle::Traps);
+    shapes.fill(TT_right, Muscle::Traps);
     // painter.add(Shape::convex_polygon(TT_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(TT_right, muscle_color, border));
 
@@ -496,8 +558,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let T_bot_r = Pos2::new(T_left_top_r.x, T_left_top_r.y + trap_height);
     let T_right = vec![T_right_top_r, T_left_top_r, T_bot_r];
 
-    painter.add(Shape::convex_polygon(T_left, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(T_right, ColorQualifier(Muscle::Traps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(T_left, Muscle::Traps);
+    shapes.fill(T_right, Muscle::Traps);
     // painter.add(Shape::convex_polygon(T_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(T_right, muscle_color, border));
 
@@ -515,8 +577,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let I_left_bot_r = Pos2::new(I_top_r.x - infraspinatus_width, I_right_bot_r.y);
     let I_right = vec![I_top_r, I_right_bot_r, I_left_bot_r];
 
-    painter.add(Shape::convex_polygon(I_left, ColorQualifier(Muscle::Infraspinatus, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(I_right, ColorQualifier(Muscle::Infraspinatus, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(I_left, Muscle::Infraspinatus);
+    shapes.fill(I_right, Muscle::Infraspinatus);
     // painter.add(Shape::convex_polygon(I_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(I_right, muscle_color, border));
 
@@ -535,8 +597,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let RD_right_bot_r = Pos2::new(RD_right_top_r.x + offset * 4.0, RD_left_bot_r.y - offset * 1.4);
     let RD_right = vec![RD_left_top_r, RD_right_top_r, RD_right_bot_r, RD_left_bot_r];
 
-    painter.add(Shape::convex_polygon(RD_left, ColorQualifier(Muscle::RearDelt, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(RD_right, ColorQualifier(Muscle::RearDelt, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(RD_left, Muscle::RearDelt);
+    shapes.fill(RD_right, Muscle::RearDelt);
     // painter.add(Shape::convex_polygon(RD_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(RD_right, muscle_color, border));
 
@@ -555,8 +617,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let SD_right_bot_r = Pos2::new(SD_left_bot_r.x + side_delt_width, SD_left_bot_r.y - offset * 1.4);
     let SD_right = vec![SD_left_top_r, SD_left_bot_r, SD_right_bot_r, SD_right_top_r];
 
-    painter.add(Shape::convex_polygon(SD_left, ColorQualifier(Muscle::SideDelt, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(SD_right, ColorQualifier(Muscle::SideDelt, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(SD_left, Muscle::SideDelt);
+    shapes.fill(SD_right, Muscle::SideDelt);
     // painter.add(Shape::convex_polygon(SD_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(SD_right, muscle_color, border));
 
@@ -576,8 +638,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let OT_right_bot_r = Pos2::new(OT_right_top_r.x + 9.7 * scale, OT_right_top_r.y + outer_triceps_height);
     let OT_right = vec![OT_left_top_r, OT_right_top_r, OT_right_bot_r, OT_left_bot_r];
 
-    painter.add(Shape::convex_polygon(OT_left, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(OT_right, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(OT_left, Muscle::Triceps);
+    shapes.fill(OT_right, Muscle::Triceps);
     // painter.add(Shape::convex_polygon(OT_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(OT_right, muscle_color, border));
 
@@ -597,8 +659,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let IT_right_bot_r = Pos2::new(IT_right_top_r.x, IT_right_top_r.y + inner_triceps_height);
     let IT_right= vec![IT_left_top_r, IT_right_top_r, IT_right_bot_r, IT_left_bot_r];
 
-    painter.add(Shape::convex_polygon(IT_left, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(IT_right, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(IT_left, Muscle::Triceps);
+    shapes.fill(IT_right, Muscle::Triceps);
     // painter.add(Shape::convex_polygon(IT_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(IT_right, muscle_color, border));
 
@@ -620,8 +682,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let MT_right_mid_r = Pos2::new(MT_right_bot_r.x, MT_right_bot_r.y - 4.0 * scale);
     let MT_right = vec![MT_top_r, MT_right_mid_r, MT_right_bot_r, MT_left_bot_r, MT_left_mid_r];
 
-    painter.add(Shape::convex_polygon(MT_left, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(MT_right, ColorQualifier(Muscle::Triceps, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(MT_left, Muscle::Triceps);
+    shapes.fill(MT_right, Muscle::Triceps);
     // painter.add(Shape::convex_polygon(MT_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(MT_right, muscle_color, border));
     
@@ -652,10 +714,10 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let F_out_right_bot_r = Pos2::new(F_out_left_bot_r.x + 2.0 * scale, F_out_left_bot_r.y);
     let F_out_right = vec![F_out_right_top_r, F_out_left_top_r, F_out_left_bot_r, F_out_right_bot_r];
 
-    painter.add(Shape::convex_polygon(F_in_left, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_in_right, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_out_left, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(F_out_right, ColorQualifier(Muscle::Forearms, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(F_in_left, Muscle::Forearms);
+    shapes.fill(F_in_right, Muscle::Forearms);
+    shapes.fill(F_out_left, Muscle::Forearms);
+    shapes.fill(F_out_right, Muscle::Forearms);
     // painter.add(Shape::convex_polygon(F_in_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(F_out_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(F_in_right, muscle_color, border));
@@ -692,8 +754,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let L_mid_r = Pos2::new(T_bot_r.x + offset, T_bot_r.y);
     let L_right = vec![L_top_left_r, L_top_right_r, L_bot_r, L_mid_r];
 
-    painter.add(Shape::convex_polygon(L_left, ColorQualifier(Muscle::Lats, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(L_right, ColorQualifier(Muscle::Lats, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(L_left, Muscle::Lats);
+    shapes.fill(L_right, Muscle::Lats);
     // painter.add(Shape::convex_polygon(L_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(L_right, muscle_color, border));
 
@@ -710,8 +772,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let LB_mid_r = Pos2::new(L_bot_r.x - offset, L_bot_r.y);
     let LB_right = vec![LB_top_r, LB_bot_r, LB_mid_r];
 
-    painter.add(Shape::convex_polygon(LB_left, ColorQualifier(Muscle::LowerBack, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(LB_right, ColorQualifier(Muscle::LowerBack, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(LB_left, Muscle::LowerBack);
+    shapes.fill(LB_right, Muscle::LowerBack);
     // painter.add(Shape::convex_polygon(LB_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(LB_right, muscle_color, border));
 
@@ -732,8 +794,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let G_right_bot_r = Pos2::new(G_right_top_r.x + 9.0 * scale, G_left_bot_r.y - 8.0 * scale);
     let G_right = vec![G_right_top_r, G_left_top_r, G_left_bot_r, G_mid_bot_r, G_right_bot_r];
 
-    painter.add(Shape::convex_polygon(G_left, ColorQualifier(Muscle::Glutes, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(G_right, ColorQualifier(Muscle::Glutes, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(G_left, Muscle::Glutes);
+    shapes.fill(G_right, Muscle::Glutes);
     // painter.add(Shape::convex_polygon(G_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(G_right, muscle_color, border));
 
@@ -750,8 +812,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let IH_right_top_r = Pos2::new(IH_bot_r.x, IH_bot_r.y - inner_hamstring_height * 0.86);
     let IH_right = vec![IH_right_top_r, IH_bot_r, IH_left_top_r];
 
-    painter.add(Shape::convex_polygon(IH_left, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(IH_right, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(IH_left, Muscle::Hamstrings);
+    shapes.fill(IH_right, Muscle::Hamstrings);
     // painter.add(Shape::convex_polygon(IH_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(IH_right, muscle_color, border));
 
@@ -772,8 +834,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let H_left_bot_r = Pos2::new(H_left_top_r.x, H_right_bot_r.y);
     let H_right = vec![H_right_top_r , H_mid_top_r, H_left_top_r, H_left_bot_r, H_right_bot_r];
 
-    painter.add(Shape::convex_polygon(H_left, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(H_right, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(H_left, Muscle::Hamstrings);
+    shapes.fill(H_right, Muscle::Hamstrings);
     // painter.add(Shape::convex_polygon(H_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(H_right, muscle_color, border));
 
@@ -790,8 +852,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let OH_bot_r = Pos2::new(H_right_bot_r.x + offset, OH_left_top_r.y + outer_hamstrings_height);
     let OH_right = vec![OH_right_top_r, OH_left_top_r, OH_bot_r];
 
-    painter.add(Shape::convex_polygon(OH_left, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(OH_right, ColorQualifier(Muscle::Hamstrings, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(OH_left, Muscle::Hamstrings);
+    shapes.fill(OH_right, Muscle::Hamstrings);
     // painter.add(Shape::convex_polygon(OH_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(OH_right, muscle_color, border));
 
@@ -812,8 +874,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let OC_right_mid_r = Pos2::new(OC_right_top_r.x + 4.0 * scale, OC_right_top_r.y + outer_calfs_height * 0.4);
     let OC_right = vec![OC_right_top_r, OC_left_top_r, OC_left_bot_r, OC_right_bot_r, OC_right_mid_r];
 
-    painter.add(Shape::convex_polygon(OC_left, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(OC_right, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(OC_left, Muscle::Calfs);
+    shapes.fill(OC_right, Muscle::Calfs);
     // painter.add(Shape::convex_polygon(OC_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(OC_right, muscle_color, border));
 
@@ -832,8 +894,8 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     let IC_right_bot_r = Pos2::new(IC_right_top_r.x,OC_left_bot_r.y);
     let IC_right = vec![IC_left_top_r, IC_left_mid_r, IC_left_bot_r, IC_right_bot_r, IC_right_top_r];
 
-    painter.add(Shape::convex_polygon(IC_left, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
-    painter.add(Shape::convex_polygon(IC_right, ColorQualifier(Muscle::Calfs, &primary_muscles, &secondary_muscles), border));
+    shapes.fill(IC_left, Muscle::Calfs);
+    shapes.fill(IC_right, Muscle::Calfs);
     // painter.add(Shape::convex_polygon(IC_left, muscle_color, border));
     // painter.add(Shape::convex_polygon(IC_right, muscle_color, border));
 
@@ -868,6 +930,7 @@ pub fn workout_tracker_widget_behind(ctx: &egui::Context, ui: &mut egui::Ui, siz
     // let SD_right_bot_r = Pos2::new(SD_left_bot_r.x + side_delt_width, SD_left_bot_r.y - offset);
     // let SD_right = vec![SD_top_r, SD_left_bot_r, SD_right_bot_r];
 
+    shapes.clicked
 }
 
 pub fn ColorQualifier(muscle: Muscle, primary_muscles: &Vec<Muscle>, secondary_muscles: &Vec<Muscle>) -> Color32 {
@@ -879,4 +942,17 @@ pub fn ColorQualifier(muscle: Muscle, primary_muscles: &Vec<Muscle>, secondary_m
     } else {
         Color32::GRAY
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_land_inside_the_polygon() {
+        let triangle = [Pos2::new(0.0, 0.0), Pos2::new(10.0, 0.0), Pos2::new(0.0, 10.0)];
+        assert!(contains(&triangle, Pos2::new(2.0, 2.0)));
+        assert!(!contains(&triangle, Pos2::new(8.0, 8.0)));
+        assert!(!contains(&[], Pos2::new(0.0, 0.0)));
+    }
+}
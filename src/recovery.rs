@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use chrono::{NaiveDateTime, NaiveTime};
+use eframe::egui::Color32;
+
+use crate::models::{AllWorkoutData, CardioKind, Muscle, muscles_for};
+
+// this many hard sets still inside their window paint a muscle fully fatigued
+const SATURATION_SETS: f32 = 10.0;
+// cardio counts one set for every quarter hour
+const CARDIO_MINUTES_PER_SET: f32 = 15.0;
+
+// large muscles take about three days to recover, small ones about two
+pub fn recovery_hours(muscle: &Muscle) -> f32 {
+    match muscle {
+        Muscle::Quads | Muscle::Hamstrings | Muscle::Glutes | Muscle::LowerBack | Muscle::Lats
+        | Muscle::UpperChest | Muscle::LowerChest | Muscle::Adductors | Muscle::Hips | Muscle::ExtHips => 72.0,
+        _ => 48.0,
+    }
+}
+
+fn cardio_muscles(kind: &CardioKind) -> Vec<Muscle> {
+    match kind {
+        CardioKind::Running | CardioKind::Walking => vec![Muscle::Quads, Muscle::Hamstrings, Muscle::Calfs],
+        CardioKind::Cycling => vec![Muscle::Quads, Muscle::Glutes],
+        CardioKind::Rowing => vec![Muscle::Lats, Muscle::LowerBack, Muscle::Quads],
+        CardioKind::Swimming => vec![Muscle::Lats, Muscle::FrontDelt, Muscle::SideDelt],
+    }
+}
+
+// fatigue from 0 (fresh) to 1, each set fades out linearly over the muscle's recovery window.
+// sessions only carry a date, so they are taken to have happened at noon. one logged today
+// counts from now even before noon, anything dated after today has not happened yet
+pub fn fatigue(data: &AllWorkoutData, now: NaiveDateTime) -> HashMap<Muscle, f32> {
+    let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default();
+    let share = data.landmarks.secondary_share;
+    let mut load: HashMap<Muscle, f32> = HashMap::new();
+    let mut add = |muscle: &Muscle, sets: f32, hours: f32| {
+        let left = 1.0 - hours.max(0.0) / recovery_hours(muscle);
+        if left > 0.0 {
+            *load.entry(muscle.clone()).or_default() += sets * left;
+        }
+    };
+
+    for workout in data.workouts.iter() {
+        let hours = (now - workout.date.and_time(noon)).num_minutes() as f32 / 60.0;
+        if workout.date > now.date() || hours > 72.0 {
+            continue;
+        }
+        for done in workout.exercises.iter() {
+            let sets = done.set_count() as f32;
+            let (primary, secondary) = muscles_for(&done.exercise);
+            for muscle in primary.iter() {
+                add(muscle, sets, hours);
+            }
+            for muscle in secondary.iter() {
+                add(muscle, sets * share, hours);
+            }
+        }
+    }
+
+    for session in data.cardio.iter() {
+        let hours = (now - session.date.and_time(noon)).num_minutes() as f32 / 60.0;
+        if session.date > now.date() || hours > 72.0 {
+            continue;
+        }
+        let sets = session.active_secs() as f32 / 60.0 / CARDIO_MINUTES_PER_SET;
+        for muscle in cardio_muscles(&session.kind).iter() {
+            add(muscle, sets, hours);
+        }
+    }
+
+    load.into_iter().map(|(muscle, sets)| (muscle, (sets / SATURATION_SETS).min(1.0))).collect()
+}
+
+// how ready a set of muscles is, 1 when all of them are fresh
+pub fn readiness(fatigue: &HashMap<Muscle, f32>, muscles: &[Muscle]) -> f32 {
+    if muscles.is_empty() {
+        return 1.0;
+    }
+    1.0 - muscles.iter().map(|muscle| fatigue.get(muscle).copied().unwrap_or(0.0)).sum::<f32>() / muscles.len() as f32
+}
+
+// green when fresh through yellow to red when fatigued
+pub fn fatigue_color(fatigue: f32) -> Color32 {
+    let fresh = [46.0, 160.0, 67.0];
+    let middle = [230.0, 190.0, 40.0];
+    let tired = [200.0, 30.0, 30.0];
+    let (from, to, t) = if fatigue < 0.5 { (fresh, middle, fatigue * 2.0) } else { (middle, tired, (fatigue - 0.5) * 2.0) };
+    let mix = |channel: usize| (from[channel] + (to[channel] - from[channel]) * t.clamp(0.0, 1.0)) as u8;
+    Color32::from_rgb(mix(0), mix(1), mix(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::models::{ExerciseDone, Exercises, SetDone, WorkoutDone};
+
+    fn benched(date: NaiveDate, sets: usize) -> AllWorkoutData {
+        let mut data = AllWorkoutData::default();
+        let done = ExerciseDone { exercise: Exercises::BenchPress, sets: vec![SetDone { weight: 60.0, reps: 8, ..SetDone::default() }; sets], notes: String::new() };
+        data.workouts.push(WorkoutDone { date, exercises: vec![done], ..WorkoutDone::default() });
+        data
+    }
+
+    #[test]
+    fn fatigue_fades_over_the_recovery_window() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let data = benched(date, 5);
+        let at = |hours: i64| date.and_hms_opt(12, 0, 0).unwrap() + chrono::Duration::hours(hours);
+
+        assert_eq!(fatigue(&data, at(0))[&Muscle::LowerChest], 0.5);
+        assert_eq!(fatigue(&data, at(36))[&Muscle::LowerChest], 0.25);
+        assert!(!fatigue(&data, at(72)).contains_key(&Muscle::LowerChest));
+        // triceps are only secondary and small, so they recover within two days
+        assert_eq!(fatigue(&data, at(0))[&Muscle::Triceps], 0.25);
+        assert!(!fatigue(&data, at(48)).contains_key(&Muscle::Triceps));
+    }
+
+    #[test]
+    fn workouts_after_today_add_no_fatigue() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let data = benched(date, 5);
+        assert!(fatigue(&data, (date - chrono::Duration::days(1)).and_hms_opt(20, 0, 0).unwrap()).is_empty());
+        // earlier the same day still counts in full
+        assert_eq!(fatigue(&data, date.and_hms_opt(8, 0, 0).unwrap())[&Muscle::LowerChest], 0.5);
+    }
+
+    #[test]
+    fn secondary_muscles_use_the_landmark_share() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut data = benched(date, 4);
+        data.landmarks.secondary_share = 0.25;
+        let fatigue = fatigue(&data, date.and_hms_opt(12, 0, 0).unwrap());
+        assert_eq!(fatigue[&Muscle::Triceps], 0.1);
+        assert_eq!(readiness(&fatigue, &[Muscle::LowerChest, Muscle::Quads]), 0.8);
+    }
+}
@@ -0,0 +1,314 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::models::{AllWorkoutData, ExerciseGroup, ExerciseSlot, Exercises, Prescription, Program, WorkoutTemplate};
+
+pub const FORMAT_VERSION: u32 = 1;
+
+// the file layout is documented in README.md, keep both in sync
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct TemplateFile {
+    pub version: u32,
+    pub templates: Vec<TemplateEntry>,
+    pub programs: Vec<Program>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct TemplateEntry {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub exercises: Vec<ExerciseEntry>,
+    pub groups: Vec<ExerciseGroup>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct ExerciseEntry {
+    pub exercise: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prescription: Option<Prescription>,
+}
+
+impl TemplateEntry {
+    pub fn from_template(template: &WorkoutTemplate) -> Self {
+        Self {
+            name: template.workout_name.clone(),
+            tags: template.tags.clone(),
+            exercises: template.exercises
+                .iter()
+                .enumerate()
+                .map(|(index, exercise)| {
+                    let slot = template.slot(index);
+                    ExerciseEntry {
+                        exercise: exercise.to_string(),
+                        group: slot.group,
+                        prescription: slot.prescription,
+                    }
+                })
+                .collect(),
+            groups: template.groups.clone(),
+        }
+    }
+
+    // unknown exercises are left out and reported back
+    pub fn to_template(&self) -> (WorkoutTemplate, Vec<String>) {
+        let mut template = WorkoutTemplate::new(self.name.trim().to_string(), Vec::new());
+        let mut unknown = Vec::new();
+
+        for entry in self.exercises.iter() {
+            match Exercises::from_name(&entry.exercise) {
+                Some(exercise) => {
+                    template.exercises.push(exercise);
+                    template.slots.push(ExerciseSlot { group: entry.group, prescription: entry.prescription.clone() });
+                }
+                None => unknown.push(format!("{}: unknown exercise \"{}\"", self.name, entry.exercise)),
+            }
+        }
+
+        let used: Vec<u32> = template.slots.iter().filter_map(|slot| slot.group).collect();
+        template.groups = self.groups.iter().filter(|group| used.contains(&group.id)).cloned().collect();
+        for slot in template.slots.iter_mut() {
+            if slot.group.is_some_and(|id| !template.groups.iter().any(|group| group.id == id)) {
+                slot.group = None;
+            }
+        }
+        for tag in self.tags.iter() {
+            template.add_tag(tag);
+        }
+
+        (template, unknown)
+    }
+}
+
+// exporting a program also exports every template it points at
+pub fn export(data: &AllWorkoutData, templates: &[String], programs: &[String]) -> TemplateFile {
+    let programs: Vec<Program> = programs.iter().filter_map(|name| data.programs.get(name).cloned()).collect();
+
+    let mut names: Vec<String> = templates.to_vec();
+    names.extend(programs.iter().flat_map(|program| program.templates()));
+    names.sort();
+    names.dedup();
+
+    TemplateFile {
+        version: FORMAT_VERSION,
+        templates: names.iter().filter_map(|name| data.workout_templates.get(name)).map(TemplateEntry::from_template).collect(),
+        programs,
+    }
+}
+
+pub fn export_to_file(data: &AllWorkoutData, templates: &[String], programs: &[String], path: &str) -> Result<usize, String> {
+    let file = export(data, templates, programs);
+    let text = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fs::write(Path::new(path.trim()), text).map_err(|e| format!("could not write {}: {}", path.trim(), e))?;
+    Ok(file.templates.len() + file.programs.len())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum CollisionPolicy {
+    KeepBoth,
+    Replace,
+    Skip,
+}
+
+impl fmt::Display for CollisionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CollisionPolicy::KeepBoth => "keep both",
+            CollisionPolicy::Replace => "replace",
+            CollisionPolicy::Skip => "skip",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct ImportPreview {
+    pub templates: Vec<WorkoutTemplate>,
+    pub programs: Vec<Program>,
+    pub problems: Vec<String>,
+    // template and program names that already exist
+    pub collisions: Vec<String>,
+}
+
+impl ImportPreview {
+    pub fn parse(text: &str, data: &AllWorkoutData) -> Result<Self, String> {
+        let file: TemplateFile = serde_json::from_str(text).map_err(|e| format!("not a valid template file: {}", e))?;
+        if file.version > FORMAT_VERSION {
+            return Err(format!("file version {} is newer than supported version {}", file.version, FORMAT_VERSION));
+        }
+
+        let mut preview = Self::default();
+        for entry in file.templates.iter() {
+            if entry.name.trim().is_empty() {
+                preview.problems.push(String::from("a template without a name was skipped"));
+                continue;
+            }
+            let (template, unknown) = entry.to_template();
+            preview.problems.extend(unknown);
+            if template.exercises.is_empty() {
+                preview.problems.push(format!("{}: no known exercises, not imported", template.workout_name));
+                continue;
+            }
+            if data.workout_templates.contains_key(&template.workout_name) {
+                preview.collisions.push(template.workout_name.clone());
+            }
+            preview.templates.push(template);
+        }
+
+        for program in file.programs {
+            // a program is only imported when every day has a template to point at
+            let mut complete = true;
+            for name in program.templates() {
+                let known = preview.templates.iter().any(|template| template.workout_name == name) || data.workout_templates.contains_key(&name);
+                if !known {
+                    preview.problems.push(format!("{}: uses missing template \"{}\", not imported", program.name, name));
+                    complete = false;
+                }
+            }
+            if !complete {
+                continue;
+            }
+            if data.programs.contains_key(&program.name) {
+                preview.collisions.push(program.name.clone());
+            }
+            preview.programs.push(program);
+        }
+
+        Ok(preview)
+    }
+
+    pub fn load(path: &str, data: &AllWorkoutData) -> Result<Self, String> {
+        let text = fs::read_to_string(Path::new(path.trim())).map_err(|e| format!("could not read {}: {}", path.trim(), e))?;
+        Self::parse(&text, data)
+    }
+
+    // returns how many templates and programs were added or replaced
+    pub fn apply(&self, data: &mut AllWorkoutData, policy: CollisionPolicy) -> usize {
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        let mut count = 0;
+
+        for template in self.templates.iter() {
+            let mut template = template.clone();
+            if data.workout_templates.contains_key(&template.workout_name) {
+                match policy {
+                    CollisionPolicy::Skip => continue,
+                    CollisionPolicy::Replace => {}
+                    CollisionPolicy::KeepBoth => {
+                        let name = free_name(&template.workout_name, |name| data.workout_templates.contains_key(name));
+                        renamed.insert(template.workout_name.clone(), name.clone());
+                        template.workout_name = name;
+                    }
+                }
+            }
+            data.workout_templates.insert(template.workout_name.clone(), template);
+            count += 1;
+        }
+
+        for program in self.programs.iter() {
+            let mut program = program.clone();
+            for day in program.weeks.iter_mut().flat_map(|week| week.days.iter_mut()) {
+                if let Some(name) = renamed.get(&day.template) {
+                    day.template = name.clone();
+                }
+            }
+            if data.programs.contains_key(&program.name) {
+                match policy {
+                    CollisionPolicy::Skip => continue,
+                    CollisionPolicy::Replace => {}
+                    CollisionPolicy::KeepBoth => program.name = free_name(&program.name, |name| data.programs.contains_key(name)),
+                }
+            }
+            data.programs.insert(program.name.clone(), program);
+            count += 1;
+        }
+
+        count
+    }
+}
+
+fn free_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut candidate = format!("{} (imported)", name);
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{} (imported {})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProgramDay;
+
+    fn library() -> AllWorkoutData {
+        let mut data = AllWorkoutData::default();
+        data.workout_templates.clear();
+        let mut template = WorkoutTemplate::new(String::from("push"), vec![Exercises::BenchPress, Exercises::TricepDips]);
+        template.add_tag("strength");
+        data.workout_templates.insert(template.workout_name.clone(), template);
+        let program = Program::new(String::from("block"), 2, vec![ProgramDay { day: 0, template: String::from("push") }]);
+        data.programs.insert(program.name.clone(), program);
+        data
+    }
+
+    fn exported(data: &AllWorkoutData) -> String {
+        serde_json::to_string(&export(data, &[], &[String::from("block")])).unwrap()
+    }
+
+    #[test]
+    fn programs_export_their_templates_and_collide_on_import() {
+        let data = library();
+        let preview = ImportPreview::parse(&exported(&data), &data).unwrap();
+        assert_eq!(preview.templates.len(), 1);
+        assert_eq!(preview.templates[0].exercises, vec![Exercises::BenchPress, Exercises::TricepDips]);
+        assert_eq!(preview.templates[0].tags, vec![String::from("strength")]);
+        assert_eq!(preview.collisions, vec![String::from("push"), String::from("block")]);
+        assert!(preview.problems.is_empty());
+    }
+
+    #[test]
+    fn keep_both_renames_and_repoints_program_days() {
+        let mut data = library();
+        let preview = ImportPreview::parse(&exported(&data), &data).unwrap();
+        assert_eq!(preview.apply(&mut data, CollisionPolicy::KeepBoth), 2);
+
+        assert!(data.workout_templates.contains_key("push (imported)"));
+        let program = &data.programs["block (imported)"];
+        assert!(program.weeks.iter().flat_map(|week| week.days.iter()).all(|day| day.template == "push (imported)"));
+        assert_eq!(data.programs["block"].weeks[0].days[0].template, "push");
+
+        preview.apply(&mut data, CollisionPolicy::KeepBoth);
+        assert!(data.workout_templates.contains_key("push (imported 2)"));
+    }
+
+    #[test]
+    fn skip_and_replace_leave_one_copy() {
+        let mut data = library();
+        let mut preview = ImportPreview::parse(&exported(&data), &data).unwrap();
+        assert_eq!(preview.apply(&mut data, CollisionPolicy::Skip), 0);
+
+        preview.templates[0].exercises.truncate(1);
+        assert_eq!(preview.apply(&mut data, CollisionPolicy::Replace), 2);
+        assert_eq!(data.workout_templates.len(), 1);
+        assert_eq!(data.workout_templates["push"].exercises, vec![Exercises::BenchPress]);
+    }
+
+    #[test]
+    fn bad_entries_are_reported() {
+        let data = library();
+        let text = r#"{"version": 1, "templates": [{"name": "odd", "exercises": [{"exercise": "Moon Squat"}]}, {"name": " "}],
+            "programs": [{"name": "other", "weeks": [{"days": [{"day": 0, "template": "odd"}]}]}]}"#;
+        let preview = ImportPreview::parse(text, &data).unwrap();
+        assert_eq!(preview.problems.len(), 4);
+        assert!(preview.templates.is_empty() && preview.programs.is_empty());
+
+        assert!(ImportPreview::parse(r#"{"version": 99}"#, &data).is_err());
+        assert!(ImportPreview::parse("not json", &data).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use strum::IntoEnumIterator;
+
+use crate::models::{Exercises, Muscle, UserDataPack, muscles_for};
+use crate::streaks::{Streak, StreakKind};
+use crate::levels::XpBreakdown;
+use crate::achievements::{self, Badge};
+use crate::load::LoadReport;
+use crate::recovery;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl DateRange {
+    pub fn all() -> Self {
+        Self { from: NaiveDate::MIN, to: NaiveDate::MAX }
+    }
+
+    pub fn day(date: NaiveDate) -> Self {
+        Self { from: date, to: date }
+    }
+
+    // weeks start on monday
+    pub fn week_of(date: NaiveDate) -> Self {
+        let from = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        Self { from, to: from + Duration::days(6) }
+    }
+
+    pub fn last_days(date: NaiveDate, days: i64) -> Self {
+        Self { from: date - Duration::days(days - 1), to: date }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    // days of the range that have already happened, used for daily averages
+    pub fn days_until(&self, today: NaiveDate) -> u32 {
+        let to = self.to.min(today);
+        if to < self.from { 0 } else { ((to - self.from).num_days() + 1) as u32 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Totals {
+    // planned days in the range, rest days left out
+    pub planned: u32,
+    pub workouts: u32,
+    pub volume: f32,
+    pub sets: u32,
+    pub reps: u32,
+    pub workout_mins: u32,
+    pub prs: u32,
+    pub exercises: Vec<Exercises>,
+    pub muscles: usize,
+    // hard sets per muscle, secondary muscles count a share of each set
+    pub muscle_sets: HashMap<Muscle, f32>,
+
+    pub cardio_sessions: u32,
+    pub cardio_secs: u32,
+    pub distance_km: f32,
+    pub calories_burned: u32,
+
+    pub meals: u32,
+    pub calories: u32,
+    pub protein: u32,
+    pub carb: u32,
+    pub fat: u32,
+
+    pub drinks: u32,
+    pub water: u32,
+    pub hydrated: u32,
+}
+
+impl Totals {
+    pub fn compute(data: &UserDataPack, range: DateRange) -> Self {
+        let mut totals = Self::default();
+        let body_weight = data.user_information.body_weight();
+        let share = data.all_workout_data.landmarks.secondary_share;
+
+        totals.planned = data.planned_workout_data.workouts
+            .iter()
+            .filter(|(date, _)| range.contains(**date))
+            .flat_map(|(_, planned)| planned.iter())
+            .filter(|planned| !planned.is_rest())
+            .count() as u32;
+
+        let prs = data.all_workout_data.prs(body_weight);
+        for (workout, prs) in data.all_workout_data.workouts.iter().zip(prs).filter(|(workout, _)| range.contains(workout.date)) {
+            totals.workouts += 1;
+            totals.workout_mins += workout.length;
+            totals.prs += prs.len() as u32;
+            for done in workout.exercises.iter() {
+                totals.volume += done.volume(body_weight);
+                totals.sets += done.set_count() as u32;
+                totals.reps += done.working_sets().map(|set| set.reps).sum::<u32>();
+                let sets = done.set_count() as f32;
+                let (primary, secondary) = muscles_for(&done.exercise);
+                for muscle in primary {
+                    *totals.muscle_sets.entry(muscle).or_default() += sets;
+                }
+                for muscle in secondary {
+                    *totals.muscle_sets.entry(muscle).or_default() += sets * share;
+                }
+                if !totals.exercises.contains(&done.exercise) {
+                    totals.exercises.push(done.exercise.clone());
+                }
+            }
+        }
+
+        totals.muscles = totals.muscle_sets.len();
+
+        for session in data.all_workout_data.cardio.iter().filter(|session| range.contains(session.date)) {
+            totals.cardio_sessions += 1;
+            totals.cardio_secs += session.active_secs();
+            totals.distance_km += session.distance_km;
+            totals.calories_burned += session.calories;
+        }
+
+        for (_, eats) in data.macro_data.meal_history.iter().filter(|(date, _)| range.contains(**date)) {
+            for eat in eats {
+                totals.meals += 1;
+                totals.calories += eat.meal.calory;
+                totals.protein += eat.meal.protein;
+                totals.carb += eat.meal.carb;
+                totals.fat += eat.meal.fat;
+            }
+        }
+
+        for (_, drinks) in data.water_data.water_history.iter().filter(|(date, _)| range.contains(**date)) {
+            for drink in drinks {
+                totals.drinks += 1;
+                totals.water += drink.beverage.amount;
+                totals.hydrated += drink.beverage.hydration_amount;
+            }
+        }
+
+        totals
+    }
+
+    pub fn muscle_percent(&self) -> u32 {
+        (self.muscles as f32 / Muscle::iter().count() as f32 * 100.0).round() as u32
+    }
+}
+
+// aggregates are cached per range and dropped whenever the data revision moves on
+#[derive(Debug, Default)]
+pub struct Stats {
+    revision: u64,
+    cache: HashMap<DateRange, Totals>,
+    streaks: HashMap<(StreakKind, NaiveDate), Streak>,
+    xp: Option<XpBreakdown>,
+    badges: Option<(NaiveDate, Vec<Badge>)>,
+    load: Option<(NaiveDate, LoadReport)>,
+    // fatigue fades by the minute, finer than that isn't worth recomputing for
+    fatigue: Option<(i64, HashMap<Muscle, f32>)>,
+}
+
+impl Stats {
+    fn invalidate(&mut self) {
+        self.cache.clear();
+        self.streaks.clear();
+        self.xp = None;
+        self.badges = None;
+        self.load = None;
+        self.fatigue = None;
+    }
+
+    fn refresh(&mut self, data: &UserDataPack) {
+        if data.revision != self.revision {
+            self.revision = data.revision;
+            self.invalidate();
+        }
+    }
+
+    pub fn streak(&mut self, data: &UserDataPack, kind: StreakKind, today: NaiveDate) -> Streak {
+        self.refresh(data);
+        self.streaks.entry((kind, today)).or_insert_with(|| Streak::compute(data, kind, today)).clone()
+    }
+
+    pub fn xp(&mut self, data: &UserDataPack) -> XpBreakdown {
+        self.refresh(data);
+        *self.xp.get_or_insert_with(|| data.account_data.xp_settings.breakdown(data))
+    }
+
+    pub fn badges(&mut self, data: &UserDataPack, today: NaiveDate) -> Vec<Badge> {
+        self.refresh(data);
+        if self.badges.as_ref().is_none_or(|(date, _)| *date != today) {
+            let plan = self.streak(data, StreakKind::Plan, today);
+            self.badges = Some((today, achievements::evaluate_all(data, &plan)));
+        }
+        self.badges.as_ref().map(|(_, badges)| badges.clone()).unwrap_or_default()
+    }
+
+    pub fn load(&mut self, data: &UserDataPack, today: NaiveDate) -> LoadReport {
+        self.refresh(data);
+        if self.load.as_ref().is_none_or(|(date, _)| *date != today) {
+            self.load = Some((today, data.all_workout_data.load.report(data, today)));
+        }
+        self.load.as_ref().map(|(_, report)| report.clone()).unwrap_or_default()
+    }
+
+    pub fn fatigue(&mut self, data: &UserDataPack, now: NaiveDateTime) -> HashMap<Muscle, f32> {
+        self.refresh(data);
+        let minute = now.and_utc().timestamp() / 60;
+        if self.fatigue.as_ref().is_none_or(|(at, _)| *at != minute) {
+            self.fatigue = Some((minute, recovery::fatigue(&data.all_workout_data, now)));
+        }
+        self.fatigue.as_ref().map(|(_, fatigue)| fatigue.clone()).unwrap_or_default()
+    }
+
+    pub fn totals(&mut self, data: &UserDataPack, range: DateRange) -> Totals {
+        self.refresh(data);
+        self.cache.entry(range).or_insert_with(|| Totals::compute(data, range)).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExerciseDone, SetDone, VolumeZone, WorkoutDone};
+
+    #[test]
+    fn week_starts_on_monday() {
+        let sunday = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        let week = DateRange::week_of(sunday);
+        assert_eq!(week.from, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+        assert_eq!(week.to, sunday);
+        assert_eq!(week.days_until(NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()), 2);
+    }
+
+    #[test]
+    fn touching_the_data_refreshes_the_totals() {
+        let mut data = UserDataPack::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let set = SetDone { weight: 50.0, reps: 10, ..SetDone::default() };
+        let done = ExerciseDone { exercise: Exercises::BenchPress, sets: vec![set], notes: String::new() };
+        data.all_workout_data.workouts.push(WorkoutDone { date, exercises: vec![done], ..WorkoutDone::default() });
+
+        let mut stats = Stats::default();
+        assert_eq!(stats.totals(&data, DateRange::day(date)).reps, 10);
+
+        data.all_workout_data.workouts[0].exercises[0].sets[0].reps = 12;
+        assert_eq!(stats.totals(&data, DateRange::day(date)).reps, 10);
+        data.touch();
+        assert_eq!(stats.totals(&data, DateRange::day(date)).reps, 12);
+    }
+
+    #[test]
+    fn secondary_muscles_get_a_share_of_each_set() {
+        let mut data = UserDataPack::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let sets = vec![SetDone { weight: 60.0, reps: 8, ..SetDone::default() }; 4];
+        let done = ExerciseDone { exercise: Exercises::BenchPress, sets, notes: String::new() };
+        data.all_workout_data.workouts = vec![WorkoutDone { date, exercises: vec![done], ..WorkoutDone::default() }];
+
+        let totals = Totals::compute(&data, DateRange::week_of(date));
+        assert_eq!(totals.muscle_sets[&Muscle::LowerChest], 4.0);
+        assert_eq!(totals.muscle_sets[&Muscle::Triceps], 2.0);
+        assert_eq!(totals.muscles, 3);
+
+        let landmarks = &data.all_workout_data.landmarks;
+        assert_eq!(landmarks.get(&Muscle::LowerChest).zone(4.0), VolumeZone::Maintenance);
+        assert_eq!(landmarks.get(&Muscle::LowerChest).zone(6.0), VolumeZone::Productive);
+        assert_eq!(landmarks.get(&Muscle::Triceps).zone(2.0), VolumeZone::Below);
+        assert_eq!(landmarks.get(&Muscle::LowerChest).zone(17.0), VolumeZone::Over);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, fmt};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::models::UserDataPack;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum StreakKind {
+    Plan,
+    Calories,
+    Water,
+}
+
+impl fmt::Display for StreakKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StreakKind::Plan => "plan",
+            StreakKind::Calories => "calories",
+            StreakKind::Water => "water",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayMark {
+    Hit,
+    // a missed day covered by a freeze token
+    Frozen,
+    Missed,
+    // nothing planned and nothing done, the streak carries over
+    Neutral,
+    // today, not met yet
+    Pending,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StreakSettings {
+    pub freezes_per_month: u32,
+    // how far off the calorie budget a day may land and still count, in percent
+    pub calorie_tolerance: u32,
+}
+
+impl Default for StreakSettings {
+    fn default() -> Self {
+        Self {
+            freezes_per_month: 2,
+            calorie_tolerance: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+    pub freezes_left: u32,
+    pub days: BTreeMap<NaiveDate, DayMark>,
+}
+
+impl Streak {
+    // walks the history day by day, so freezes are spent the same way every time it is recomputed
+    pub fn compute(data: &UserDataPack, kind: StreakKind, today: NaiveDate) -> Self {
+        let settings = &data.account_data.streak_settings;
+        let mut streak = Self { freezes_left: settings.freezes_per_month, ..Self::default() };
+        let Some(start) = first_day(data, kind) else {
+            return streak;
+        };
+
+        let mut run = 0;
+        let mut month = (start.year(), start.month());
+        let mut date = start;
+        while date <= today {
+            if (date.year(), date.month()) != month {
+                month = (date.year(), date.month());
+                streak.freezes_left = settings.freezes_per_month;
+            }
+
+            let mut mark = day_mark(data, kind, date, today);
+            if mark == DayMark::Missed && streak.freezes_left > 0 {
+                streak.freezes_left -= 1;
+                mark = DayMark::Frozen;
+            }
+
+            match mark {
+                DayMark::Hit => run += 1,
+                DayMark::Missed => run = 0,
+                _ => {}
+            }
+            streak.longest = streak.longest.max(run);
+            streak.days.insert(date, mark);
+            date += Duration::days(1);
+        }
+
+        streak.current = run;
+        streak
+    }
+}
+
+fn first_day(data: &UserDataPack, kind: StreakKind) -> Option<NaiveDate> {
+    match kind {
+        StreakKind::Plan => data.planned_workout_data.workouts.keys()
+            .chain(data.all_workout_data.workouts.iter().map(|workout| &workout.date))
+            .chain(data.all_workout_data.cardio.iter().map(|session| &session.date))
+            .min()
+            .copied(),
+        StreakKind::Calories => data.macro_data.meal_history.iter().filter(|(_, eats)| !eats.is_empty()).map(|(date, _)| *date).min(),
+        StreakKind::Water => data.water_data.water_history.iter().filter(|(_, drinks)| !drinks.is_empty()).map(|(date, _)| *date).min(),
+    }
+}
+
+fn day_mark(data: &UserDataPack, kind: StreakKind, date: NaiveDate, today: NaiveDate) -> DayMark {
+    let unmet = if date == today { DayMark::Pending } else { DayMark::Missed };
+
+    match kind {
+        StreakKind::Plan => {
+            let planned = data.planned_workout_data.workouts.get(&date).map(Vec::as_slice).unwrap_or_default();
+            let trained = data.all_workout_data.workouts.iter().any(|workout| workout.date == date)
+                || data.all_workout_data.cardio.iter().any(|session| session.date == date)
+                || planned.iter().any(|workout| workout.completed && !workout.is_rest());
+            let training_planned = planned.iter().any(|workout| !workout.is_rest());
+
+            if training_planned {
+                if trained { DayMark::Hit } else { unmet }
+            } else if !planned.is_empty() || trained {
+                // a planned rest day keeps the streak whether or not the user trains on it
+                DayMark::Hit
+            } else {
+                DayMark::Neutral
+            }
+        }
+        StreakKind::Calories => {
+            let eaten: u32 = data.macro_data.meal_history.get(&date).map_or(0, |eats| eats.iter().map(|eat| eat.meal.calory).sum());
+            let burned: u32 = if data.macro_data.count_burned {
+                data.all_workout_data.cardio.iter().filter(|session| session.date == date).map(|session| session.calories).sum()
+            } else {
+                0
+            };
+            let budget = data.macro_data.calory_goal + burned;
+            let tolerance = budget * data.account_data.streak_settings.calorie_tolerance / 100;
+            if eaten > 0 && eaten.abs_diff(budget) <= tolerance { DayMark::Hit } else { unmet }
+        }
+        StreakKind::Water => {
+            let drunk: u32 = data.water_data.water_history.get(&date).map_or(0, |drinks| drinks.iter().map(|drink| drink.beverage.amount).sum());
+            if drunk >= data.water_data.water_goal { DayMark::Hit } else { unmet }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CardioPlan, WorkoutDone, WorkoutPlanned, WorkoutPlannedData, WorkoutTemplate};
+
+    fn week_of_training(trained: &[i64]) -> (UserDataPack, NaiveDate) {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut data = UserDataPack::default();
+        data.planned_workout_data = WorkoutPlannedData { workouts: Default::default(), schedules: Vec::new() };
+        for offset in 0..7 {
+            let date = start + Duration::days(offset);
+            let _ = data.planned_workout_data.add_workout(date, WorkoutPlanned::new(WorkoutTemplate::new(String::from("push"), Vec::new()), date));
+        }
+        for offset in trained {
+            data.all_workout_data.workouts.push(WorkoutDone { date: start + Duration::days(*offset), ..WorkoutDone::default() });
+        }
+        (data, start)
+    }
+
+    #[test]
+    fn freezes_cover_missed_days_until_they_run_out() {
+        let (data, start) = week_of_training(&[0, 1, 2, 5]);
+        let streak = Streak::compute(&data, StreakKind::Plan, start + Duration::days(6));
+        assert_eq!((streak.current, streak.longest, streak.freezes_left), (4, 4, 0));
+        assert_eq!(streak.days[&(start + Duration::days(3))], DayMark::Frozen);
+        assert_eq!(streak.days[&(start + Duration::days(6))], DayMark::Pending);
+
+        let streak = Streak::compute(&data, StreakKind::Plan, start + Duration::days(8));
+        assert_eq!(streak.days[&(start + Duration::days(6))], DayMark::Missed);
+        assert_eq!(streak.days[&(start + Duration::days(7))], DayMark::Neutral);
+        assert_eq!((streak.current, streak.longest), (0, 4));
+    }
+
+    #[test]
+    fn rest_days_count_with_or_without_training_but_planned_cardio_is_due() {
+        let (mut data, start) = week_of_training(&[0, 1]);
+        let (rest, cardio) = (start + Duration::days(1), start + Duration::days(2));
+        data.planned_workout_data.workouts.insert(rest, vec![WorkoutPlanned::rest(rest)]);
+        data.planned_workout_data.workouts.insert(cardio, vec![WorkoutPlanned::cardio(CardioPlan::default(), cardio)]);
+        data.account_data.streak_settings.freezes_per_month = 0;
+
+        let streak = Streak::compute(&data, StreakKind::Plan, start + Duration::days(3));
+        assert_eq!(streak.days[&rest], DayMark::Hit);
+        assert_eq!(streak.days[&cardio], DayMark::Missed);
+        assert_eq!((streak.current, streak.longest), (0, 2));
+    }
+}
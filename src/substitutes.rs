@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+use crate::models::{Equipment, Exercises, Muscle, equipment_for, movement_for, muscles_for};
+
+// muscles decide most of the score, the movement pattern breaks ties between similar candidates
+const MUSCLE_SHARE: f32 = 0.7;
+const PATTERN_SHARE: f32 = 0.3;
+
+#[derive(Debug, Clone)]
+pub struct Substitute {
+    pub exercise: Exercises,
+    // 0 to 1, higher is a closer match
+    pub score: f32,
+    pub overlap: f32,
+    pub same_pattern: bool,
+    pub available: bool,
+}
+
+fn weights(exercise: &Exercises, secondary_share: f32) -> HashMap<Muscle, f32> {
+    let (primary, secondary) = muscles_for(exercise);
+    let mut weights: HashMap<Muscle, f32> = secondary.into_iter().map(|muscle| (muscle, secondary_share)).collect();
+    weights.extend(primary.into_iter().map(|muscle| (muscle, 1.0)));
+    weights
+}
+
+// weighted jaccard, a primary muscle matched only as secondary counts the secondary share
+pub fn muscle_overlap(a: &Exercises, b: &Exercises, secondary_share: f32) -> f32 {
+    let (a, b) = (weights(a, secondary_share), weights(b, secondary_share));
+    let mut shared = 0.0;
+    let mut union = 0.0;
+    for muscle in Muscle::iter() {
+        let (x, y) = (a.get(&muscle).copied().unwrap_or(0.0), b.get(&muscle).copied().unwrap_or(0.0));
+        shared += x.min(y);
+        union += x.max(y);
+    }
+    if union > 0.0 { shared / union } else { 0.0 }
+}
+
+// candidates that share no muscle are left out, available equipment is listed first
+pub fn substitutes(exercise: &Exercises, unavailable: &[Equipment], secondary_share: f32) -> Vec<Substitute> {
+    let pattern = movement_for(exercise);
+    let mut found: Vec<Substitute> = Exercises::iter()
+        .filter(|candidate| candidate != exercise)
+        .map(|candidate| {
+            let overlap = muscle_overlap(exercise, &candidate, secondary_share);
+            let same_pattern = movement_for(&candidate) == pattern;
+            let available = !unavailable.contains(&equipment_for(&candidate));
+            let score = overlap * MUSCLE_SHARE + if same_pattern { PATTERN_SHARE } else { 0.0 };
+            Substitute { exercise: candidate, score, overlap, same_pattern, available }
+        })
+        .filter(|substitute| substitute.overlap > 0.0)
+        .collect();
+
+    found.sort_by(|a, b| b.available.cmp(&a.available).then(b.score.total_cmp(&a.score)));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SECONDARY_SHARE;
+
+    #[test]
+    fn overlap_is_symmetric_and_full_for_the_same_exercise() {
+        assert_eq!(muscle_overlap(&Exercises::Squat, &Exercises::Squat, SECONDARY_SHARE), 1.0);
+        let ab = muscle_overlap(&Exercises::Squat, &Exercises::LegPress, SECONDARY_SHARE);
+        let ba = muscle_overlap(&Exercises::LegPress, &Exercises::Squat, SECONDARY_SHARE);
+        assert!(ab > 0.0 && ab <= 1.0);
+        assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn candidates_leave_out_the_exercise_and_unrelated_muscles() {
+        let found = substitutes(&Exercises::Squat, &[], SECONDARY_SHARE);
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|s| s.exercise != Exercises::Squat && s.overlap > 0.0));
+        assert!(found.windows(2).all(|w| w[0].score >= w[1].score));
+        // a squat pattern candidate outranks anything with a different pattern
+        assert!(found[0].same_pattern);
+    }
+
+    #[test]
+    fn available_equipment_is_listed_first() {
+        let found = substitutes(&Exercises::Squat, &[Equipment::Machine], SECONDARY_SHARE);
+        let first_missing = found.iter().position(|s| !s.available).unwrap();
+        assert!(found[first_missing..].iter().all(|s| !s.available));
+        assert!(found.iter().any(|s| s.exercise == Exercises::LegPress && !s.available));
+    }
+}
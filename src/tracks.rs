@@ -0,0 +1,227 @@
+use std::{fs, path::Path};
+use chrono::{DateTime, FixedOffset, Local};
+
+use crate::models::{CardioKind, CardioSession};
+
+// slower than this between two points counts as standing still
+const MOVING_SPEED: f64 = 0.5;
+// gaps longer than this are treated as a paused recording
+const MAX_GAP_SECS: i64 = 120;
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct TrackPoint {
+    // latitude and longitude, indoor tcx laps have none
+    pub position: Option<(f64, f64)>,
+    // running total the device measured itself, preferred over the gps path when present
+    pub distance: Option<f64>,
+    pub ele: Option<f64>,
+    pub time: Option<DateTime<FixedOffset>>,
+    pub hr: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Track {
+    pub kind: Option<CardioKind>,
+    pub points: Vec<TrackPoint>,
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<&'a str> {
+    node.children().find(|child| child.tag_name().name() == name).and_then(|child| child.text()).map(str::trim)
+}
+
+fn descendant_text<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<&'a str> {
+    node.descendants().find(|child| child.tag_name().name() == name).and_then(|child| child.text()).map(str::trim)
+}
+
+fn kind_from(name: &str) -> Option<CardioKind> {
+    match name.to_lowercase().as_str() {
+        "running" | "run" | "trail_running" => Some(CardioKind::Running),
+        "biking" | "cycling" | "ride" | "road_biking" | "mountain_biking" => Some(CardioKind::Cycling),
+        "rowing" => Some(CardioKind::Rowing),
+        "swimming" | "open_water_swimming" => Some(CardioKind::Swimming),
+        "walking" | "walk" | "hiking" => Some(CardioKind::Walking),
+        _ => None,
+    }
+}
+
+pub fn parse_gpx(text: &str) -> Result<Track, String> {
+    let document = roxmltree::Document::parse(text).map_err(|e| format!("not valid gpx: {}", e))?;
+    let kind = document.descendants().find(|node| node.tag_name().name() == "trk").and_then(|trk| child_text(trk, "type")).and_then(kind_from);
+
+    let points = document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "trkpt")
+        .filter_map(|node| {
+            Some(TrackPoint {
+                position: Some((node.attribute("lat")?.parse().ok()?, node.attribute("lon")?.parse().ok()?)),
+                distance: None,
+                ele: child_text(node, "ele").and_then(|ele| ele.parse().ok()),
+                time: child_text(node, "time").and_then(|time| DateTime::parse_from_rfc3339(time).ok()),
+                // garmin and most other watches put heart rate in a TrackPointExtension
+                hr: descendant_text(node, "hr").and_then(|hr| hr.parse().ok()),
+            })
+        })
+        .collect();
+
+    Ok(Track { kind, points })
+}
+
+pub fn parse_tcx(text: &str) -> Result<Track, String> {
+    let document = roxmltree::Document::parse(text).map_err(|e| format!("not valid tcx: {}", e))?;
+    let kind = document.descendants().find(|node| node.tag_name().name() == "Activity").and_then(|activity| activity.attribute("Sport")).and_then(kind_from);
+
+    let points = document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "Trackpoint")
+        .filter_map(|node| {
+            let position = node.children().find(|child| child.tag_name().name() == "Position").and_then(|position| {
+                Some((child_text(position, "LatitudeDegrees")?.parse().ok()?, child_text(position, "LongitudeDegrees")?.parse().ok()?))
+            });
+            let point = TrackPoint {
+                position,
+                distance: child_text(node, "DistanceMeters").and_then(|distance| distance.parse().ok()),
+                ele: child_text(node, "AltitudeMeters").and_then(|ele| ele.parse().ok()),
+                time: child_text(node, "Time").and_then(|time| DateTime::parse_from_rfc3339(time).ok()),
+                hr: node.children().find(|child| child.tag_name().name() == "HeartRateBpm").and_then(|hr| child_text(hr, "Value")).and_then(|hr| hr.parse().ok()),
+            };
+            // a point with neither a position, a distance nor a time carries nothing to measure
+            (point.position.is_some() || point.distance.is_some() || point.time.is_some()).then_some(point)
+        })
+        .collect();
+
+    Ok(Track { kind, points })
+}
+
+fn haversine(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().asin()
+}
+
+impl Track {
+    pub fn to_session(&self, fallback: CardioKind) -> Result<CardioSession, String> {
+        if self.points.len() < 2 {
+            return Err(String::from("the file has no track points"));
+        }
+
+        let start = self.points.iter().find_map(|point| point.time);
+        let end = self.points.iter().rev().find_map(|point| point.time);
+        let date = start.map_or(Local::now().date_naive(), |time| time.with_timezone(&Local).date_naive());
+
+        let mut session = CardioSession::new(self.kind.unwrap_or(fallback), date);
+        session.duration_secs = match (start, end) {
+            (Some(start), Some(end)) => (end - start).num_seconds().max(0) as u32,
+            _ => 0,
+        };
+
+        let mut distance = 0.0;
+        let mut moving = 0.0;
+        let mut climb = 0.0;
+        let mut split_start = 0.0;
+
+        for pair in self.points.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let step = match (a.distance, b.distance, a.position, b.position) {
+                (Some(from), Some(to), _, _) => (to - from).max(0.0),
+                (_, _, Some(from), Some(to)) => haversine(from, to),
+                _ => 0.0,
+            };
+            distance += step;
+
+            if let (Some(from), Some(to)) = (a.ele, b.ele) {
+                if to > from {
+                    climb += to - from;
+                }
+            }
+
+            if let (Some(from), Some(to)) = (a.time, b.time) {
+                let secs = (to - from).num_seconds();
+                if secs > 0 && secs <= MAX_GAP_SECS && step / secs as f64 >= MOVING_SPEED {
+                    moving += secs as f64;
+                }
+            }
+
+            // a split closes on the first point past each whole kilometre
+            if distance >= (session.splits.len() + 1) as f64 * 1000.0 {
+                session.splits.push((moving - split_start).round() as u32);
+                split_start = moving;
+            }
+        }
+
+        session.distance_km = (distance / 1000.0) as f32;
+        session.moving_secs = moving.round() as u32;
+        session.elevation_m = climb as f32;
+        if session.duration_secs == 0 {
+            session.duration_secs = session.moving_secs;
+        }
+
+        let rates: Vec<u32> = self.points.iter().filter_map(|point| point.hr).filter(|hr| *hr > 0).collect();
+        if !rates.is_empty() {
+            session.avg_hr = Some(rates.iter().sum::<u32>() / rates.len() as u32);
+            session.max_hr = rates.iter().max().copied();
+        }
+
+        session.route = self.points.iter().filter_map(|point| point.position).collect();
+        Ok(session)
+    }
+}
+
+// picks the parser from the extension, falling back to sniffing the root element
+pub fn import_file(path: &str, fallback: CardioKind) -> Result<CardioSession, String> {
+    let path = Path::new(path.trim());
+    let text = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_lowercase();
+
+    let track = match extension.as_str() {
+        "gpx" => parse_gpx(&text)?,
+        "tcx" => parse_tcx(&text)?,
+        _ if text.contains("<TrainingCenterDatabase") => parse_tcx(&text)?,
+        _ => parse_gpx(&text)?,
+    };
+
+    track.to_session(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpx_distance_comes_from_the_path() {
+        let gpx = r#"<gpx><trk><type>running</type><trkseg>
+            <trkpt lat="0.0" lon="0.0"><ele>10</ele><time>2025-03-03T08:00:00Z</time></trkpt>
+            <trkpt lat="0.0" lon="0.009"><ele>15</ele><time>2025-03-03T08:05:00Z</time></trkpt>
+        </trkseg></trk></gpx>"#;
+        let track = parse_gpx(gpx).unwrap();
+        assert_eq!(track.kind, Some(CardioKind::Running));
+
+        let session = track.to_session(CardioKind::Walking).unwrap();
+        assert!((session.distance_km - 1.0).abs() < 0.01);
+        assert_eq!(session.duration_secs, 300);
+        assert_eq!(session.elevation_m, 5.0);
+        assert_eq!(session.route.len(), 2);
+    }
+
+    #[test]
+    fn tcx_points_without_position_use_distance_meters() {
+        let tcx = r#"<TrainingCenterDatabase><Activities><Activity Sport="Biking"><Lap><Track>
+            <Trackpoint><Time>2025-03-03T08:00:00Z</Time><DistanceMeters>0</DistanceMeters><HeartRateBpm><Value>120</Value></HeartRateBpm></Trackpoint>
+            <Trackpoint><Time>2025-03-03T08:01:00Z</Time><DistanceMeters>600</DistanceMeters><HeartRateBpm><Value>140</Value></HeartRateBpm></Trackpoint>
+            <Trackpoint><Time>2025-03-03T08:02:00Z</Time><DistanceMeters>1500</DistanceMeters></Trackpoint>
+        </Track></Lap></Activity></Activities></TrainingCenterDatabase>"#;
+        let track = parse_tcx(tcx).unwrap();
+        assert_eq!(track.kind, Some(CardioKind::Cycling));
+        assert_eq!(track.points.len(), 3);
+
+        let session = track.to_session(CardioKind::Running).unwrap();
+        assert_eq!(session.distance_km, 1.5);
+        assert_eq!(session.duration_secs, 120);
+        assert_eq!(session.moving_secs, 120);
+        assert_eq!(session.splits.len(), 1);
+        assert_eq!(session.avg_hr, Some(130));
+        assert!(session.route.is_empty());
+    }
+}
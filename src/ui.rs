@@ -5,25 +5,126 @@ use time::{OffsetDateTime};
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
 use strum::IntoEnumIterator;
 
-use crate::models::{AppMedia, States, Summary, UserDataPack, WorkoutPlanned, WorkoutPlannedData, WorkoutTemplate, Muscle, Exercises};
-use crate::muscles::{workout_tracker_widget_front, workout_tracker_widget_behind};
+use crate::models::{AppMedia, States, Summary, UserDataPack, WorkoutPlanned, WorkoutPlannedData, WorkoutTemplate, Muscle, Exercises, WorkoutSession, SessionSet, SetDone, ProgressionRule, Program, ProgramDay, RecurrenceRule, GroupKind, TargetLoad, Equipment, WeightUnit, PlateCount, PlateInventory, WarmupStep, TrackingMode, tracking_for, Side, SideBalance, SessionExercise, is_unilateral, CardioKind, CardioPlan, CardioSession, equipment_for, MissedAction, muscle_for_workout, VolumeZone, movement_for, HistoryFilter};
+use crate::muscles::{workout_tracker_widget_front, workout_tracker_widget_behind, body_map_front, body_map_behind, MuscleShading};
+use crate::recovery;
+use crate::substitutes::substitutes;
+use crate::generator::{self, Experience, estimated_secs};
 use crate::tools::weekday_iso;
+use crate::sharing::{self, CollisionPolicy, ImportPreview};
+use crate::tracks;
+use crate::stats::{DateRange, Stats, Totals};
+use crate::streaks::{DayMark, Streak, StreakKind};
+use crate::levels::LevelProgress;
+use crate::achievements::Badge;
+use crate::load::{LoadMethod, LoadZone, CAUTION_RATIO, RISK_RATIO};
 
 const REMAINDER: f32 = 10.0;
+const LEVEL_UP_SECS: f64 = 2.5;
 
 pub struct Gui<'a> {
     pub datas: UserDataPack,
     pub medias: AppMedia<'a>,
     pub states: States,
+    pub stats: Stats,
 }
 
 impl Gui<'_> {
     pub fn init(ctx: &Context) -> Self {
-        Self {
+        let mut gui = Self {
             datas: UserDataPack::default(),
             medias: AppMedia::load_media(ctx),
             states: States::default(),
+            stats: Stats::default(),
+        };
+        gui.refresh_schedules();
+        gui
+    }
+
+    // recurring workouts are generated four weeks past today or the selected day, whichever is later
+    pub fn refresh_schedules(&mut self) {
+        let today = Local::now().date_naive();
+        let until = today.max(self.states.selected_day) + Duration::days(28);
+        self.datas.planned_workout_data.generate_occurrences(&self.datas.all_workout_data.workout_templates, today, until);
+        self.datas.touch();
+    }
+
+    // the account mirrors the plan streak, level and unlocked badges so they outlive deleted history,
+    // it is brought up to date once per data revision and day instead of from the drawing code
+    pub fn sync_account(&mut self, ctx: &Context) {
+        let today = Local::now().date_naive();
+        if self.states.account_synced == Some((self.datas.revision, today)) {
+            return;
+        }
+        self.states.account_synced = Some((self.datas.revision, today));
+
+        let streak = self.stats.streak(&self.datas, StreakKind::Plan, today);
+        let xp = self.stats.xp(&self.datas).total();
+        let badges = self.stats.badges(&self.datas, today);
+        let account = &mut self.datas.account_data;
+
+        account.current_streak = streak.current;
+        account.longest_streak = account.longest_streak.max(streak.longest);
+
+        let progress = account.xp_settings.progress(xp);
+        // a fresh profile sits at level 0 and should not celebrate reaching level 1,
+        // and retuning the curve is not an achievement either
+        if account.level > 0 && progress.level > account.level && xp > account.xp {
+            self.states.level_up = Some((progress.level, ctx.input(|i| i.time)));
+        }
+        account.level = progress.level;
+        account.xp = xp;
+
+        // the earliest known unlock date wins
+        for badge in badges {
+            if let Some(date) = badge.unlocked {
+                let stored = account.badges.entry(badge.achievement.id.to_string()).or_insert(date);
+                *stored = (*stored).min(date);
+            }
+        }
+    }
+
+    pub fn streak(&mut self, kind: StreakKind) -> Streak {
+        self.stats.streak(&self.datas, kind, Local::now().date_naive())
+    }
+
+    pub fn level(&self) -> LevelProgress {
+        let account = &self.datas.account_data;
+        account.xp_settings.progress(account.xp)
+    }
+
+    pub fn badges(&mut self) -> Vec<Badge> {
+        let mut badges = self.stats.badges(&self.datas, Local::now().date_naive());
+        let earned = &self.datas.account_data.badges;
+        for badge in badges.iter_mut() {
+            if let Some(stored) = earned.get(&badge.achievement.id.to_string()) {
+                badge.unlocked = Some(badge.unlocked.map_or(*stored, |date| date.min(*stored)));
+            }
+        }
+        badges
+    }
+
+    fn draw_level_up(&mut self, ctx: &Context) {
+        let Some((level, started)) = self.states.level_up else {
+            return;
+        };
+        let t = ((ctx.input(|i| i.time) - started) / LEVEL_UP_SECS) as f32;
+        if t >= 1.0 {
+            self.states.level_up = None;
+            return;
         }
+
+        // fade in, hold, fade out while the text grows into place
+        let alpha = if t < 0.15 { t / 0.15 } else if t > 0.7 { (1.0 - t) / 0.3 } else { 1.0 };
+        let scale = 0.6 + 0.4 * (t / 0.15).min(1.0);
+        let white = Color32::from_white_alpha((alpha * 255.0) as u8);
+
+        let screen_rect = ctx.screen_rect();
+        let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("level_up")));
+        painter.rect_filled(screen_rect, 0.0, Color32::from_black_alpha((alpha * 160.0) as u8));
+        painter.text(screen_rect.center(), egui::Align2::CENTER_CENTER, "LEVEL UP", FontId::proportional(44.0 * scale), white);
+        painter.text(screen_rect.center() + vec2(0.0, 50.0 * scale), egui::Align2::CENTER_CENTER, format!("level {}", level), FontId::proportional(24.0 * scale), white);
+        ctx.request_repaint();
     }
 
     pub fn home(&mut self, ctx: &Context, frame: &mut Frame, ui: &mut Ui, tint_color: Color32) {
@@ -34,7 +135,12 @@ impl Gui<'_> {
         let rect_size = 10.0;
         let calory_rows = 5;
         let calory_cols = 25;
-        let calory_percent = ((self.datas.macro_data.calory_registered as f32 / self.datas.macro_data.calory_goal as f32) * 100.0) as u32;
+        let week = self.stats.totals(&self.datas, DateRange::week_of(Local::now().date_naive()));
+        let streak = self.streak(StreakKind::Plan);
+        let level = self.level();
+        let load = self.stats.load(&self.datas, Local::now().date_naive());
+        let eaten = self.stats.totals(&self.datas, DateRange::day(self.states.selected_day));
+        let calory_percent = ((eaten.calories as f32 / self.datas.macro_data.budget() as f32) * 100.0) as u32;
         let calory_tracker_width = (rect_size * calory_cols as f32) + (spacing * (calory_cols as f32 - 1.0));
 
         let circle_size = 14.0;
@@ -70,6 +176,7 @@ impl Gui<'_> {
                                         ui.add_sized([100.0, 100.0], Image::new(self.medias.default_pp.clone()).corner_radius(5.0));
                                         ui.label(RichText::new(format!("{}", self.datas.user_information.name)).size(20.0).strong());
                                         ui.label(RichText::new(format!("@{}", self.datas.user_information.username)).size(15.0));
+                                        ui.label(RichText::new(format!("{} day streak", streak.current)).size(13.0));
                                     });
                             });
 
@@ -92,21 +199,21 @@ impl Gui<'_> {
                             strip.cell(|ui| {
                                 ui.add_space(ui.available_height() / 4.0);
                                 ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
-                                    ui.label(RichText::new("14 lev").size(14.0).strong());
+                                    ui.label(RichText::new(format!("{} lev", level.level)).size(14.0).strong());
                                 });
                             });
 
                             strip.cell(|ui| {
                                 ui.add_space(ui.available_height() / 4.0);
                                 ui.vertical_centered(|ui| {
-                                    ui.add_sized(vec2(ui.available_width(), 10.0), egui::ProgressBar::new(0.0).show_percentage());
+                                    ui.add_sized(vec2(ui.available_width(), 10.0), egui::ProgressBar::new(level.fraction()).show_percentage());
                                 });
                             });
 
                             strip.cell(|ui| {
                                 ui.add_space(ui.available_height() / 4.0);
                                 ui.with_layout(Layout::left_to_right(Align::Min), |ui| {
-                                    ui.label(RichText::new("1300/2400").size(14.0).strong());
+                                    ui.label(RichText::new(format!("{}/{}", level.into_level, level.needed)).size(14.0).strong());
                                 });
                             });
                         });
@@ -133,11 +240,11 @@ impl Gui<'_> {
                                     ui.vertical_centered(|ui| {
                                         ui.label(RichText::new("REGISTERED").size(15.0).strong());
                                         ui.add_space(5.0);
-                                        ui.label(RichText::new(format!("proteins: {}", self.datas.macro_data.protein_registered)).size(13.0));
+                                        ui.label(RichText::new(format!("proteins: {}", eaten.protein)).size(13.0));
                                         ui.add_space(5.0);
-                                        ui.label(RichText::new(format!("carbs: {}", self.datas.macro_data.carb_registered)).size(13.0));
+                                        ui.label(RichText::new(format!("carbs: {}", eaten.carb)).size(13.0));
                                         ui.add_space(5.0);
-                                        ui.label(RichText::new(format!("fats: {}", self.datas.macro_data.fat_registered)).size(13.0));
+                                        ui.label(RichText::new(format!("fats: {}", eaten.fat)).size(13.0));
                                     })
                                 });
                             });
@@ -161,17 +268,17 @@ impl Gui<'_> {
                                     ui.add_space(5.0);
                                     ui.label(RichText::new(format!(
                                         "proteins: {}",
-                                        self.datas.macro_data.protein_goal.saturating_sub(self.datas.macro_data.protein_registered)
+                                        self.datas.macro_data.protein_goal.saturating_sub(eaten.protein)
                                     )).size(13.0));
                                     ui.add_space(5.0);
                                     ui.label(RichText::new(format!(
                                         "carbs: {}",
-                                        self.datas.macro_data.carb_goal.saturating_sub(self.datas.macro_data.carb_registered)
+                                        self.datas.macro_data.carb_goal.saturating_sub(eaten.carb)
                                     )).size(13.0));
                                     ui.add_space(5.0);
                                     ui.label(RichText::new(format!(
                                         "fats: {}",
-                                        self.datas.macro_data.fat_goal.saturating_sub(self.datas.macro_data.fat_registered)
+                                        self.datas.macro_data.fat_goal.saturating_sub(eaten.fat)
                                     )).size(13.0));
                                 });
                             });
@@ -223,23 +330,43 @@ impl Gui<'_> {
                             strip.cell(|ui| {
                                 ui.vertical_centered(|ui| {
                                     ui.label(RichText::new("workout tracker").size(17.5).strong());
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(ui.available_width() / 2.0 - 60.0);
+                                        ui.selectable_value(&mut self.states.recovery_map, false, "this week");
+                                        ui.selectable_value(&mut self.states.recovery_map, true, "recovery");
+                                    });
 
-                                    ui.add_space(REMAINDER);
-
-                                    ui.label(RichText::new(format!("{} % of muscles worked out this week", self.datas.all_workout_data.worked_out)).size(15.0));
+                                    let fatigue = self.stats.fatigue(&self.datas, Local::now().naive_local());
+                                    if self.states.recovery_map {
+                                        let recovering = fatigue.values().filter(|fatigue| **fatigue >= 0.3).count();
+                                        ui.label(RichText::new(format!("{} muscles still recovering", recovering)).size(15.0));
+                                    } else {
+                                        ui.label(RichText::new(format!("{} % of muscles worked out this week", week.muscle_percent())).size(15.0));
+                                    }
+                                    if load.warning() {
+                                        ui.label(RichText::new(if load.deload { "fatigue is building up, plan a deload week" } else { "training load is spiking, ease off" }).size(13.0).color(Color32::from_rgb(200, 30, 30)));
+                                    }
                                     StripBuilder::new(ui)
                                         .size(Size::relative(0.5))
                                         .size(Size::relative(0.5))
                                         .horizontal(|mut strip| {
                                             strip.cell(|ui| {
                                                 ui.vertical_centered(|ui| {
-                                                    workout_tracker_widget_front(ctx, ui, Vec2::new(100.0, 226.0), &vec![Exercises::Deadlift, Exercises::BenchPress]);
+                                                    if self.states.recovery_map {
+                                                        body_map_front(ctx, ui, Vec2::new(100.0, 226.0), &MuscleShading::Recovery(fatigue.clone()));
+                                                    } else {
+                                                        workout_tracker_widget_front(ctx, ui, Vec2::new(100.0, 226.0), &week.exercises);
+                                                    }
                                                 });
                                             });
 
                                             strip.cell(|ui| {
                                                 ui.vertical_centered(|ui| {
-                                                    workout_tracker_widget_behind(ctx, ui, Vec2::new(100.0, 226.0), &vec![]);
+                                                    if self.states.recovery_map {
+                                                        body_map_behind(ctx, ui, Vec2::new(100.0, 226.0), &MuscleShading::Recovery(fatigue.clone()));
+                                                    } else {
+                                                        workout_tracker_widget_behind(ctx, ui, Vec2::new(100.0, 226.0), &week.exercises);
+                                                    }
                                                 });
                                             });
                                         })
@@ -315,7 +442,7 @@ impl Gui<'_> {
                             let workouts = &self.datas.planned_workout_data.workouts;
 
                             //TODO improve logic
-                            if workouts.contains_key(&self.states.selected_day) && !workouts.get(&self.states.selected_day).expect("workout data error").is_empty() && workouts.get(&self.states.selected_day).unwrap()[0].template.workout_name != "rest" {
+                            if workouts.contains_key(&self.states.selected_day) && !workouts.get(&self.states.selected_day).expect("workout data error").is_empty() && !workouts.get(&self.states.selected_day).unwrap()[0].is_rest() {
                             // if self.datas.planned_workout_data.workouts.contains_key(&self.states.selected_day) && self.datas.planned_workout_data.workouts.get(&self.states.selected_day).unwrap()[0].template.workout_name != "rest" {
                                 ui.vertical_centered(|ui| {
                                     if response.dragged() {
@@ -479,6 +606,7 @@ impl Gui<'_> {
                                                     .rounding(10),
                                             ).clicked() {
                                                 self.datas.planned_workout_data.rest(self.states.selected_day);
+                                                self.datas.touch();
                                             };
 
                                             let padding = side_rect.width() - (((side_rect.width() / 13.0) * 2.0) + ((side_rect.width() / 2.5) * 2.0)) - 8.0;
@@ -536,6 +664,50 @@ impl Gui<'_> {
                             self.draw_alert_window(ui, ctx, is_dark, "are you sure to remove workout?", "remove");
                         }
 
+                        if self.states.session.is_some() {
+                            self.draw_session_window(ui, ctx, is_dark, other_elements_color, text_color);
+                        }
+
+                        if self.states.cardio_window {
+                            self.draw_cardio_window(ctx, text_color, &mut true);
+                        }
+
+                        if self.states.plates_window {
+                            self.draw_plates_window(ctx, other_elements_color, text_color, &mut true);
+                        }
+
+                        if self.states.progression_window {
+                            self.draw_progression_window(ctx, text_color, &mut true);
+                        }
+
+                        if self.states.programs_window {
+                            self.draw_programs_window(ui, ctx, is_dark, other_elements_color, text_color, &mut true);
+                        }
+
+                        if self.states.sharing_window {
+                            self.draw_sharing_window(ui, ctx, is_dark, other_elements_color, text_color, &mut true);
+                        }
+
+                        if self.states.schedule_window {
+                            self.draw_schedule_window(ctx, text_color, &mut true);
+                        }
+
+                        if self.states.occurrence_edit.is_some() {
+                            self.draw_occurrence_window(ctx, text_color, &mut true);
+                        }
+
+                        if self.states.missed_edit.is_some() {
+                            self.draw_missed_window(ctx, other_elements_color, text_color, &mut true);
+                        }
+
+                        if self.states.generator_window {
+                            self.draw_generator_window(ctx, other_elements_color, text_color, &mut true);
+                        }
+
+                        if self.states.history_window {
+                            self.draw_history_window(ctx, other_elements_color, text_color, &mut true);
+                        }
+
                         let top_rect = Rect::from_min_size(
                             ctx.screen_rect().left_top(),
                             vec2(ui.available_width(), 100.0),
@@ -641,6 +813,7 @@ impl Gui<'_> {
         }
 
         let now = chrono::Local::now();
+        let eaten = self.stats.totals(&self.datas, DateRange::day(self.states.selected_day));
 
         StripBuilder::new(ui)
             // .size(Size::exact(100.0))
@@ -680,7 +853,10 @@ impl Gui<'_> {
                             ui.add_space(calory_rect.width() / 12.0);
 
                             ui.vertical_centered(|ui| {
-                                ui.label(RichText::new(format!("{}/{}", self.datas.macro_data.calory_registered, self.datas.macro_data.calory_goal)).size(35.0).strong());
+                                ui.label(RichText::new(format!("{}/{}", eaten.calories, self.datas.macro_data.budget())).size(35.0).strong());
+                                if self.datas.macro_data.count_burned && self.datas.macro_data.calory_burned > 0 {
+                                    ui.add(Label::new(RichText::new(format!("+{} burned", self.datas.macro_data.calory_burned)).size(13.0)).selectable(false));
+                                }
                             });
                         });
                     });
@@ -692,7 +868,7 @@ impl Gui<'_> {
                     let rows = 5;
                     let cols = 25;
 
-                    let calory_percent = ((self.datas.macro_data.calory_registered as f32 / self.datas.macro_data.calory_goal as f32) * 100.0) as u32;
+                    let calory_percent = ((eaten.calories as f32 / self.datas.macro_data.budget() as f32) * 100.0) as u32;
 
                     let total_width = (rect_size * cols as f32) + (spacing * (cols as f32 - 1.0));
                     let available_width = ui.available_width();
@@ -756,11 +932,11 @@ impl Gui<'_> {
                                 ui.vertical_centered(|ui| {
                                     ui.add_space(2.0);
                                     ui.label(RichText::new("carbs").strong().color(Color32::from_rgb(141, 54, 0)).size(14.0));
-                                    ui.label(RichText::new(format!("{}/{}", self.datas.macro_data.carb_registered, self.datas.macro_data.carb_goal)).size(23.0).strong());
+                                    ui.label(RichText::new(format!("{}/{}", eaten.carb, self.datas.macro_data.carb_goal)).size(23.0).strong());
                                     ui.add_space(5.0);
                                     ui.horizontal(|ui| {
                                         ui.add_space(31.0);
-                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, self.datas.macro_data.carb_registered, self.datas.macro_data.carb_goal);
+                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, eaten.carb, self.datas.macro_data.carb_goal);
                                     });
                                 });
                             });
@@ -780,11 +956,11 @@ impl Gui<'_> {
                                 ui.vertical_centered(|ui| {
                                     ui.add_space(2.0);
                                     ui.label(RichText::new("proteins").strong().color(Color32::from_rgb(0, 75, 140)).size(14.0));
-                                    ui.label(RichText::new(format!("{}/{}", self.datas.macro_data.protein_registered, self.datas.macro_data.protein_goal)).size(23.0).strong());
+                                    ui.label(RichText::new(format!("{}/{}", eaten.protein, self.datas.macro_data.protein_goal)).size(23.0).strong());
                                     ui.add_space(5.0);
                                     ui.horizontal(|ui| {
                                         ui.add_space(31.0);
-                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, self.datas.macro_data.protein_registered, self.datas.macro_data.protein_goal);
+                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, eaten.protein, self.datas.macro_data.protein_goal);
                                     });
                                 });
                             });
@@ -804,11 +980,11 @@ impl Gui<'_> {
                                 ui.vertical_centered(|ui| {
                                     ui.add_space(2.0);
                                     ui.label(RichText::new("fats").strong().color(Color32::from_rgb(141, 0, 19)).size(14.0));
-                                    ui.label(RichText::new(format!("{}/{}", self.datas.macro_data.fat_registered, self.datas.macro_data.fat_goal)).size(23.0).strong());
+                                    ui.label(RichText::new(format!("{}/{}", eaten.fat, self.datas.macro_data.fat_goal)).size(23.0).strong());
                                     ui.add_space(5.0);
                                     ui.horizontal(|ui| {
                                         ui.add_space(31.0);
-                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, self.datas.macro_data.fat_registered, self.datas.macro_data.fat_goal);
+                                        self.mini_tracker_bar(ctx, frame, ui, spacing, rect_size, 5, eaten.fat, self.datas.macro_data.fat_goal);
                                     });
                                 });
                             });
@@ -853,6 +1029,7 @@ impl Gui<'_> {
                                                     // for i in 0..10 {
                                                     // for j in self.datas.macro_data.meal_history.get(&self.states.selected_day) {
                                                     //     for i in j {
+                                                    let mut removed = false;
                                                     if let Some(eats) = self.datas.macro_data.meal_history.get_mut(&self.states.selected_day) {
                                                         for (index, eat) in eats.clone().iter().enumerate() {
                                                             ui.vertical(|ui| {
@@ -874,6 +1051,7 @@ impl Gui<'_> {
 
                                                                     if self.states.delete_was_positive {
                                                                         eats.remove(index);
+                                                                        removed = true;
                                                                         self.states.delete_was_positive = false;
                                                                     }
 
@@ -900,6 +1078,9 @@ impl Gui<'_> {
                                                             });
                                                         }
                                                     }
+                                                    if removed {
+                                                        self.datas.touch();
+                                                    }
                                                 });
                                         });
                                     });
@@ -1073,7 +1254,8 @@ impl Gui<'_> {
                                                                         &self.states.carb_add_value,
                                                                         &self.states.fat_add_value);
 
-                                                                    self.datas.macro_data.summarize(Some(self.states.selected_day));
+                                                                    self.datas.refresh_burned(self.states.selected_day);
+                                                                    self.datas.touch();
                                                                     self.states.reset_macros();
                                                                     self.states.macro_add_clicked = !self.states.macro_add_clicked;
                                                                 };
@@ -1533,6 +1715,7 @@ impl Gui<'_> {
                                         ScrollArea::vertical()
                                             .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
                                             .show(ui, |ui| {
+                                                let mut removed = false;
                                                 if let Some(drinks) = self.datas.water_data.water_history.get_mut(&self.states.selected_day) {
                                                     for (index, drink) in drinks.clone().iter().enumerate() {
                                                         ui.vertical(|ui| {
@@ -1554,6 +1737,7 @@ impl Gui<'_> {
 
                                                                 if self.states.delete_was_positive {
                                                                     drinks.remove(index);
+                                                                removed = true;
                                                                     self.states.delete_was_positive = false;
                                                                 }
 
@@ -1576,6 +1760,9 @@ impl Gui<'_> {
                                                         });
                                                     }
                                                 }
+                                                if removed {
+                                                    self.datas.touch();
+                                                }
                                             });
                                         });
                                 });
@@ -1711,6 +1898,7 @@ impl Gui<'_> {
                                                                     self.datas.water_data.add_drink(self.states.selected_day, &self.states.water_add_value, &self.states.hydration_percent);
 
                                                                     self.datas.water_data.summarize(Some(self.states.selected_day));
+                                                                    self.datas.touch();
                                                                     self.states.reset_water();
                                                                     self.states.water_add_clicked = !self.states.water_add_clicked;
                                                                 };
@@ -1776,10 +1964,546 @@ impl Gui<'_> {
             });
     }
 
-    pub fn statistics_ui(&mut self, ctx: &Context, frame: &mut Frame, ui: &mut Ui) {
+    pub fn statistics_ui(&mut self, ctx: &Context, frame: &mut Frame, ui: &mut Ui, elements_color: Color32) {
+        let is_dark = ctx.style().visuals.dark_mode;
+        let (other_elements_color, text_color) = if is_dark {
+            (Color32::from_rgb(67, 67, 67), Color32::WHITE)
+        } else {
+            (Color32::from_rgb(240, 240, 240), Color32::BLACK)
+        };
+
+        let top_rect = Rect::from_min_size(
+            ctx.screen_rect().left_top(),
+            vec2(ui.available_width(), 100.0),
+        );
+
+        Self::draw_rect_with_black_shadow(ui.painter(), top_rect, 24, elements_color, 0.0, 6.0, [(5.0, 20), (3.0, 25), (2.0, 30),], Rounding {
+            nw: 0,
+            ne: 0,
+            sw: 24,
+            se: 24,
+        });
+
+        ui.allocate_ui_at_rect(top_rect, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(35.0);
+                ui.add(Label::new(RichText::new("STATISTICS").size(25.0).strong()).selectable(false));
+            });
+        });
+
+        ui.add_space(115.0);
+
+        ScrollArea::vertical()
+            .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+            .show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.set_width(ui.available_width() - 60.0);
+                    self.draw_totals_report(ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_streak_report(ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_level_report(ctx, ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_badge_summary(ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_load_report(ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_muscle_volume_report(ctx, ui, other_elements_color, text_color);
+                    ui.add_space(REMAINDER * 2.0);
+                    self.draw_side_balance_report(ui, other_elements_color, text_color);
+                });
+            });
+
+        if self.states.badges_window {
+            self.draw_badges_window(ctx, text_color, &mut true);
+        }
+    }
+
+    fn draw_totals_report(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let today = Local::now().date_naive();
+        let this_week = DateRange::week_of(today);
+        let last_week = DateRange::week_of(today - Duration::days(7));
+        let columns: Vec<(&str, DateRange, Totals)> = vec![
+            ("this week", this_week, self.stats.totals(&self.datas, this_week)),
+            ("last week", last_week, self.stats.totals(&self.datas, last_week)),
+            ("all time", DateRange::all(), self.stats.totals(&self.datas, DateRange::all())),
+        ];
+
+        // meals and drinks are shown as daily averages over the days that have passed
+        let per_day = |total: u32, range: &DateRange| {
+            let days = if range.from == NaiveDate::MIN { 0 } else { range.days_until(today) };
+            total.checked_div(days).map_or(total.to_string(), |per_day| format!("{per_day}/day"))
+        };
+        let rows: Vec<(&str, Vec<String>)> = vec![
+            ("workouts", columns.iter().map(|(_, _, totals)| format!("{}/{}", totals.workouts, totals.planned)).collect()),
+            ("volume", columns.iter().map(|(_, _, totals)| format!("{:.0} kg", totals.volume)).collect()),
+            ("sets", columns.iter().map(|(_, _, totals)| totals.sets.to_string()).collect()),
+            ("reps", columns.iter().map(|(_, _, totals)| totals.reps.to_string()).collect()),
+            ("time", columns.iter().map(|(_, _, totals)| format!("{} min", totals.workout_mins)).collect()),
+            ("prs", columns.iter().map(|(_, _, totals)| totals.prs.to_string()).collect()),
+            ("cardio", columns.iter().map(|(_, _, totals)| format!("{:.1} km", totals.distance_km)).collect()),
+            ("burned", columns.iter().map(|(_, _, totals)| format!("{} kcal", totals.calories_burned)).collect()),
+            ("eaten", columns.iter().map(|(_, range, totals)| per_day(totals.calories, range)).collect()),
+            ("water", columns.iter().map(|(_, range, totals)| per_day(totals.water, range)).collect()),
+        ];
+
+        ui.add(Label::new(RichText::new("totals").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            egui::Grid::new("totals_grid").num_columns(4).spacing(vec2(20.0, 6.0)).show(ui, |ui| {
+                ui.label("");
+                for (name, _, _) in columns.iter() {
+                    ui.add(Label::new(RichText::new(*name).size(14.0).strong().color(text_color)));
+                }
+                ui.end_row();
+
+                for (name, values) in rows {
+                    ui.add(Label::new(RichText::new(name).size(14.0).color(text_color)));
+                    for value in values {
+                        ui.add(Label::new(RichText::new(value).size(14.0).color(text_color)));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn draw_badge_summary(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let badges = self.badges();
+        let unlocked = badges.iter().filter(|badge| badge.unlocked.is_some()).count();
+
+        ui.add(Label::new(RichText::new("badges").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            ui.add(Label::new(RichText::new(format!("{} of {} unlocked", unlocked, badges.len())).size(15.0).strong().color(text_color)));
+
+            // the closest locked badge keeps the next goal in sight
+            if let Some(next) = badges.iter().filter(|badge| badge.unlocked.is_none()).max_by(|a, b| a.fraction().total_cmp(&b.fraction())) {
+                ui.add(Label::new(RichText::new(format!("next: {}", next.achievement.name)).size(13.0).color(text_color)));
+                ui.add(egui::ProgressBar::new(next.fraction()).text(format!("{:.0}/{:.0}", next.progress.min(next.target), next.target)));
+            }
+
+            if ui.add(Button::new(RichText::new("gallery").size(14.0).strong().color(Color32::WHITE))
+                .fill(Color32::from_rgb(0, 79, 148))
+                .min_size(Vec2::new(80.0, 25.0))
+                .rounding(9)).clicked() {
+                self.states.badges_window = true;
+            }
+        });
+    }
+
+    fn draw_badges_window(&mut self, ctx: &Context, text_color: Color32, open: &mut bool) {
+        let badges = self.badges();
+
+        Window::new("badges")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(vec2(340.0, 460.0))
+            .show(ctx, |ui| {
+                ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("badge_gallery").num_columns(2).spacing(vec2(10.0, 10.0)).show(ui, |ui| {
+                        for (index, badge) in badges.iter().enumerate() {
+                            Self::draw_badge(ui, badge, text_color);
+                            if index % 2 == 1 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+                });
+            });
+
+        if !*open {
+            self.states.badges_window = false;
+        }
+    }
+
+    // ranked alternatives for an exercise, returns the one picked
+    fn draw_substitutes(ui: &mut Ui, exercise: &Exercises, unavailable: &mut Vec<Equipment>, secondary_share: f32, text_color: Color32) -> Option<Exercises> {
+        let mut picked = None;
+
+        ui.add(Label::new(RichText::new(format!("instead of {}", exercise)).size(15.0).strong().color(text_color)));
+        ui.horizontal_wrapped(|ui| {
+            ui.add(Label::new(RichText::new("unavailable:").size(12.0).color(text_color)));
+            for equipment in Equipment::iter().filter(|equipment| *equipment != Equipment::Bodyweight) {
+                let mut taken = unavailable.contains(&equipment);
+                if ui.checkbox(&mut taken, equipment.to_string()).changed() {
+                    if taken {
+                        unavailable.push(equipment);
+                    } else {
+                        unavailable.retain(|other| *other != equipment);
+                    }
+                }
+            }
+        });
+
+        for substitute in substitutes(exercise, unavailable, secondary_share).into_iter().take(6) {
+            let color = if substitute.available { text_color } else { Color32::GRAY };
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new(format!("{:.0}%", substitute.score * 100.0)).size(13.0).strong().color(color)));
+                ui.add(Label::new(RichText::new(substitute.exercise.to_string()).size(13.0).color(color)));
+                let detail = format!("{}, {}", equipment_for(&substitute.exercise), if substitute.same_pattern { movement_for(&substitute.exercise).to_string() } else { String::from("other pattern") });
+                ui.add(Label::new(RichText::new(detail).size(11.0).color(Color32::GRAY)));
+                if ui.small_button("use").clicked() {
+                    picked = Some(substitute.exercise.clone());
+                }
+            });
+        }
+
+        picked
+    }
+
+    fn draw_badge(ui: &mut Ui, badge: &Badge, text_color: Color32) {
+        let (fill, faded) = if badge.unlocked.is_some() {
+            (Color32::from_rgb(212, 160, 23), text_color)
+        } else {
+            (Color32::from_gray(90), Color32::from_gray(140))
+        };
+
+        ui.vertical_centered(|ui| {
+            ui.set_width(155.0);
+            let (rect, _) = ui.allocate_exact_size(vec2(48.0, 48.0), Sense::hover());
+            ui.painter().circle_filled(rect.center(), 22.0, fill);
+            let initial = badge.achievement.name.chars().next().unwrap_or('?').to_uppercase().to_string();
+            ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, initial, FontId::proportional(22.0), Color32::WHITE);
+
+            ui.add(Label::new(RichText::new(badge.achievement.name).size(14.0).strong().color(faded)));
+            ui.add(Label::new(RichText::new(badge.achievement.description).size(11.0).color(faded)).wrap());
+            match badge.unlocked {
+                Some(date) => {
+                    ui.add(Label::new(RichText::new(format!("unlocked {}", date.format("%b %e, %Y"))).size(11.0).color(text_color)));
+                }
+                None => {
+                    ui.add(egui::ProgressBar::new(badge.fraction()).desired_width(120.0).text(format!("{:.0}/{:.0}", badge.progress.min(badge.target), badge.target)));
+                }
+            }
+        });
+    }
+
+    fn draw_level_report(&mut self, ctx: &Context, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let level = self.level();
+        let breakdown = self.stats.xp(&self.datas);
+
+        ui.add(Label::new(RichText::new("level").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            ui.add(Label::new(RichText::new(format!("level {}, {} xp in total", level.level, level.xp)).size(15.0).strong().color(text_color)));
+            ui.add(egui::ProgressBar::new(level.fraction()).text(format!("{}/{} to level {}", level.into_level, level.needed, level.level + 1)));
+            ui.add_space(REMAINDER / 2.0);
+
+            let settings = &mut self.datas.account_data.xp_settings;
+            let mut changed = false;
+            egui::Grid::new("xp_grid").num_columns(3).spacing(vec2(20.0, 6.0)).show(ui, |ui| {
+                for (name, earned, award) in [
+                    ("workouts", breakdown.workouts, &mut settings.workout),
+                    ("prs", breakdown.prs, &mut settings.pr),
+                    ("meals", breakdown.meals, &mut settings.meal),
+                    ("water goals", breakdown.water, &mut settings.water_goal),
+                ] {
+                    ui.add(Label::new(RichText::new(name).size(14.0).color(text_color)));
+                    ui.add(Label::new(RichText::new(format!("{} xp", earned)).size(14.0).color(text_color)));
+                    changed |= ui.add(egui::DragValue::new(award).range(0..=1000).suffix(" xp each")).changed();
+                    ui.end_row();
+                }
+            });
+            ui.add_space(REMAINDER / 2.0);
+
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new("first level").size(13.0).color(text_color)));
+                changed |= ui.add(egui::DragValue::new(&mut settings.base).range(10..=10000).suffix(" xp")).changed();
+                ui.add(Label::new(RichText::new("growth").size(13.0).color(text_color)));
+                changed |= ui.add(egui::DragValue::new(&mut settings.growth).range(1.0..=2.0).speed(0.01)).changed();
+            });
+            if changed {
+                self.datas.touch();
+            }
+        });
+    }
+
+    fn draw_streak_report(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let today = Local::now().date_naive();
+        let kind = self.states.streak_kind;
+        let streak = self.streak(kind);
+        let longest = if kind == StreakKind::Plan { self.datas.account_data.longest_streak } else { streak.longest };
+
+        ui.add(Label::new(RichText::new("streaks").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for option in StreakKind::iter() {
+                    ui.selectable_value(&mut self.states.streak_kind, option, RichText::new(option.to_string()).size(14.0));
+                }
+            });
+            ui.add(Label::new(RichText::new(format!("current {} days, longest {} days", streak.current, longest)).size(15.0).strong().color(text_color)));
+            ui.add(Label::new(RichText::new(format!("{} freezes left this month", streak.freezes_left)).size(13.0).color(text_color)));
+            ui.add_space(REMAINDER / 2.0);
+
+            // the last five weeks, monday first
+            let size = 24.0;
+            let start = DateRange::week_of(today).from - Duration::days(28);
+            ui.horizontal(|ui| {
+                for day in ["m", "t", "w", "t", "f", "s", "s"] {
+                    ui.add_sized(vec2(size, 14.0), Label::new(RichText::new(day).size(12.0).color(text_color)));
+                }
+            });
+            for week in 0..5 {
+                ui.horizontal(|ui| {
+                    for weekday in 0..7 {
+                        let date = start + Duration::days(week * 7 + weekday);
+                        let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::hover());
+                        let fill = match streak.days.get(&date) {
+                            Some(DayMark::Hit) => Color32::from_rgb(46, 160, 67),
+                            Some(DayMark::Frozen) => Color32::from_rgb(120, 190, 255),
+                            Some(DayMark::Missed) => Color32::from_rgb(140, 0, 0),
+                            Some(DayMark::Neutral) | Some(DayMark::Pending) => Color32::from_gray(120),
+                            None => Color32::from_gray(60),
+                        };
+                        ui.painter().rect_filled(rect.shrink(2.0), 4, fill);
+                        if date == today {
+                            ui.painter().rect_stroke(rect.shrink(1.0), 4, Stroke::new(2.0, text_color), StrokeKind::Outside);
+                        }
+                        ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, date.day().to_string(), FontId::proportional(10.0), Color32::WHITE);
+                        response.on_hover_text(date.format("%b %e").to_string());
+                    }
+                });
+            }
+            ui.add(Label::new(RichText::new("green kept, blue frozen, red missed, grey nothing due").size(12.0).color(text_color)));
+            ui.add_space(REMAINDER / 2.0);
+
+            let settings = &mut self.datas.account_data.streak_settings;
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new("freezes per month").size(13.0).color(text_color)));
+                changed |= ui.add(egui::DragValue::new(&mut settings.freezes_per_month).range(0..=10)).changed();
+            });
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new("calorie tolerance").size(13.0).color(text_color)));
+                changed |= ui.add(egui::DragValue::new(&mut settings.calorie_tolerance).range(0..=50).suffix(" %")).changed();
+            });
+            if changed {
+                self.datas.touch();
+            }
+        });
+    }
+
+    fn draw_load_report(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let today = Local::now().date_naive();
+        let report = self.stats.load(&self.datas, today);
+        let warning = Color32::from_rgb(200, 30, 30);
+
+        ui.add(Label::new(RichText::new("training load").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            match (report.ratio, report.zone()) {
+                (Some(ratio), Some(zone)) => {
+                    let color = match zone {
+                        LoadZone::Risk => warning,
+                        LoadZone::Caution => Color32::from_rgb(255, 140, 0),
+                        _ => text_color,
+                    };
+                    ui.add(Label::new(RichText::new(format!("ratio {:.2}, {}", ratio, zone)).size(15.0).strong().color(color)));
+                }
+                _ => {
+                    ui.add(Label::new(RichText::new("log a few weeks of training to see your ratio").size(14.0).color(text_color)));
+                }
+            }
+            ui.add(Label::new(RichText::new(format!("acute {:.0} / chronic {:.0} per day", report.acute, report.chronic)).size(13.0).color(text_color)));
+            ui.add(Label::new(RichText::new(format!("monotony {:.1}, strain {:.0}", report.monotony, report.strain)).size(13.0).color(text_color)));
+
+            // daily load over four weeks, the last seven days are the acute window
+            let (rect, _) = ui.allocate_exact_size(vec2(ui.available_width(), 80.0), Sense::hover());
+            let most = report.daily.iter().map(|(_, load)| *load).fold(1.0, f32::max);
+            let width = rect.width() / report.daily.len().max(1) as f32;
+            for (position, (date, load)) in report.daily.iter().enumerate() {
+                let height = rect.height() * load / most;
+                let left = rect.left() + position as f32 * width;
+                let bar = Rect::from_min_max(Pos2::new(left + 1.0, rect.bottom() - height), Pos2::new(left + width - 1.0, rect.bottom()));
+                let fill = if (today - *date).num_days() < 7 { Color32::from_rgb(59, 163, 255) } else { Color32::from_rgb(0, 75, 141) };
+                ui.painter().rect_filled(bar, 2, fill);
+            }
+            let chronic_y = rect.bottom() - rect.height() * report.chronic / most;
+            for (ratio, color) in [(CAUTION_RATIO, Color32::from_rgb(255, 140, 0)), (RISK_RATIO, warning)] {
+                let y = rect.bottom() - rect.height() * report.chronic * ratio / most;
+                if report.chronic > 0.0 && y > rect.top() {
+                    ui.painter().hline(rect.x_range(), y, Stroke::new(1.0, color));
+                }
+            }
+            if report.chronic > 0.0 {
+                ui.painter().hline(rect.x_range(), chronic_y, Stroke::new(1.0, text_color));
+            }
+
+            if report.deload {
+                ui.add(Label::new(RichText::new("fatigue has stayed high, a deload week is suggested").size(14.0).strong().color(warning)));
+                if ui.add(Button::new(RichText::new("lighten next 7 days").size(13.0).strong().color(Color32::WHITE))
+                    .fill(Color32::from_rgb(91, 0, 113))
+                    .rounding(8)).clicked() {
+                    let changed = self.datas.planned_workout_data.plan_deload(today, today + Duration::days(6));
+                    self.states.load_message = if changed == 0 {
+                        String::from("no program workouts planned next week, keep them light by feel")
+                    } else {
+                        format!("{} workouts set to deload intensity and volume", changed)
+                    };
+                }
+            }
+            if !self.states.load_message.is_empty() {
+                ui.add(Label::new(RichText::new(&self.states.load_message).size(12.0).color(text_color)));
+            }
+            ui.add_space(REMAINDER / 2.0);
+
+            let settings = &mut self.datas.all_workout_data.load;
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new("load from").size(13.0).color(text_color)));
+                for method in LoadMethod::iter() {
+                    changed |= ui.selectable_value(&mut settings.method, method, method.to_string()).changed();
+                }
+            });
+            ui.horizontal(|ui| {
+                match settings.method {
+                    LoadMethod::SessionRpe => {
+                        ui.add(Label::new(RichText::new("rpe when not logged").size(13.0).color(text_color)));
+                        changed |= ui.add(egui::DragValue::new(&mut settings.default_rpe).speed(0.5).range(1.0..=10.0)).changed();
+                    }
+                    LoadMethod::Volume => {
+                        ui.add(Label::new(RichText::new("kg per load unit").size(13.0).color(text_color)));
+                        changed |= ui.add(egui::DragValue::new(&mut settings.volume_per_unit).speed(1.0).range(1.0..=500.0)).changed();
+                    }
+                }
+            });
+            if changed {
+                self.datas.touch();
+            }
+        });
+    }
+
+    fn draw_muscle_volume_report(&mut self, ctx: &Context, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let today = Local::now().date_naive();
+        let week = DateRange::week_of(today - Duration::days(7 * self.states.volume_week));
+        let sets = self.stats.totals(&self.datas, week).muscle_sets;
+        let zone_color = |zone: VolumeZone| match zone {
+            VolumeZone::Below => Color32::from_rgb(200, 30, 30),
+            VolumeZone::Maintenance => Color32::from_rgb(230, 190, 40),
+            VolumeZone::Productive => Color32::from_rgb(46, 160, 67),
+            VolumeZone::Over => Color32::from_rgb(91, 0, 113),
+        };
+        let landmarks = &mut self.datas.all_workout_data.landmarks;
+        let mut shared = false;
+        let colors = Muscle::iter()
+            .map(|muscle| {
+                let done = sets.get(&muscle).copied().unwrap_or(0.0);
+                (muscle.clone(), zone_color(landmarks.get(&muscle).zone(done)))
+            })
+            .collect();
+        let shading = MuscleShading::Colors(colors);
+
+        ui.add(Label::new(RichText::new("weekly sets per muscle").size(20.0).strong().color(text_color)).selectable(false));
+        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("<").clicked() {
+                    self.states.volume_week += 1;
+                }
+                ui.add(Label::new(RichText::new(format!("{} - {}", week.from.format("%d.%m"), week.to.format("%d.%m"))).size(15.0).strong().color(text_color)));
+                if self.states.volume_week > 0 && ui.button(">").clicked() {
+                    self.states.volume_week -= 1;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                body_map_front(ctx, ui, vec2(100.0, 226.0), &shading);
+                body_map_behind(ctx, ui, vec2(100.0, 226.0), &shading);
+            });
+            ui.horizontal_wrapped(|ui| {
+                for zone in [VolumeZone::Below, VolumeZone::Maintenance, VolumeZone::Productive, VolumeZone::Over] {
+                    ui.add(Label::new(RichText::new(zone.to_string()).size(12.0).strong().color(zone_color(zone))));
+                }
+            });
+            ui.add_space(REMAINDER / 2.0);
+
+            // targets are edited in place, mv <= mev <= mrv is kept while dragging
+            egui::Grid::new("muscle_volume_grid").num_columns(5).spacing(vec2(12.0, 6.0)).show(ui, |ui| {
+                for header in ["muscle", "sets", "mv", "mev", "mrv"] {
+                    ui.add(Label::new(RichText::new(header).size(14.0).strong().color(text_color)));
+                }
+                ui.end_row();
+
+                for muscle in Muscle::iter() {
+                    let done = sets.get(&muscle).copied().unwrap_or(0.0);
+                    let mut target = landmarks.get(&muscle);
+                    ui.add(Label::new(RichText::new(muscle.to_string()).size(14.0).color(text_color)));
+                    ui.add(Label::new(RichText::new(format!("{:.1}", done)).size(14.0).strong().color(zone_color(target.zone(done)))));
+                    ui.add(egui::DragValue::new(&mut target.mv).speed(0.5).range(0.0..=target.mev));
+                    ui.add(egui::DragValue::new(&mut target.mev).speed(0.5).range(target.mv..=target.mrv));
+                    ui.add(egui::DragValue::new(&mut target.mrv).speed(0.5).range(target.mev..=60.0));
+                    landmarks.targets.insert(muscle, target);
+                    ui.end_row();
+                }
+            });
+            ui.add_space(REMAINDER / 2.0);
+
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new("secondary muscles count").size(13.0).color(text_color)));
+                if ui.add(egui::DragValue::new(&mut landmarks.secondary_share).speed(0.05).range(0.0..=1.0).fixed_decimals(2)).changed() {
+                    shared = true;
+                }
+                ui.add(Label::new(RichText::new("of a set").size(13.0).color(text_color)));
+            });
+        });
+        if shared {
+            self.datas.touch();
+        }
+    }
+
+    fn draw_side_balance_report(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let body_weight = self.datas.user_information.body_weight();
+        let reports: Vec<(Exercises, Vec<SideBalance>)> = Exercises::iter()
+            .filter(is_unilateral)
+            .map(|exercise| {
+                let history = self.datas.all_workout_data.side_balance(&exercise, body_weight);
+                (exercise, history)
+            })
+            .filter(|(_, history)| !history.is_empty())
+            .collect();
+
+        ui.add(Label::new(RichText::new("side balance").size(20.0).strong().color(text_color)).selectable(false));
+        if reports.is_empty() {
+            ui.add(Label::new(RichText::new("log a unilateral exercise to compare your sides").size(14.0).color(text_color)).selectable(false));
+            return;
+        }
+
+        let warning = Color32::from_rgb(255, 140, 0);
+        for (exercise, history) in reports {
+            egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(10.0).show(ui, |ui| {
+                let left_volume: f32 = history.iter().map(|balance| balance.left_volume).sum();
+                let right_volume: f32 = history.iter().map(|balance| balance.right_volume).sum();
+                let left_best = history.iter().map(|balance| balance.left_best).fold(0.0, f32::max);
+                let right_best = history.iter().map(|balance| balance.right_best).fold(0.0, f32::max);
+                let volume_gap = SideBalance::imbalance(left_volume, right_volume);
+                let best_gap = SideBalance::imbalance(left_best, right_best);
+
+                ui.add(Label::new(RichText::new(exercise.to_string()).size(16.0).strong().color(text_color)));
+                ui.add(Label::new(RichText::new(format!("volume L {:.0} / R {:.0} kg", left_volume, right_volume)).size(14.0).color(if volume_gap > 10.0 { warning } else { text_color })));
+                ui.add(Label::new(RichText::new(format!("best L {} / R {}", left_best, right_best)).size(14.0).color(if best_gap > 10.0 { warning } else { text_color })));
+                if volume_gap > 10.0 || best_gap > 10.0 {
+                    let weaker = if left_volume + left_best < right_volume + right_best { "left" } else { "right" };
+                    ui.add(Label::new(RichText::new(format!("{} side trails by {:.0}%", weaker, volume_gap.max(best_gap))).size(13.0).color(warning)));
+                }
+
+                // latest sessions, left bar grows to the left and right bar to the right
+                let most = history.iter().map(|balance| balance.left_volume.max(balance.right_volume)).fold(1.0, f32::max);
+                for balance in history.iter().rev().take(6) {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(RichText::new(balance.date.format("%d.%m").to_string()).size(12.0).color(text_color)));
+                        let (rect, _) = ui.allocate_exact_size(vec2(200.0, 12.0), Sense::hover());
+                        let center = rect.center().x;
+                        let half = rect.width() / 2.0 - 2.0;
+                        ui.painter().rect_filled(Rect::from_min_max(Pos2::new(center - half * balance.left_volume / most, rect.top()), Pos2::new(center - 1.0, rect.bottom())), 2, Color32::from_rgb(0, 75, 141));
+                        ui.painter().rect_filled(Rect::from_min_max(Pos2::new(center + 1.0, rect.top()), Pos2::new(center + half * balance.right_volume / most, rect.bottom())), 2, Color32::from_rgb(59, 163, 255));
+                        ui.add(Label::new(RichText::new(format!("{:.0}%", balance.volume_imbalance())).size(12.0).color(text_color)));
+                    });
+                }
+            });
+            ui.add_space(REMAINDER);
+        }
     }
 
     pub fn navigation_bar(&mut self, ctx: &Context, frame: &mut Frame, ui: &mut Ui) {
+        self.sync_account(ctx);
         let is_dark = ctx.style().visuals.dark_mode;
         let mut elements_color;
         let mut tint_color;
@@ -1798,18 +2522,20 @@ impl Gui<'_> {
             2 => self.workouts_ui(ctx, frame, ui, elements_color, tint_color, is_dark),
             3 => self.calory_tracker_ui(ctx, frame, ui, elements_color, tint_color),
             4 => self.water_tracker_ui(ctx, frame, ui, elements_color, tint_color),
-            5 => self.statistics_ui(ctx, frame, ui),
+            5 => self.statistics_ui(ctx, frame, ui, elements_color),
             _ => {ui.label("empty");},
         }
+
+        self.draw_level_up(ctx);
     }
 
     fn calory_tracker_bar(&mut self, ctx: &Context, frame: &mut Frame, ui: &mut Ui, spacing: f32, rect_size: f32, rows: u32, cols: u32, calory_percent: u32) {
         ui.spacing_mut().item_spacing = vec2(1.0, -3.0);
 
         let mut green_rects = {
-            if self.datas.macro_data.calory_registered == 0 {
+            if calory_percent == 0 {
                 0
-            } else if self.datas.macro_data.calory_goal > self.datas.macro_data.calory_registered{
+            } else if calory_percent < 100 {
                 (((rows * cols) as f32 / 100.0) * calory_percent as f32).round() as u32
             } else {
                rows * cols
@@ -1973,33 +2699,135 @@ impl Gui<'_> {
                         ui.vertical_centered(|ui| {
                             ui.add_space(30.0);
                             ui.label(RichText::new(&self.datas.planned_workout_data.workouts.get(&selected_day).unwrap()[index].template.workout_name).size(27.0).strong());
-                            ui.add_space(50.0);
+                            if let Some(slot) = &self.datas.planned_workout_data.workouts.get(&selected_day).unwrap()[index].program {
+                                ui.add(Label::new(RichText::new(format!("{} - week {} ({}% intensity, {}% volume)", slot.name, slot.week, (slot.intensity * 100.0).round(), (slot.volume * 100.0).round())).size(12.0)).selectable(false));
+                            } else {
+                                ui.add_space(17.0);
+                            }
+                            ui.add_space(3.0);
 
-                            ui.horizontal(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.set_width(side_rect.width() / 2.0);
+                            let planned = self.datas.planned_workout_data.workouts.get(&selected_day).unwrap()[index].clone();
+
+                            if planned.is_missed(Local::now().date_naive()) {
+                                if ui.add(Button::new(RichText::new("missed - reschedule").size(13.0).strong().color(Color32::WHITE))
+                                    .fill(Color32::from_rgb(140, 0, 0))
+                                    .rounding(8)).clicked() {
+                                    self.states.missed_edit = Some((selected_day, index));
+                                }
+                            } else if planned.skipped {
+                                ui.add(Label::new(RichText::new("skipped").size(13.0).color(text_color)).selectable(false));
+                            }
+
+                            if let Some(plan) = &planned.cardio {
+                                ui.allocate_ui(vec2(side_rect.width() - 60.0, 249.0), |ui| {
+                                    ui.set_min_height(249.0);
                                     ui.vertical_centered(|ui| {
-                                        workout_tracker_widget_front(ctx, ui, Vec2::new(110.0, 249.0), &self.datas.planned_workout_data.workouts.get(&selected_day).unwrap()[index].template.exercises);
+                                        let logged = self.datas.all_workout_data.cardio.iter().rev().find(|session| session.date == selected_day && session.kind == plan.kind);
+                                        let route = logged.filter(|session| planned.completed && session.route.len() > 1);
+                                        ui.add_space(if route.is_some() { 0.0 } else { 60.0 });
+                                        ui.add(Label::new(RichText::new(plan.to_string()).size(22.0).color(text_color)).selectable(false));
+                                        if let Some(session) = route {
+                                            Self::draw_route(ui, &session.route, vec2(200.0, 100.0), Color32::from_rgb(59, 163, 255));
+                                            if !session.splits.is_empty() {
+                                                let splits: Vec<String> = session.splits.iter().map(|split| format!("{}:{:02}", split / 60, split % 60)).collect();
+                                                ui.add(Label::new(RichText::new(format!("splits {}", splits.join(" "))).size(11.0).color(text_color)));
+                                            }
+                                        }
+                                        if let (true, Some(session)) = (planned.completed, logged) {
+                                            ui.add_space(if route.is_some() { 2.0 } else { 15.0 });
+                                            ui.add(Label::new(RichText::new(format!("{:.2} km in {}:{:02}, {}", session.distance_km, session.duration_secs / 60, session.duration_secs % 60, session.pace_label())).size(16.0).color(text_color)).selectable(false));
+                                            if session.elevation_m > 0.0 {
+                                                ui.add(Label::new(RichText::new(format!("{:.0} m elevation", session.elevation_m)).size(14.0).color(text_color)).selectable(false));
+                                            }
+                                            if let Some(avg) = session.avg_hr {
+                                                ui.add(Label::new(RichText::new(format!("hr {} avg / {} max", avg, session.max_hr.map_or(String::from("-"), |max| max.to_string()))).size(14.0).color(text_color)).selectable(false));
+                                            }
+                                            ui.add(Label::new(RichText::new(format!("{} kcal", session.calories)).size(14.0).color(text_color)).selectable(false));
+                                        }
                                     });
                                 });
+                            } else {
+                                ui.horizontal(|ui| {
+                                    ui.vertical(|ui| {
+                                        ui.set_width(side_rect.width() / 2.0);
+                                        ui.vertical_centered(|ui| {
+                                            workout_tracker_widget_front(ctx, ui, Vec2::new(110.0, 249.0), &planned.template.exercises);
+                                        });
+                                    });
 
-                                ui.vertical(|ui| {
-                                    ui.set_width(side_rect.width() / 2.0);
-                                    ui.vertical_centered(|ui| {
-                                        workout_tracker_widget_behind(ctx, ui, Vec2::new(110.0, 249.0), &self.datas.planned_workout_data.workouts.get(&selected_day).unwrap()[index].template.exercises);
+                                    ui.vertical(|ui| {
+                                        ui.set_width(side_rect.width() / 2.0);
+                                        ui.vertical_centered(|ui| {
+                                            workout_tracker_widget_behind(ctx, ui, Vec2::new(110.0, 249.0), &planned.template.exercises);
+                                        });
                                     });
                                 });
+                            }
+
+                            ui.add_space(10.0);
+
+                            ui.allocate_ui(vec2(side_rect.width() - 60.0, 90.0), |ui| {
+                                ui.set_min_height(90.0);
+                                ui.vertical_centered(|ui| {
+                                    for (exercise_index, exercise) in planned.template.exercises.iter().enumerate().take(4) {
+                                        let prescription = planned.template.prescription(exercise_index);
+                                        let suggestion = match (self.datas.all_workout_data.suggest_prescribed(exercise, prescription.as_ref(), planned.program.as_ref()), &prescription) {
+                                            (Some(suggestion), Some(prescription)) => format!("{} ({})", suggestion, prescription),
+                                            (Some(suggestion), None) => suggestion.to_string(),
+                                            (None, Some(prescription)) => prescription.to_string(),
+                                            (None, None) => match self.datas.all_workout_data.last_performance(exercise).and_then(|last| last.working_sets().next()) {
+                                                Some(last) => format!("last {}", last.label(tracking_for(exercise))),
+                                                None => String::from("no history yet"),
+                                            },
+                                        };
+                                        ui.add(Label::new(RichText::new(format!("{}: {}", exercise, suggestion)).size(13.0).color(text_color)).selectable(false));
+                                    }
+
+                                    if planned.template.exercises.len() > 4 {
+                                        ui.add(Label::new(RichText::new(format!("+{} more", planned.template.exercises.len() - 4)).size(12.0)).selectable(false));
+                                    }
+
+                                    if planned.cardio.is_none() { ui.horizontal(|ui| {
+                                        ui.add_space((side_rect.width() - 60.0) / 2.0 - 110.0);
+                                        if ui.add(Button::new(RichText::new(format!("rules: {}", self.datas.all_workout_data.progression.rule)).size(12.0)).frame(false)).clicked() {
+                                            self.states.progression_window = !self.states.progression_window;
+                                        }
+
+                                        let repeat_label = match planned.schedule.and_then(|id| self.datas.planned_workout_data.schedules.iter().find(|schedule| schedule.id == id)) {
+                                            Some(schedule) => schedule.rule.to_string(),
+                                            None => String::from("repeat..."),
+                                        };
+                                        if ui.add(Button::new(RichText::new(repeat_label).size(12.0)).frame(false)).clicked() {
+                                            if planned.schedule.is_some() {
+                                                self.states.occurrence_edit = Some((selected_day, index));
+                                            } else {
+                                                self.states.schedule_template = planned.template.workout_name.clone();
+                                                self.states.schedule_window = true;
+                                            }
+                                        }
+                                    }); }
+                                });
                             });
 
-                            ui.add_space(50.0);
+                            ui.add_space(10.0);
 
-                            ui.add(
-                                Button::new(RichText::new("start").size(22.0).strong().color(Color32::WHITE))
+                            if ui.add_enabled(
+                                !planned.completed,
+                                Button::new(RichText::new(if planned.completed {"done"} else {"start"}).size(22.0).strong().color(Color32::WHITE))
                                     .fill(Color32::from_rgb(21, 141, 0))
                                     .min_size(Vec2::new(side_rect.width() / 4.0, 40.0))
                                     .rounding(10),
-                            );
-
+                            ).clicked() && self.states.session.is_none() {
+                                match &planned.cardio {
+                                    Some(plan) => {
+                                        self.states.cardio_draft = CardioSession::from_plan(plan, selected_day);
+                                        self.states.cardio_planned = Some((selected_day, index));
+                                        self.states.cardio_window = true;
+                                    }
+                                    None => self.states.session = Some(WorkoutSession::new(planned.template.clone(), selected_day, Some(index), planned.program.as_ref(), &self.datas.all_workout_data)),
+                                }
+                            }
+
                             ui.add_space(12.0);
 
                             ui.horizontal(|ui| {
@@ -2032,6 +2860,7 @@ impl Gui<'_> {
                                         .rounding(8),
                                 ).clicked() {
                                     self.datas.planned_workout_data.rest(self.states.selected_day);
+                                    self.datas.touch();
                                 };
 
                                 ui.add_space(spacing);
@@ -2042,11 +2871,16 @@ impl Gui<'_> {
                                         .min_size(Vec2::new(button_width, 30.0))
                                         .rounding(8),
                                 ).clicked() {
-                                    self.states.alert_modal = !self.states.alert_modal;
+                                    if planned.schedule.is_some() {
+                                        self.states.occurrence_edit = Some((selected_day, index));
+                                    } else {
+                                        self.states.alert_modal = !self.states.alert_modal;
+                                    }
                                 };
 
                                 if self.states.delete_was_positive {
                                     self.datas.planned_workout_data.remove_workout(selected_day,  index);
+                                    self.datas.touch();
                                     self.states.delete_was_positive = false;
                                 }
                             });
@@ -2056,6 +2890,31 @@ impl Gui<'_> {
             });
     }
 
+    // equirectangular projection is plenty for the few kilometres of a workout
+    fn draw_route(ui: &mut Ui, route: &[(f64, f64)], size: Vec2, color: Color32) {
+        let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        let (min_lat, max_lat) = route.iter().fold((f64::MAX, f64::MIN), |(lo, hi), (lat, _)| (lo.min(*lat), hi.max(*lat)));
+        let (min_lon, max_lon) = route.iter().fold((f64::MAX, f64::MIN), |(lo, hi), (_, lon)| (lo.min(*lon), hi.max(*lon)));
+        let squash = ((min_lat + max_lat) / 2.0).to_radians().cos();
+
+        let width = ((max_lon - min_lon) * squash).max(1e-9);
+        let height = (max_lat - min_lat).max(1e-9);
+        let scale = ((rect.width() - 8.0) as f64 / width).min((rect.height() - 8.0) as f64 / height);
+        let offset = vec2((rect.width() - (width * scale) as f32) / 2.0, (rect.height() - (height * scale) as f32) / 2.0);
+
+        let points: Vec<Pos2> = route
+            .iter()
+            .map(|(lat, lon)| rect.min + offset + vec2((((lon - min_lon) * squash) * scale) as f32, ((max_lat - lat) * scale) as f32))
+            .collect();
+
+        let painter = ui.painter_at(rect);
+        if let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) {
+            painter.add(egui::Shape::line(points, Stroke::new(2.0, color)));
+            painter.circle_filled(first, 3.5, Color32::from_rgb(21, 141, 0));
+            painter.circle_filled(last, 3.5, Color32::from_rgb(141, 0, 19));
+        }
+    }
+
     fn draw_rect_with_black_shadow(painter: &egui::Painter, rect: Rect, rounding: u8, fill: Color32, offset_x: f32, offset_y: f32, layer: [(f32, u8); 3], corners: Rounding) {
         let shadow_color = |alpha: u8| Color32::from_rgba_unmultiplied(0, 0, 0, alpha);
 
@@ -2107,6 +2966,7 @@ impl Gui<'_> {
 
                             let is_today = date == today;
                             let is_selected = Some(date) == Some(self.states.selected_day);
+                            let is_missed = self.datas.planned_workout_data.has_missed(date, today);
 
                             if ui.add(
                                 Button::new(
@@ -2123,12 +2983,14 @@ impl Gui<'_> {
                                         Color32::from_rgb(96, 96, 96)
                                     },
                                 )
+                                .stroke(if is_missed { Stroke::new(2.0, Color32::from_rgb(200, 30, 30)) } else { Stroke::NONE })
                                 .min_size(Vec2::new(rect_width, 60.0))
                                 .rounding(8),
                             ).clicked() {
                                 self.states.selected_day = date;
-                                self.datas.macro_data.summarize(Some(self.states.selected_day));
+                                self.datas.refresh_burned(self.states.selected_day);
                                 self.datas.water_data.summarize(Some(self.states.selected_day));
+                                self.refresh_schedules();
                             }
                         });
                     }
@@ -2261,10 +3123,47 @@ impl Gui<'_> {
                     ui.add_space(REMAINDER);
 
                     if self.states.show_templates {
-                        for template in self.datas.all_workout_data.workout_templates.values() {
+                        let tags = self.datas.all_workout_data.template_tags();
+                        if !tags.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.add_space(15.0);
+                                if ui.selectable_label(self.states.tag_filter.is_none(), "all").clicked() {
+                                    self.states.tag_filter = None;
+                                }
+                                for tag in tags {
+                                    let selected = self.states.tag_filter.as_ref() == Some(&tag);
+                                    if ui.selectable_label(selected, tag.as_str()).clicked() {
+                                        self.states.tag_filter = if selected { None } else { Some(tag) };
+                                    }
+                                }
+                            });
+                            ui.add_space(10.0);
+                        }
+
+                        // picking a workout shows how recovered each muscle is right now
+                        let fatigue = self.stats.fatigue(&self.datas, Local::now().naive_local());
+                        if !self.states.editable {
+                            ui.horizontal(|ui| {
+                                ui.add_space(ui.available_width() / 2.0 - 60.0);
+                                body_map_front(ctx, ui, vec2(55.0, 124.0), &MuscleShading::Recovery(fatigue.clone()));
+                                body_map_behind(ctx, ui, vec2(55.0, 124.0), &MuscleShading::Recovery(fatigue.clone()));
+                            });
+                            ui.add(Label::new(RichText::new("green is fresh, red still recovering").size(11.0).color(text_color)).selectable(false));
+                            ui.add_space(5.0);
+                        }
+
+                        let tag_filter = self.states.tag_filter.clone();
+                        let mut planned = false;
+                        for template in self.datas.all_workout_data.templates_tagged(tag_filter.as_deref()) {
+                            let label = if self.states.editable {
+                                template.workout_name.clone()
+                            } else {
+                                let (primary, _) = muscle_for_workout(&template.exercises);
+                                format!("{}  -  {:.0}% recovered", template.workout_name, recovery::readiness(&fatigue, &primary) * 100.0)
+                            };
                             if ui.add(
                                 Button::new(
-                                    RichText::new(format!("{}", template.workout_name))
+                                    RichText::new(label)
                                         .size(18.0)
                                         .color(text_color),
                                 )
@@ -2274,19 +3173,24 @@ impl Gui<'_> {
                             ).clicked() {
                                 if self.states.editable {
                                     self.states.current_template = template.workout_name.clone();
+                                    self.states.template_rename = template.workout_name.clone();
                                     self.states.show_templates = !self.states.show_templates;
                                 } else {
                                     if let Some(rst) = self.datas.planned_workout_data.workouts.get_mut(&self.states.selected_day) {
-                                        if rst[0].template.workout_name == "rest" {
+                                        if rst[0].is_rest() {
                                             rst.remove(0);
                                         }
                                     };
-                                    self.datas.planned_workout_data.add_workout(self.states.selected_day, WorkoutPlanned { template: template.clone() , date: self.states.selected_day });
+                                    self.datas.planned_workout_data.add_workout(self.states.selected_day, WorkoutPlanned::new(template.clone(), self.states.selected_day));
+                                    planned = true;
                                     self.states.reset_template_window();
                                 }
                             };
                             ui.add_space(10.0);
                         }
+                        if planned {
+                            self.datas.touch();
+                        }
                         if ui.add(
                             Button::image_and_text(self.medias.plus.clone(),
                                 RichText::new("create template")
@@ -2300,6 +3204,97 @@ impl Gui<'_> {
                             self.states.create_template = !self.states.create_template;
                             self.states.show_templates = !self.states.show_templates;
                         };
+                        ui.add_space(10.0);
+                        if ui.add(
+                            Button::new(
+                                RichText::new("generate workout")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.generated = None;
+                            self.states.generator_message.clear();
+                            self.states.generator_window = true;
+                        };
+                        ui.add_space(10.0);
+                        if !self.states.editable && ui.add(
+                            Button::new(
+                                RichText::new("cardio")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.cardio_draft = CardioSession::new(CardioKind::Running, self.states.selected_day);
+                            self.states.cardio_planned = None;
+                            self.states.cardio_window = true;
+                        };
+                        ui.add_space(10.0);
+                        if ui.add(
+                            Button::image_and_text(self.medias.calendar.clone(),
+                                RichText::new("programs")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.programs_window = true;
+                        };
+                        ui.add_space(10.0);
+                        if ui.add(
+                            Button::image_and_text(self.medias.switch.clone(),
+                                RichText::new("import / export")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.sharing_window = true;
+                        };
+                        ui.add_space(10.0);
+                        if ui.add(
+                            Button::new(
+                                RichText::new("plate calculator")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.plate_set = None;
+                            self.states.plates_window = true;
+                        };
+                        ui.add_space(10.0);
+                        if ui.add(
+                            Button::new(
+                                RichText::new("history")
+                                    .size(18.0)
+                                    .color(text_color),
+                            )
+                            .fill(other_elements_color)
+                            .min_size(button_size)
+                            .rounding(8),
+                        ).clicked() {
+                            self.states.reset_template_window();
+                            self.states.history_selected = None;
+                            self.states.history_draft = None;
+                            self.states.history_window = true;
+                        };
                     } else if self.states.create_template {
                         // ui.vertical_centered(|ui| {
                         // ui.set_width(400.0);
@@ -2494,6 +3489,11 @@ impl Gui<'_> {
                                                     Button::image_and_text(self.medias.left_arrow.clone(), "back").rounding(8)
                                                 ).clicked() {
                                                     self.states.current_template.clear();
+                                                    self.states.group_selection.clear();
+                                                    self.states.prescription_index = None;
+                                                    self.states.swap_index = None;
+                                                    self.states.confirm_delete = false;
+                                                    self.states.template_message.clear();
                                                     self.states.show_templates = !self.states.show_templates;
                                                 }
                                             });
@@ -2538,6 +3538,8 @@ impl Gui<'_> {
                                                         .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
                                                         .show(ui, |ui| {
                                                             if let Some(template) = self.datas.all_workout_data.workout_templates.get_mut(&self.states.current_template) {
+                                                                let labels = template.labels();
+                                                                let mut moved: Option<(usize, usize)> = None;
                                                                 for (index, exercise) in template.exercises.clone().iter().enumerate() {
                                                                     ui.vertical_centered(|ui| {
                                                                         ui.set_height(42.0);
@@ -2547,6 +3549,14 @@ impl Gui<'_> {
 
                                                                         ui.painter().rect_filled(rect, 8, other_elements_color);
 
+                                                                        let drop_zone = ui.interact(rect, Id::new(("template_exercise_row", index)), Sense::hover());
+                                                                        if drop_zone.dnd_hover_payload::<usize>().is_some() {
+                                                                            ui.painter().rect_stroke(rect, 8, Stroke::new(1.5, Color32::from_rgb(59, 163, 255)), StrokeKind::Inside);
+                                                                        }
+                                                                        if let Some(from) = drop_zone.dnd_release_payload::<usize>() {
+                                                                            moved = Some((*from, index));
+                                                                        }
+
                                                                         ui.allocate_ui_at_rect(rect, |ui| {
                                                                             // ui.add_space(10.0);
                                                                             ui.horizontal(|ui| {
@@ -2561,11 +3571,38 @@ impl Gui<'_> {
                                                                                         strip.cell(|ui| {
                                                                                             ui.vertical(|ui| {
                                                                                                 ui.add_space(10.0);
-                                                                                                ui.add(Label::new(RichText::new(exercise.to_string()).size(15.0).color(text_color)));
+                                                                                                ui.dnd_drag_source(Id::new(("template_exercise", index)), index, |ui| {
+                                                                                                    ui.add(Label::new(RichText::new(format!("{} {}", labels[index], exercise)).size(15.0).color(text_color)).selectable(false));
+                                                                                                }).response.on_hover_cursor(CursorIcon::Grab);
                                                                                             });
                                                                                         });
 
-                                                                                        strip.empty();
+                                                                                        strip.cell(|ui| {
+                                                                                            ui.vertical_centered(|ui| {
+                                                                                                ui.add_space(10.0);
+                                                                                                ui.horizontal(|ui| {
+                                                                                                    let mut selected = self.states.group_selection.contains(&index);
+                                                                                                    if ui.checkbox(&mut selected, "").changed() {
+                                                                                                        if selected {
+                                                                                                            self.states.group_selection.push(index);
+                                                                                                        } else {
+                                                                                                            self.states.group_selection.retain(|i| *i != index);
+                                                                                                        }
+                                                                                                    }
+
+                                                                                                    let editing = self.states.prescription_index == Some(index);
+                                                                                                    let rx = template.prescription(index).map_or(String::from("rx"), |prescription| prescription.short());
+                                                                                                    if ui.selectable_label(editing, RichText::new(rx).size(11.0)).clicked() {
+                                                                                                        self.states.prescription_index = if editing { None } else { Some(index) };
+                                                                                                    }
+
+                                                                                                    let swapping = self.states.swap_index == Some(index);
+                                                                                                    if ui.selectable_label(swapping, RichText::new("swap").size(11.0)).clicked() {
+                                                                                                        self.states.swap_index = if swapping { None } else { Some(index) };
+                                                                                                    }
+                                                                                                });
+                                                                                            });
+                                                                                        });
 
                                                                                         strip.cell(|ui| {
                                                                                             ui.vertical_centered(|ui| {
@@ -2574,7 +3611,10 @@ impl Gui<'_> {
                                                                                                     vec2(35.0, 35.0),
                                                                                                     ImageButton::new(Image::new(self.medias.remove.clone())).frame(false)
                                                                                                 ).clicked() {
-                                                                                                    template.exercises.remove(index);
+                                                                                                    template.remove_exercise(index);
+                                                                                                    self.states.group_selection.clear();
+                                                                                                    self.states.prescription_index = None;
+                                                                                                    self.states.swap_index = None;
                                                                                                 };
                                                                                             });
                                                                                         });
@@ -2584,7 +3624,215 @@ impl Gui<'_> {
                                                                         });
                                                                     ui.add_space(REMAINDER);
                                                                 }
+
+                                                                if let Some((from, to)) = moved {
+                                                                    template.move_exercise(from, to);
+                                                                    self.states.group_selection.clear();
+                                                                    self.states.prescription_index = None;
+                                                                    self.states.swap_index = None;
+                                                                }
+
+                                                                if let Some(index) = self.states.swap_index.filter(|index| *index < template.exercises.len()) {
+                                                                    let picked = ui.vertical(|ui| {
+                                                                        Self::draw_substitutes(ui, &template.exercises[index], &mut self.datas.all_workout_data.unavailable_equipment, self.datas.all_workout_data.landmarks.secondary_share, text_color)
+                                                                    }).inner;
+                                                                    if let Some(exercise) = picked {
+                                                                        template.replace_exercise(index, exercise);
+                                                                        self.states.swap_index = None;
+                                                                    }
+                                                                    ui.add_space(REMAINDER);
+                                                                }
+
+                                                                if let Some(index) = self.states.prescription_index.filter(|index| *index < template.exercises.len()) {
+                                                                    let mut prescription = template.prescription(index).unwrap_or_default();
+                                                                    let mut cleared = false;
+                                                                    let mut changed = false;
+
+                                                                    ui.vertical(|ui| {
+                                                                        ui.add(Label::new(RichText::new(format!("{} prescription", template.exercises[index])).size(15.0).strong().color(text_color)));
+
+                                                                        egui::Grid::new("prescription_grid").num_columns(2).spacing(vec2(15.0, 6.0)).show(ui, |ui| {
+                                                                            ui.label("sets");
+                                                                            changed |= ui.add(egui::DragValue::new(&mut prescription.sets).range(1..=20)).changed();
+                                                                            ui.end_row();
+
+                                                                            ui.label("reps");
+                                                                            ui.horizontal(|ui| {
+                                                                                changed |= ui.add(egui::DragValue::new(&mut prescription.min_reps).range(1..=100)).changed();
+                                                                                ui.label("-");
+                                                                                changed |= ui.add(egui::DragValue::new(&mut prescription.max_reps).range(prescription.min_reps..=100)).changed();
+                                                                            });
+                                                                            ui.end_row();
+
+                                                                            ui.label("load");
+                                                                            ui.horizontal(|ui| {
+                                                                                let kinds = ["auto", "kg", "% 1rm", "rpe"];
+                                                                                let selected = match prescription.load {
+                                                                                    None => 0,
+                                                                                    Some(TargetLoad::Absolute(_)) => 1,
+                                                                                    Some(TargetLoad::PercentOneRm(_)) => 2,
+                                                                                    Some(TargetLoad::Rpe(_)) => 3,
+                                                                                };
+                                                                                egui::ComboBox::from_id_salt("prescription_load")
+                                                                                    .width(70.0)
+                                                                                    .selected_text(kinds[selected])
+                                                                                    .show_ui(ui, |ui| {
+                                                                                        if ui.selectable_label(selected == 0, kinds[0]).clicked() { prescription.load = None; changed = true; }
+                                                                                        if ui.selectable_label(selected == 1, kinds[1]).clicked() { prescription.load = Some(TargetLoad::Absolute(20.0)); changed = true; }
+                                                                                        if ui.selectable_label(selected == 2, kinds[2]).clicked() { prescription.load = Some(TargetLoad::PercentOneRm(75.0)); changed = true; }
+                                                                                        if ui.selectable_label(selected == 3, kinds[3]).clicked() { prescription.load = Some(TargetLoad::Rpe(8.0)); changed = true; }
+                                                                                    });
+
+                                                                                match &mut prescription.load {
+                                                                                    Some(TargetLoad::Absolute(weight)) => { changed |= ui.add(egui::DragValue::new(weight).speed(0.5).range(0.0..=500.0).suffix(" kg")).changed(); }
+                                                                                    Some(TargetLoad::PercentOneRm(percent)) => { changed |= ui.add(egui::DragValue::new(percent).speed(1.0).range(30.0..=100.0).suffix(" %")).changed(); }
+                                                                                    Some(TargetLoad::Rpe(rpe)) => { changed |= ui.add(egui::DragValue::new(rpe).speed(0.5).range(5.0..=10.0)).changed(); }
+                                                                                    None => {}
+                                                                                }
+                                                                            });
+                                                                            ui.end_row();
+
+                                                                            ui.label("tempo");
+                                                                            changed |= ui.add_sized(vec2(70.0, 20.0), TextEdit::singleline(&mut prescription.tempo).hint_text("3010").char_limit(7)).changed();
+                                                                            ui.end_row();
+
+                                                                            ui.label("rest");
+                                                                            changed |= ui.add(egui::DragValue::new(&mut prescription.rest_secs).speed(5).range(0..=600).suffix(" s")).changed();
+                                                                            ui.end_row();
+                                                                        });
+
+                                                                        cleared = ui.button("clear prescription").clicked();
+                                                                    });
+
+                                                                    if cleared {
+                                                                        template.set_prescription(index, None);
+                                                                        self.states.prescription_index = None;
+                                                                    } else if changed {
+                                                                        template.set_prescription(index, Some(prescription));
+                                                                    }
+                                                                    ui.add_space(REMAINDER);
+                                                                }
+
+                                                                ui.horizontal(|ui| {
+                                                                    ui.add_space(15.0);
+                                                                    let enough = self.states.group_selection.len() > 1;
+                                                                    for kind in GroupKind::iter() {
+                                                                        if ui.add_enabled(enough, Button::new(RichText::new(kind.to_string()).size(13.0).color(text_color)).fill(other_elements_color).rounding(5.0)).clicked() {
+                                                                            self.states.group_selection.sort();
+                                                                            template.group_exercises(&self.states.group_selection, kind);
+                                                                            self.states.group_selection.clear();
+                                                                        }
+                                                                    }
+                                                                    if ui.add_enabled(!self.states.group_selection.is_empty(), Button::new(RichText::new("ungroup").size(13.0).color(text_color)).fill(other_elements_color).rounding(5.0)).clicked() {
+                                                                        template.ungroup(&self.states.group_selection);
+                                                                        self.states.group_selection.clear();
+                                                                    }
+                                                                });
+
+                                                                let group_labels: Vec<(u32, String)> = (0..template.exercises.len())
+                                                                    .filter_map(|index| template.slot(index).group.map(|id| (id, labels[index][..1].to_string())))
+                                                                    .fold(Vec::new(), |mut seen, (id, letter)| {
+                                                                        if !seen.iter().any(|(g, _)| *g == id) {
+                                                                            seen.push((id, letter));
+                                                                        }
+                                                                        seen
+                                                                    });
+
+                                                                for (id, letter) in group_labels {
+                                                                    if let Some(group) = template.groups.iter_mut().find(|group| group.id == id) {
+                                                                        ui.horizontal(|ui| {
+                                                                            ui.add_space(15.0);
+                                                                            ui.add(Label::new(RichText::new(format!("{}: {}", letter, group.kind)).size(14.0).color(text_color)));
+                                                                            ui.add(egui::DragValue::new(&mut group.rounds).range(1..=20).suffix(" rounds"));
+                                                                            ui.add(egui::DragValue::new(&mut group.rest_secs).speed(5).range(0..=600).prefix("rest ").suffix(" s"));
+                                                                        });
+                                                                    }
+                                                                }
+                                                                ui.add_space(REMAINDER);
+
+                                                                ui.horizontal_wrapped(|ui| {
+                                                                    ui.add_space(15.0);
+                                                                    ui.add(Label::new(RichText::new("tags").size(14.0).color(text_color)));
+                                                                    let mut removed = None;
+                                                                    for tag in template.tags.iter() {
+                                                                        if ui.button(format!("{} x", tag)).clicked() {
+                                                                            removed = Some(tag.clone());
+                                                                        }
+                                                                    }
+                                                                    if let Some(tag) = removed {
+                                                                        template.tags.retain(|t| *t != tag);
+                                                                    }
+                                                                    let response = ui.add_sized(vec2(90.0, 20.0), TextEdit::singleline(&mut self.states.template_tag).hint_text("new tag"));
+                                                                    if ui.button("add").clicked() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
+                                                                        template.add_tag(&self.states.template_tag);
+                                                                        self.states.template_tag.clear();
+                                                                    }
+                                                                });
+                                                                ui.add_space(REMAINDER);
+                                                            }
+
+                                                            let today = Local::now().date_naive();
+                                                            let current = self.states.current_template.clone();
+
+                                                            ui.horizontal(|ui| {
+                                                                ui.add_space(15.0);
+                                                                ui.add_sized(vec2(150.0, 22.0), TextEdit::singleline(&mut self.states.template_rename));
+                                                                if ui.button("rename").clicked() {
+                                                                    match self.datas.rename_template(&current, &self.states.template_rename) {
+                                                                        Ok(()) => {
+                                                                            self.states.current_template = self.states.template_rename.trim().to_string();
+                                                                            self.states.template_message.clear();
+                                                                        }
+                                                                        Err(message) => self.states.template_message = message,
+                                                                    }
+                                                                }
+                                                                if ui.button("duplicate").clicked() {
+                                                                    if let Some(copy) = self.datas.all_workout_data.duplicate_template(&current) {
+                                                                        self.states.current_template = copy.clone();
+                                                                        self.states.template_rename = copy;
+                                                                        self.states.group_selection.clear();
+                                                                        self.states.prescription_index = None;
+                                                                        self.states.swap_index = None;
+                                                                        self.states.template_message.clear();
+                                                                    }
+                                                                }
+                                                                if ui.button("delete").clicked() {
+                                                                    match self.datas.delete_template(&current, today, false) {
+                                                                        Ok(()) => {
+                                                                            self.states.current_template.clear();
+                                                                            self.states.show_templates = true;
+                                                                        }
+                                                                        Err(message) => {
+                                                                            self.states.template_message = message;
+                                                                            self.states.confirm_delete = true;
+                                                                        }
+                                                                    }
+                                                                }
+                                                            });
+
+                                                            if !self.states.template_message.is_empty() {
+                                                                ui.add(Label::new(RichText::new(&self.states.template_message).size(13.0).color(Color32::from_rgb(255, 90, 90))));
+                                                            }
+
+                                                            if self.states.confirm_delete {
+                                                                ui.horizontal(|ui| {
+                                                                    ui.add_space(15.0);
+                                                                    ui.add(Label::new(RichText::new("delete anyway? upcoming uses will be removed").size(13.0).color(text_color)));
+                                                                    if ui.button("delete").clicked() {
+                                                                        if self.datas.delete_template(&current, today, true).is_ok() {
+                                                                            self.states.current_template.clear();
+                                                                            self.states.confirm_delete = false;
+                                                                            self.states.template_message.clear();
+                                                                            self.states.show_templates = true;
+                                                                        }
+                                                                    }
+                                                                    if ui.button("keep").clicked() {
+                                                                        self.states.confirm_delete = false;
+                                                                        self.states.template_message.clear();
+                                                                    }
+                                                                });
                                                             }
+                                                            ui.add_space(REMAINDER);
                                                         if ui.add(
                                                             Button::image_and_text(self.medias.plus.clone(),
                                                                 RichText::new("add exercise")
@@ -2660,8 +3908,41 @@ impl Gui<'_> {
                 ui.add_space(REMAINDER);
                 ui.vertical_centered(|ui| {
                     if self.states.show_exercises {
+                        ui.add_sized(vec2(300.0, 24.0), TextEdit::singleline(&mut self.states.note_query).hint_text("search notes"));
                         ScrollArea::vertical().show(ui, |ui| {
                             ui.add_space(REMAINDER);
+                            if !self.states.note_query.trim().is_empty() {
+                                let hits = self.datas.all_workout_data.search_notes(&self.states.note_query);
+                                if hits.is_empty() {
+                                    ui.add(Label::new(RichText::new("no notes match").size(14.0).color(text_color)));
+                                }
+                                for hit in hits {
+                                    let place = hit.exercise.as_ref().map_or(hit.workout.clone(), |exercise| format!("{} - {}", hit.workout, exercise));
+                                    let response = egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                                        ui.set_width(button_size.x);
+                                        ui.add(Label::new(RichText::new(format!("{}  {}", hit.date.format("%b %e, %Y"), place)).size(12.0).strong().color(text_color)).selectable(false));
+                                        ui.add(Label::new(RichText::new(&hit.text).size(14.0).color(text_color)).wrap().selectable(false));
+                                    }).response.interact(Sense::click());
+                                    // exercise notes open that exercise's history, session notes the workout itself
+                                    if response.clicked() && !self.states.templates_window {
+                                        match hit.exercise {
+                                            Some(exercise) => {
+                                                self.states.current_exercise = exercise;
+                                                self.states.show_exercises = false;
+                                            }
+                                            None => {
+                                                self.states.history_selected = Some(hit.index);
+                                                self.states.history_draft = None;
+                                                self.states.history_confirm_delete = false;
+                                                self.states.history_window = true;
+                                                self.states.exercises_window = false;
+                                            }
+                                        }
+                                    }
+                                    ui.add_space(5.0);
+                                }
+                                return;
+                            }
                             for exercise in Exercises::iter() {
                                 if ui.add(
                                     Button::new(
@@ -2677,7 +3958,7 @@ impl Gui<'_> {
                                         if self.states.create_template {
                                             self.states.new_template_exercises.push(exercise);
                                         } else {
-                                            self.datas.all_workout_data.workout_templates.get_mut(&self.states.current_template).unwrap().exercises.push(exercise);
+                                            self.datas.all_workout_data.workout_templates.get_mut(&self.states.current_template).unwrap().push_exercise(exercise);
                                         }
                                         self.states.exercises_window = false;
                                     } else {
@@ -2783,7 +4064,30 @@ impl Gui<'_> {
                                                 });
 
                                                 strip.cell(|ui| {
-                                                    ui.add(Label::new(RichText::new("statistics:").size(18.0).color(text_color)));
+                                                    ui.add(Label::new(RichText::new("history").size(18.0).color(text_color)));
+                                                    let mode = tracking_for(&self.states.current_exercise);
+                                                    let history = self.datas.all_workout_data.exercise_history(&self.states.current_exercise);
+                                                    ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |ui| {
+                                                        if history.is_empty() {
+                                                            ui.add(Label::new(RichText::new("not logged yet").size(14.0).color(text_color)));
+                                                        }
+                                                        for (workout, done) in history {
+                                                            egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                                                                ui.set_width(ui.available_width());
+                                                                let rating = workout.rating.map(|stars| format!("  {}/5", stars)).unwrap_or_default();
+                                                                ui.add(Label::new(RichText::new(format!("{}  {}{}", workout.date.format("%b %e, %Y"), workout.template.workout_name, rating)).size(13.0).strong().color(text_color)));
+                                                                for set in done.sets.iter() {
+                                                                    let note = if set.note.is_empty() { String::new() } else { format!("  - {}", set.note) };
+                                                                    let (prefix, color) = if set.warmup { ("warm-up ", Color32::GRAY) } else { ("", text_color) };
+                                                                    ui.add(Label::new(RichText::new(format!("{}{}{}", prefix, set.label(mode), note)).size(13.0).color(color)));
+                                                                }
+                                                                for notes in [&done.notes, &workout.notes].into_iter().filter(|notes| !notes.is_empty()) {
+                                                                    ui.add(Label::new(RichText::new(notes.as_str()).size(12.0).italics().color(Color32::GRAY)).wrap());
+                                                                }
+                                                            });
+                                                            ui.add_space(5.0);
+                                                        }
+                                                    });
                                                 })
                                             });
                                     }
@@ -2857,4 +4161,1837 @@ impl Gui<'_> {
             self.states.exercises_window = false;
         }
     }
+
+    pub fn draw_session_window(&mut self, ui: &mut Ui, ctx: &Context, is_dark: bool, other_elements_color: Color32, text_color: Color32) {
+        let screen_rect = ctx.screen_rect();
+
+        ui.painter().rect_filled(
+            screen_rect,
+            0.0,
+            if is_dark {
+                Color32::from_rgba_unmultiplied(20, 20, 20,150)
+            } else {
+                Color32::from_rgba_unmultiplied(240, 240, 240, 150)
+            }
+        );
+
+        Area::new("modal_blocker".into())
+            .order(Order::Background)
+            .fixed_pos(screen_rect.min)
+            .show(ctx, |ui| {
+                let _response = ui.allocate_response(screen_rect.size(), Sense::click());
+            });
+
+        let window_size = vec2(450.0, 650.0);
+        let mut finished = false;
+        let mut cancelled = false;
+
+        let rpe_mode = self.datas.all_workout_data.progression.rule == ProgressionRule::Rpe;
+
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+
+        Window::new("workout session")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                let Some(session) = self.states.session.as_mut() else { return; };
+                let elapsed = session.elapsed_secs();
+
+                let labels = session.template.labels();
+                let rest = session.rest_remaining();
+
+                ui.vertical_centered(|ui| {
+                    ui.add(Label::new(RichText::new(&session.template.workout_name).size(23.0).strong().color(text_color)).selectable(false));
+                    ui.add(Label::new(RichText::new(format!("{:02}:{:02}", elapsed / 60, elapsed % 60)).size(18.0).color(text_color)).selectable(false));
+
+                    if rest > 0 {
+                        ui.horizontal(|ui| {
+                            ui.add(Label::new(RichText::new(format!("rest {:02}:{:02}", rest / 60, rest % 60)).size(18.0).strong().color(Color32::from_rgb(59, 163, 255))).selectable(false));
+                            if ui.button("skip").clicked() {
+                                session.rest_until = None;
+                            }
+                        });
+                    }
+
+                    if let Some(step) = session.current_step() {
+                        let next = session.exercises[step.exercise].exercise.clone();
+                        let sets = &session.exercises[step.exercise].sets;
+                        let set_label = if sets[step.set].set.warmup {
+                            String::from("warm-up")
+                        } else {
+                            let number = sets[..=step.set].iter().filter(|s| !s.set.warmup && s.set.side != Some(Side::Right)).count();
+                            format!("set {}{}", number, sets[step.set].set.side.map(|side| format!(" {}", side)).unwrap_or_default())
+                        };
+                        ui.horizontal(|ui| {
+                            ui.add(Label::new(RichText::new(format!("next: {} {} - {}", labels[step.exercise], next, set_label)).size(15.0).color(text_color)).selectable(false));
+                            if ui.add(Button::new(RichText::new("complete set").size(14.0).color(Color32::WHITE))
+                                .fill(Color32::from_rgb(21, 141, 0))
+                                .rounding(5.0)).clicked() {
+                                    session.complete_set(step.exercise, step.set);
+                                }
+                        });
+                    }
+                });
+
+                ui.add_space(REMAINDER);
+
+                let mut completed = None;
+                let mut swapped = None;
+                let mut plates_for = None;
+                let mut reweighed = None;
+                let mut start_timer = None;
+                let mut stop_timer = false;
+                session.tick();
+                let timer = session.timer.clone();
+
+                ScrollArea::vertical()
+                    .max_height(window_size.y - 120.0)
+                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+                    .show(ui, |ui| {
+                        for (exercise_index, session_exercise) in session.exercises.iter_mut().enumerate() {
+                            ui.vertical(|ui| {
+                                let group = session.template.group_of(exercise_index).map(|group| format!(" ({})", group.kind)).unwrap_or_default();
+                                ui.horizontal(|ui| {
+                                    ui.add(Label::new(RichText::new(format!("{} {}{}", labels[exercise_index], session_exercise.exercise, group)).size(17.0).strong().color(text_color)));
+                                    // only exercises nothing was logged for can be swapped
+                                    if session_exercise.sets.iter().all(|set| !set.done) {
+                                        let swapping = self.states.session_swap == Some(exercise_index);
+                                        if ui.selectable_label(swapping, RichText::new("swap").size(12.0)).clicked() {
+                                            self.states.session_swap = if swapping { None } else { Some(exercise_index) };
+                                        }
+                                    }
+                                });
+
+                                if self.states.session_swap == Some(exercise_index) {
+                                    if let Some(exercise) = Self::draw_substitutes(ui, &session_exercise.exercise, &mut self.datas.all_workout_data.unavailable_equipment, self.datas.all_workout_data.landmarks.secondary_share, text_color) {
+                                        swapped = Some((exercise_index, exercise));
+                                    }
+                                }
+
+                                if let Some(prescription) = session.template.prescription(exercise_index) {
+                                    ui.add(Label::new(RichText::new(format!("prescribed: {}", prescription)).size(12.0).color(text_color)));
+                                }
+
+                                if let Some(suggestion) = &session_exercise.suggestion {
+                                    ui.add(Label::new(RichText::new(format!("suggested: {}", suggestion)).size(12.0).color(Color32::from_rgb(59, 163, 255))));
+                                }
+
+                                ui.add(TextEdit::singleline(&mut session_exercise.notes).hint_text("exercise note").desired_width(260.0));
+
+                                let barbell = equipment_for(&session_exercise.exercise) == Equipment::Barbell;
+                                let mode = tracking_for(&session_exercise.exercise);
+                                let mut working = 0;
+                                for (set_index, session_set) in session_exercise.sets.iter_mut().enumerate() {
+                                    let label = match (session_set.set.warmup, session_set.set.side) {
+                                        (true, _) => String::from("warm-up"),
+                                        (false, Some(Side::Right)) => format!("set {} {}", working, Side::Right),
+                                        (false, side) => {
+                                            working += 1;
+                                            format!("set {}{}", working, side.map(|side| format!(" {}", side)).unwrap_or_default())
+                                        }
+                                    };
+                                    ui.horizontal(|ui| {
+                                        ui.add(Label::new(RichText::new(label).size(14.0).color(if session_set.set.warmup { Color32::GRAY } else { text_color })));
+                                        match mode {
+                                            TrackingMode::WeightReps => {
+                                                if ui.add(egui::DragValue::new(&mut session_set.set.weight).speed(0.5).range(0.0..=500.0).suffix(" kg")).changed() && !session_set.set.warmup {
+                                                    reweighed = Some(exercise_index);
+                                                }
+                                                if barbell && ui.small_button("plates").clicked() {
+                                                    plates_for = Some((exercise_index, set_index, session_set.set.weight));
+                                                }
+                                                ui.add(egui::DragValue::new(&mut session_set.set.reps).range(0..=100).suffix(" reps"));
+                                            }
+                                            TrackingMode::BodyweightPlus => {
+                                                ui.add(egui::DragValue::new(&mut session_set.set.weight).speed(0.5).range(0.0..=200.0).prefix("bw + ").suffix(" kg"));
+                                                ui.add(egui::DragValue::new(&mut session_set.set.reps).range(0..=100).suffix(" reps"));
+                                            }
+                                            TrackingMode::RepsOnly => {
+                                                ui.add(egui::DragValue::new(&mut session_set.set.reps).range(0..=500).suffix(" reps"));
+                                            }
+                                            TrackingMode::TimeOnly | TrackingMode::DistanceTime => {
+                                                if mode == TrackingMode::DistanceTime {
+                                                    ui.add(egui::DragValue::new(&mut session_set.set.distance_m).speed(1.0).range(0.0..=10000.0).suffix(" m"));
+                                                    ui.add(egui::DragValue::new(&mut session_set.set.weight).speed(0.5).range(0.0..=200.0).suffix(" kg"));
+                                                }
+                                                match timer.as_ref().filter(|timer| timer.exercise == exercise_index && timer.set == set_index) {
+                                                    Some(running) => {
+                                                        let secs = running.display_secs();
+                                                        ui.add(Label::new(RichText::new(format!("{}{}:{:02}", if running.countdown.is_some() { "-" } else { "" }, secs / 60, secs % 60)).size(16.0).strong().color(Color32::from_rgb(59, 163, 255))));
+                                                        if ui.small_button("stop").clicked() {
+                                                            stop_timer = true;
+                                                        }
+                                                    }
+                                                    None => {
+                                                        ui.add(egui::DragValue::new(&mut session_set.set.duration_secs).speed(1.0).range(0..=7200).suffix(" s"));
+                                                        if !session_set.done && timer.is_none() {
+                                                            if ui.small_button("stopwatch").clicked() {
+                                                                start_timer = Some((exercise_index, set_index, false));
+                                                            }
+                                                            if session_set.set.duration_secs > 0 && ui.small_button("countdown").clicked() {
+                                                                start_timer = Some((exercise_index, set_index, true));
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if rpe_mode && !mode.is_timed() {
+                                            let mut rpe = session_set.set.rpe.unwrap_or(8.0);
+                                            if ui.add(egui::DragValue::new(&mut rpe).speed(0.5).range(5.0..=10.0).prefix("rpe ")).changed() {
+                                                session_set.set.rpe = Some(rpe);
+                                            }
+                                        }
+                                        ui.add(TextEdit::singleline(&mut session_set.set.note).hint_text("note").desired_width(70.0));
+                                        let mut done = session_set.done;
+                                        if ui.checkbox(&mut done, "done").changed() {
+                                            if done {
+                                                completed = Some((exercise_index, set_index));
+                                            } else {
+                                                session_set.done = false;
+                                            }
+                                        }
+                                    });
+                                }
+
+                                if ui.add(Button::image_and_text(self.medias.plus.clone(), RichText::new("add set").size(13.0).color(text_color))
+                                    .fill(other_elements_color)
+                                    .rounding(5.0)
+                                ).clicked() {
+                                    let mut last = session_exercise.sets.iter().rev().find(|s| !s.set.warmup).map(|s| s.set.clone()).unwrap_or(SetDone::default());
+                                    last.note.clear();
+                                    for set in SessionExercise::sides(&session_exercise.exercise, &last) {
+                                        session_exercise.sets.push(SessionSet { set, done: false });
+                                    }
+                                }
+                            });
+                            ui.separator();
+                        }
+                    });
+
+                if let Some(index) = reweighed {
+                    session.refresh_warmups(index, &self.datas.all_workout_data);
+                }
+
+                if let Some((exercise, set)) = completed {
+                    session.complete_set(exercise, set);
+                }
+
+                if let Some((index, exercise)) = swapped {
+                    session.swap_exercise(index, exercise, &self.datas.all_workout_data);
+                    self.states.session_swap = None;
+                }
+
+                if let Some((exercise, set, countdown)) = start_timer {
+                    session.start_timer(exercise, set, countdown);
+                } else if stop_timer {
+                    session.stop_timer();
+                }
+
+                if let Some((exercise, set, weight)) = plates_for {
+                    self.states.plate_set = Some((exercise, set));
+                    self.states.plate_target = self.datas.all_workout_data.plates.unit.from_kg(weight);
+                    self.states.plates_window = true;
+                }
+
+                ui.add_space(REMAINDER);
+
+                ui.add(TextEdit::multiline(&mut session.notes).hint_text("session notes").desired_rows(2).desired_width(f32::INFINITY));
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(RichText::new("rating").size(14.0).color(text_color)));
+                    for stars in 1..=5u8 {
+                        if ui.selectable_label(session.rating.is_some_and(|rating| rating >= stars), stars.to_string()).clicked() {
+                            session.rating = if session.rating == Some(stars) { None } else { Some(stars) };
+                        }
+                    }
+                    ui.add_space(10.0);
+                    ui.add(Label::new(RichText::new("energy").size(14.0).color(text_color)));
+                    for level in 1..=5u8 {
+                        if ui.selectable_label(session.energy == Some(level), level.to_string()).clicked() {
+                            session.energy = if session.energy == Some(level) { None } else { Some(level) };
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(RichText::new("session rpe").size(14.0).color(text_color)));
+                    let mut rpe = session.rpe.unwrap_or(self.datas.all_workout_data.load.default_rpe);
+                    if ui.add(egui::DragValue::new(&mut rpe).speed(0.5).range(1.0..=10.0)).changed() {
+                        session.rpe = Some(rpe);
+                    }
+                });
+
+                StripBuilder::new(ui)
+                    .size(Size::relative(0.5))
+                    .size(Size::relative(0.5))
+                    .horizontal(|mut strip| {
+                        strip.cell(|ui| {
+                            ui.vertical_centered(|ui| {
+                                if ui.add(Button::new(RichText::new("cancel").size(16.0).strong().color(Color32::WHITE))
+                                    .fill(Color32::from_rgb(96, 96, 96))
+                                    .min_size(vec2(150.0, 35.0))
+                                    .rounding(8)).clicked() {
+                                        cancelled = true;
+                                    }
+                            });
+                        });
+
+                        strip.cell(|ui| {
+                            ui.vertical_centered(|ui| {
+                                if ui.add(Button::new(RichText::new("finish").size(16.0).strong().color(Color32::WHITE))
+                                    .fill(Color32::from_rgb(21, 141, 0))
+                                    .min_size(vec2(150.0, 35.0))
+                                    .rounding(8)).clicked() {
+                                        finished = true;
+                                    }
+                            });
+                        });
+                    });
+            });
+
+        if finished {
+            if let Some(session) = self.states.session.take() {
+                let body_weight = self.datas.user_information.body_weight();
+                self.datas.record_workout(session.finish(body_weight));
+
+                if let Some(index) = session.planned_index {
+                    if let Some(planned) = self.datas.planned_workout_data.workouts.get_mut(&session.date).and_then(|w| w.get_mut(index)) {
+                        planned.completed = true;
+                    }
+                }
+            }
+        } else if cancelled {
+            self.states.session = None;
+        }
+
+        if finished || cancelled {
+            self.states.session_swap = None;
+        }
+    }
+
+    pub fn draw_progression_window(&mut self, ctx: &Context, text_color: Color32, open: &mut bool) {
+        let window_size = vec2(320.0, 420.0);
+        let progression = &mut self.datas.all_workout_data.progression;
+        let warmups = &mut self.datas.all_workout_data.warmups;
+
+        Window::new("progression rules")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                egui::Grid::new("progression_grid").num_columns(2).spacing(vec2(20.0, 8.0)).show(ui, |ui| {
+                    ui.add(Label::new(RichText::new("rule").size(15.0).color(text_color)));
+                    egui::ComboBox::from_id_salt("progression_rule")
+                        .selected_text(progression.rule.to_string())
+                        .show_ui(ui, |ui| {
+                            for rule in ProgressionRule::iter() {
+                                ui.selectable_value(&mut progression.rule, rule, rule.to_string());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("increment").size(15.0).color(text_color)));
+                    ui.add(egui::DragValue::new(&mut progression.weight_increment).speed(0.25).range(0.25..=20.0).suffix(" kg"));
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("rep range").size(15.0).color(text_color)));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut progression.min_reps).range(1..=progression.max_reps));
+                        ui.label("-");
+                        ui.add(egui::DragValue::new(&mut progression.max_reps).range(progression.min_reps..=50));
+                    });
+                    ui.end_row();
+
+                    if progression.rule == ProgressionRule::Rpe {
+                        ui.add(Label::new(RichText::new("target rpe").size(15.0).color(text_color)));
+                        ui.add(egui::DragValue::new(&mut progression.target_rpe).speed(0.5).range(5.0..=10.0));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.checkbox(&mut warmups.enabled, RichText::new("generate warm-up sets").size(15.0).color(text_color));
+
+                if warmups.enabled {
+                    ui.horizontal_wrapped(|ui| {
+                        for exercise in Exercises::iter().filter(|exercise| equipment_for(exercise) == Equipment::Barbell) {
+                            let mut selected = warmups.exercises.contains(&exercise);
+                            if ui.checkbox(&mut selected, exercise.to_string()).changed() {
+                                if selected {
+                                    warmups.exercises.push(exercise);
+                                } else {
+                                    warmups.exercises.retain(|e| *e != exercise);
+                                }
+                            }
+                        }
+                    });
+
+                    let current = warmups.current().map(|scheme| scheme.name.clone()).unwrap_or_default();
+                    egui::ComboBox::from_id_salt("warmup_scheme")
+                        .selected_text(current)
+                        .show_ui(ui, |ui| {
+                            for index in 0..warmups.schemes.len() {
+                                let name = warmups.schemes[index].name.clone();
+                                ui.selectable_value(&mut warmups.scheme, index, name);
+                            }
+                        });
+
+                    if let Some(scheme) = warmups.schemes.get_mut(warmups.scheme) {
+                        let mut removed = None;
+                        for (index, step) in scheme.steps.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                let mut bar = step.percent.is_none();
+                                if ui.checkbox(&mut bar, "bar").changed() {
+                                    step.percent = if bar { None } else { Some(50.0) };
+                                }
+                                if let Some(percent) = step.percent.as_mut() {
+                                    ui.add(egui::DragValue::new(percent).speed(2.5).range(10.0..=95.0).suffix(" %"));
+                                }
+                                ui.add(egui::DragValue::new(&mut step.reps).range(1..=20).prefix("x "));
+                                if ui.small_button("remove").clicked() {
+                                    removed = Some(index);
+                                }
+                            });
+                        }
+                        if let Some(index) = removed {
+                            scheme.steps.remove(index);
+                        }
+                        if ui.small_button("add step").clicked() {
+                            let percent = scheme.steps.last().and_then(|step| step.percent).map_or(40.0, |percent| (percent + 20.0).min(95.0));
+                            scheme.steps.push(WarmupStep { percent: Some(percent), reps: 1 });
+                        }
+                    }
+                }
+            });
+
+        if !*open {
+            self.states.progression_window = false;
+        }
+    }
+
+    pub fn draw_programs_window(&mut self, ui: &mut Ui, ctx: &Context, is_dark: bool, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let screen_rect = ctx.screen_rect();
+
+        ui.painter().rect_filled(
+            screen_rect,
+            0.0,
+            if is_dark {
+                Color32::from_rgba_unmultiplied(20, 20, 20,150)
+            } else {
+                Color32::from_rgba_unmultiplied(240, 240, 240, 150)
+            }
+        );
+
+        Area::new("modal_blocker".into())
+            .order(Order::Background)
+            .fixed_pos(screen_rect.min)
+            .show(ctx, |ui| {
+                let _response = ui.allocate_response(screen_rect.size(), Sense::click());
+            });
+
+        let window_size = vec2(400.0, 500.0);
+        let button_size = vec2(300.0, 60.0);
+        let weekdays = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+
+        let mut template_names: Vec<String> = self.datas.all_workout_data.workout_templates.keys().cloned().collect();
+        template_names.sort();
+
+        Window::new("programs")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(REMAINDER);
+
+                    let Some(program) = self.states.current_program.as_mut() else {
+                        let mut names: Vec<String> = self.datas.all_workout_data.programs.keys().cloned().collect();
+                        names.sort();
+
+                        for name in names {
+                            if ui.add(
+                                Button::new(RichText::new(&name).size(18.0).color(text_color))
+                                    .fill(other_elements_color)
+                                    .min_size(button_size)
+                                    .rounding(8),
+                            ).clicked() {
+                                self.states.current_program = self.datas.all_workout_data.programs.get(&name).cloned();
+                                self.states.program_original = Some(name.clone());
+                                self.states.program_week = 0;
+                                self.states.program_message.clear();
+                            }
+                            ui.add_space(10.0);
+                        }
+
+                        if ui.add(
+                            Button::image_and_text(self.medias.plus.clone(), RichText::new("create program").size(18.0).color(text_color))
+                                .fill(other_elements_color)
+                                .min_size(button_size)
+                                .rounding(8),
+                        ).clicked() {
+                            let first = template_names.first().cloned().unwrap_or_default();
+                            self.states.current_program = Some(Program::new(String::from("program name"), 4, vec![ProgramDay { day: 0, template: first }]));
+                            self.states.program_original = None;
+                            self.states.program_week = 0;
+                            self.states.program_message.clear();
+                        }
+                        return;
+                    };
+
+                    let mut back = false;
+                    ui.horizontal(|ui| {
+                        back = ui.add_sized(vec2(70.0, 30.0), Button::image_and_text(self.medias.left_arrow.clone(), "back").rounding(8)).clicked();
+                        ui.add_sized(vec2(200.0, 30.0), TextEdit::singleline(&mut program.name).font(egui::FontId::new(18.0, egui::FontFamily::Proportional)));
+                    });
+
+                    ui.add_space(REMAINDER);
+
+                    let mut weeks = program.weeks.len();
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(RichText::new("weeks").size(15.0).color(text_color)));
+                        if ui.add(egui::DragValue::new(&mut weeks).range(1..=26)).changed() {
+                            program.resize(weeks);
+                        }
+
+                        ui.add_space(20.0);
+                        ui.add(Label::new(RichText::new("editing week").size(15.0).color(text_color)));
+                        let mut shown = self.states.program_week + 1;
+                        ui.add(egui::DragValue::new(&mut shown).range(1..=program.weeks.len()));
+                        self.states.program_week = shown.clamp(1, program.weeks.len()) - 1;
+                    });
+
+                    let week_index = self.states.program_week;
+                    let week = &mut program.weeks[week_index];
+
+                    ui.horizontal(|ui| {
+                        let mut deload = week.deload;
+                        if ui.checkbox(&mut deload, "deload").changed() {
+                            week.set_deload(deload);
+                        }
+                        ui.add(egui::DragValue::new(&mut week.intensity).speed(0.05).range(0.3..=1.5).prefix("intensity x"));
+                        ui.add(egui::DragValue::new(&mut week.volume).speed(0.05).range(0.3..=2.0).prefix("volume x"));
+                    });
+
+                    ui.add_space(REMAINDER);
+
+                    let mut removed = None;
+                    ScrollArea::vertical()
+                        .max_height(220.0)
+                        .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+                        .show(ui, |ui| {
+                            for (slot_index, slot) in week.days.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    egui::ComboBox::from_id_salt(("program_day", slot_index))
+                                        .width(60.0)
+                                        .selected_text(weekdays[slot.day as usize % 7])
+                                        .show_ui(ui, |ui| {
+                                            for (day, label) in weekdays.iter().enumerate() {
+                                                ui.selectable_value(&mut slot.day, day as u8, *label);
+                                            }
+                                        });
+
+                                    egui::ComboBox::from_id_salt(("program_template", slot_index))
+                                        .width(180.0)
+                                        .selected_text(&slot.template)
+                                        .show_ui(ui, |ui| {
+                                            for name in &template_names {
+                                                ui.selectable_value(&mut slot.template, name.clone(), name);
+                                            }
+                                        });
+
+                                    if ui.add_sized(vec2(25.0, 25.0), ImageButton::new(Image::new(self.medias.remove.clone())).frame(false)).clicked() {
+                                        removed = Some(slot_index);
+                                    }
+                                });
+                            }
+                        });
+
+                    if let Some(slot_index) = removed {
+                        week.days.remove(slot_index);
+                    }
+
+                    let mut copy_days = false;
+                    ui.horizontal(|ui| {
+                        if ui.add(Button::image_and_text(self.medias.plus.clone(), RichText::new("add day").size(14.0).color(text_color)).fill(other_elements_color).rounding(5.0)).clicked() {
+                            let day = week.days.last().map(|slot| (slot.day + 1).min(6)).unwrap_or(0);
+                            week.days.push(ProgramDay { day, template: template_names.first().cloned().unwrap_or_default() });
+                        }
+
+                        copy_days = ui.add(Button::new(RichText::new("copy days to all weeks").size(14.0).color(text_color)).fill(other_elements_color).rounding(5.0)).clicked();
+                    });
+
+                    if copy_days {
+                        let days = program.weeks[week_index].days.clone();
+                        for other in program.weeks.iter_mut() {
+                            other.days = days.clone();
+                        }
+                    }
+
+                    ui.add_space(REMAINDER);
+
+                    if !self.states.program_message.is_empty() {
+                        ui.add(Label::new(RichText::new(&self.states.program_message).size(13.0).color(text_color)));
+                    }
+
+                    let mut save = false;
+                    let mut apply = false;
+                    ui.horizontal(|ui| {
+                        save = ui.add(Button::new(RichText::new("save").size(16.0).strong().color(Color32::WHITE))
+                            .fill(Color32::from_rgb(0, 75, 141))
+                            .min_size(vec2(120.0, 35.0))
+                            .rounding(8)).clicked();
+
+                        apply = ui.add(Button::new(RichText::new(format!("apply from {}", self.states.selected_day.format("%b %d"))).size(16.0).strong().color(Color32::WHITE))
+                            .fill(Color32::from_rgb(21, 141, 0))
+                            .min_size(vec2(200.0, 35.0))
+                            .rounding(8)).clicked();
+                    });
+
+                    if save || apply {
+                        program.name = program.name.trim().to_string();
+                        self.states.program_message = match self.datas.save_program(self.states.program_original.as_deref(), program.clone()) {
+                            Ok(()) => {
+                                self.states.program_original = Some(program.name.clone());
+                                if apply {
+                                    match self.datas.planned_workout_data.apply_program(program, &self.datas.all_workout_data.workout_templates, self.states.selected_day) {
+                                        Ok(added) => {
+                                            self.datas.touch();
+                                            format!("{} workouts planned", added)
+                                        }
+                                        Err(error) => error,
+                                    }
+                                } else {
+                                    String::from("program saved")
+                                }
+                            }
+                            Err(error) => error,
+                        };
+                    }
+
+                    if back {
+                        self.states.current_program = None;
+                    }
+                });
+            });
+
+        if !*open {
+            self.states.programs_window = false;
+            self.states.current_program = None;
+        }
+    }
+
+    pub fn draw_cardio_window(&mut self, ctx: &Context, text_color: Color32, open: &mut bool) {
+        let window_size = vec2(320.0, 520.0);
+        let body_weight = self.datas.user_information.body_weight();
+        let mut logged = false;
+        let mut planned = false;
+
+        Window::new("cardio")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                let draft = &mut self.states.cardio_draft;
+
+                egui::Grid::new("cardio_grid").num_columns(2).spacing(vec2(20.0, 8.0)).show(ui, |ui| {
+                    ui.add(Label::new(RichText::new("activity").size(15.0).color(text_color)));
+                    egui::ComboBox::from_id_salt("cardio_kind")
+                        .selected_text(draft.kind.to_string())
+                        .show_ui(ui, |ui| {
+                            for kind in CardioKind::iter() {
+                                ui.selectable_value(&mut draft.kind, kind, kind.to_string());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("duration").size(15.0).color(text_color)));
+                    ui.horizontal(|ui| {
+                        let mut minutes = draft.duration_secs / 60;
+                        let mut seconds = draft.duration_secs % 60;
+                        let changed = ui.add(egui::DragValue::new(&mut minutes).range(0..=1440).suffix(" min")).changed()
+                            | ui.add(egui::DragValue::new(&mut seconds).range(0..=59).suffix(" s")).changed();
+                        if changed {
+                            draft.duration_secs = minutes * 60 + seconds;
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("distance").size(15.0).color(text_color)));
+                    ui.add(egui::DragValue::new(&mut draft.distance_km).speed(0.05).range(0.0..=500.0).suffix(" km"));
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("elevation").size(15.0).color(text_color)));
+                    ui.add(egui::DragValue::new(&mut draft.elevation_m).speed(5.0).range(0.0..=10000.0).suffix(" m"));
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("heart rate").size(15.0).color(text_color)));
+                    ui.horizontal(|ui| {
+                        let mut avg = draft.avg_hr.unwrap_or(0);
+                        let mut max = draft.max_hr.unwrap_or(0);
+                        if ui.add(egui::DragValue::new(&mut avg).range(0..=230).prefix("avg ")).changed() {
+                            draft.avg_hr = (avg > 0).then_some(avg);
+                        }
+                        if ui.add(egui::DragValue::new(&mut max).range(0..=240).prefix("max ")).changed() {
+                            draft.max_hr = (max > 0).then_some(max);
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("pace").size(15.0).color(text_color)));
+                    ui.add(Label::new(RichText::new(draft.pace_label()).size(15.0).color(text_color)));
+                    ui.end_row();
+
+                    ui.add(Label::new(RichText::new("burned").size(15.0).color(text_color)));
+                    ui.add(Label::new(RichText::new(format!("~{} kcal", draft.estimate_calories(body_weight))).size(15.0).color(text_color)));
+                    ui.end_row();
+                });
+
+                if draft.moving_secs > 0 {
+                    ui.add(Label::new(RichText::new(format!("moving {}:{:02}", draft.moving_secs / 60, draft.moving_secs % 60)).size(13.0).color(text_color)));
+                }
+                if draft.route.len() > 1 {
+                    Self::draw_route(ui, &draft.route, vec2(280.0, 90.0), Color32::from_rgb(59, 163, 255));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add_sized(vec2(170.0, 22.0), TextEdit::singleline(&mut self.states.cardio_path).hint_text("activity.gpx"));
+                    if ui.button("import gpx / tcx").clicked() {
+                        match tracks::import_file(&self.states.cardio_path, draft.kind) {
+                            Ok(session) => {
+                                *draft = session;
+                                self.states.cardio_message.clear();
+                            }
+                            Err(error) => self.states.cardio_message = error,
+                        }
+                    }
+                });
+                if !self.states.cardio_message.is_empty() {
+                    ui.add(Label::new(RichText::new(&self.states.cardio_message).size(12.0).color(Color32::from_rgb(255, 90, 90))));
+                }
+
+                if ui.checkbox(&mut self.datas.macro_data.count_burned, "add burned calories to the daily budget").changed() {
+                    self.datas.touch();
+                }
+                ui.add_space(REMAINDER);
+
+                ui.horizontal(|ui| {
+                    if ui.add(Button::new(RichText::new("log").size(16.0).strong().color(Color32::WHITE))
+                        .fill(Color32::from_rgb(21, 141, 0))
+                        .min_size(vec2(120.0, 32.0))
+                        .rounding(8)).clicked() {
+                            logged = true;
+                        }
+                    if self.states.cardio_planned.is_none() && ui.add(Button::new(RichText::new(format!("plan for {}", draft.date.format("%d.%m"))).size(16.0).strong().color(Color32::WHITE))
+                        .fill(Color32::from_rgb(0, 75, 141))
+                        .min_size(vec2(120.0, 32.0))
+                        .rounding(8)).clicked() {
+                            planned = true;
+                        }
+                });
+            });
+
+        let draft = self.states.cardio_draft.clone();
+        if logged {
+            if let Some((date, index)) = self.states.cardio_planned {
+                if let Some(workout) = self.datas.planned_workout_data.workouts.get_mut(&date).and_then(|workouts| workouts.get_mut(index)) {
+                    workout.completed = true;
+                }
+            }
+            self.datas.record_cardio(draft.clone());
+            if draft.date != self.states.selected_day {
+                self.datas.refresh_burned(self.states.selected_day);
+            }
+            *open = false;
+        } else if planned {
+            if let Some(workouts) = self.datas.planned_workout_data.workouts.get_mut(&draft.date) {
+                workouts.retain(|workout| !workout.is_rest());
+            }
+            let plan = CardioPlan {
+                kind: draft.kind,
+                duration_mins: draft.duration_secs / 60,
+                distance_km: (draft.distance_km > 0.0).then_some(draft.distance_km),
+            };
+            let _ = self.datas.planned_workout_data.add_workout(draft.date, WorkoutPlanned::cardio(plan, draft.date));
+            self.datas.touch();
+            *open = false;
+        }
+
+        if !*open {
+            self.states.cardio_window = false;
+            self.states.cardio_planned = None;
+        }
+    }
+
+    pub fn draw_plates_window(&mut self, ctx: &Context, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let window_size = vec2(380.0, 460.0);
+        let mut reweighed = None;
+
+        Window::new("plate calculator")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                let inventory = &mut self.datas.all_workout_data.plates;
+                let unit = inventory.unit;
+
+                ui.horizontal(|ui| {
+                    ui.label("target");
+                    ui.add(egui::DragValue::new(&mut self.states.plate_target).speed(0.5).range(0.0..=1000.0).suffix(format!(" {}", unit)));
+                });
+
+                let load = inventory.load(self.states.plate_target);
+                ui.add(Label::new(RichText::new(format!("load {:.2} {}", load.loaded, unit)).size(20.0).strong().color(text_color)));
+                if (load.loaded - load.target).abs() > 0.01 {
+                    ui.add(Label::new(RichText::new(format!("{:.2} {} is not loadable, nearest shown", load.target, unit)).size(12.0).color(text_color)));
+                }
+                ui.add(Label::new(RichText::new(if load.per_side.is_empty() {
+                    String::from("empty bar")
+                } else {
+                    format!("per side: {}", load.per_side.iter().map(|weight| weight.to_string()).collect::<Vec<String>>().join(" + "))
+                }).size(14.0).color(text_color)));
+
+                let (rect, _) = ui.allocate_exact_size(vec2(window_size.x - 20.0, 110.0), Sense::hover());
+                let painter = ui.painter_at(rect);
+                let axis = rect.center().y - 8.0;
+                let collar = rect.left() + 70.0;
+                painter.rect_filled(Rect::from_min_max(Pos2::new(rect.left() + 5.0, axis - 4.0), Pos2::new(collar, axis + 4.0)), 2, Color32::GRAY);
+                painter.rect_filled(Rect::from_min_max(Pos2::new(collar, axis - 10.0), Pos2::new(collar + 8.0, axis + 10.0)), 2, Color32::DARK_GRAY);
+                painter.rect_filled(Rect::from_min_max(Pos2::new(collar + 8.0, axis - 3.0), Pos2::new(rect.right() - 5.0, axis + 3.0)), 2, Color32::GRAY);
+
+                let heaviest = inventory.plates.iter().map(|plate| plate.weight).fold(1.0, f32::max);
+                let mut x = collar + 10.0;
+                for weight in load.per_side.iter() {
+                    let kg = unit.to_kg(*weight);
+                    let color = if kg >= 24.0 {
+                        Color32::from_rgb(200, 40, 40)
+                    } else if kg >= 19.0 {
+                        Color32::from_rgb(40, 90, 200)
+                    } else if kg >= 14.0 {
+                        Color32::from_rgb(230, 190, 30)
+                    } else if kg >= 9.0 {
+                        Color32::from_rgb(40, 150, 60)
+                    } else if kg >= 4.5 {
+                        Color32::from_rgb(235, 235, 235)
+                    } else {
+                        Color32::from_rgb(60, 60, 60)
+                    };
+                    let height = 80.0 * (weight / heaviest).max(0.3);
+                    let width = if kg >= 9.0 { 16.0 } else { 10.0 };
+                    let plate = Rect::from_min_max(Pos2::new(x, axis - height / 2.0), Pos2::new(x + width, axis + height / 2.0));
+                    painter.rect_filled(plate, 2, color);
+                    painter.rect_stroke(plate, 2, Stroke::new(1.0, Color32::BLACK), StrokeKind::Inside);
+                    painter.text(Pos2::new(x + width / 2.0, rect.bottom() - 6.0), egui::Align2::CENTER_CENTER, weight.to_string(), FontId::proportional(10.0), text_color);
+                    x += width + 2.0;
+                }
+
+                if let Some((exercise, set)) = self.states.plate_set {
+                    if let Some(session_set) = self.states.session.as_mut().and_then(|session| session.exercises.get_mut(exercise)).and_then(|exercise| exercise.sets.get_mut(set)) {
+                        if ui.add(Button::new(RichText::new(format!("use {:.2} {}", load.loaded, unit)).size(15.0).color(Color32::WHITE))
+                            .fill(Color32::from_rgb(21, 141, 0))
+                            .rounding(5.0)).clicked() {
+                                session_set.set.weight = unit.to_kg(load.loaded);
+                                if !session_set.set.warmup {
+                                    reweighed = Some(exercise);
+                                }
+                                self.states.plates_window = false;
+                            }
+                    }
+                }
+
+                ui.separator();
+                ui.add(Label::new(RichText::new("inventory").size(16.0).strong().color(text_color)));
+
+                ui.horizontal(|ui| {
+                    ui.label("unit");
+                    egui::ComboBox::from_id_salt("plate_unit")
+                        .width(60.0)
+                        .selected_text(unit.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in WeightUnit::iter() {
+                                if ui.selectable_label(option == unit, option.to_string()).clicked() && option != unit {
+                                    *inventory = PlateInventory::standard(option);
+                                    self.states.plate_target = option.from_kg(unit.to_kg(self.states.plate_target)).round();
+                                }
+                            }
+                        });
+                    ui.label("bar");
+                    ui.add(egui::DragValue::new(&mut inventory.bar_weight).speed(0.5).range(0.0..=100.0).suffix(format!(" {}", unit)));
+                });
+
+                let mut removed = None;
+                ScrollArea::vertical()
+                    .max_height(140.0)
+                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+                    .show(ui, |ui| {
+                        for (index, plate) in inventory.plates.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.add(egui::DragValue::new(&mut plate.weight).speed(0.25).range(0.25..=100.0).suffix(format!(" {}", unit)));
+                                ui.add(egui::DragValue::new(&mut plate.count).range(0..=40).prefix("x "));
+                                if ui.small_button("remove").clicked() {
+                                    removed = Some(index);
+                                }
+                            });
+                        }
+                    });
+                if let Some(index) = removed {
+                    inventory.plates.remove(index);
+                }
+
+                if ui.add(Button::image_and_text(self.medias.plus.clone(), RichText::new("add plate").size(13.0).color(text_color))
+                    .fill(other_elements_color)
+                    .rounding(5.0)).clicked() {
+                        inventory.plates.push(PlateCount { weight: 1.0, count: 2 });
+                    }
+            });
+
+        if let (Some(index), Some(session)) = (reweighed, self.states.session.as_mut()) {
+            session.refresh_warmups(index, &self.datas.all_workout_data);
+        }
+
+        if !*open {
+            self.states.plates_window = false;
+        }
+        if !self.states.plates_window {
+            self.states.plate_set = None;
+        }
+    }
+
+    pub fn draw_sharing_window(&mut self, ui: &mut Ui, ctx: &Context, is_dark: bool, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let screen_rect = ctx.screen_rect();
+
+        ui.painter().rect_filled(
+            screen_rect,
+            0.0,
+            if is_dark {
+                Color32::from_rgba_unmultiplied(20, 20, 20,150)
+            } else {
+                Color32::from_rgba_unmultiplied(240, 240, 240, 150)
+            }
+        );
+
+        Area::new("modal_blocker".into())
+            .order(Order::Background)
+            .fixed_pos(screen_rect.min)
+            .show(ctx, |ui| {
+                let _response = ui.allocate_response(screen_rect.size(), Sense::click());
+            });
+
+        let window_size = vec2(400.0, 500.0);
+        let error_color = Color32::from_rgb(255, 90, 90);
+
+        let mut template_names: Vec<String> = self.datas.all_workout_data.workout_templates.keys().cloned().collect();
+        template_names.sort();
+        let mut program_names: Vec<String> = self.datas.all_workout_data.programs.keys().cloned().collect();
+        program_names.sort();
+
+        Window::new("import / export")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                ScrollArea::vertical()
+                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("file");
+                            ui.add_sized(vec2(250.0, 22.0), TextEdit::singleline(&mut self.states.sharing_path).hint_text("templates.json"));
+                        });
+
+                        if !self.states.sharing_message.is_empty() {
+                            ui.add(Label::new(RichText::new(&self.states.sharing_message).size(13.0).color(text_color)));
+                        }
+                        ui.add_space(REMAINDER);
+
+                        let Some(preview) = self.states.import_preview.as_ref() else {
+                            ui.add(Label::new(RichText::new("export").size(16.0).strong().color(text_color)));
+                            ui.horizontal_wrapped(|ui| {
+                                for name in template_names.iter() {
+                                    let mut selected = self.states.export_templates.contains(name);
+                                    if ui.checkbox(&mut selected, name.as_str()).changed() {
+                                        if selected {
+                                            self.states.export_templates.push(name.clone());
+                                        } else {
+                                            self.states.export_templates.retain(|n| n != name);
+                                        }
+                                    }
+                                }
+                            });
+
+                            if !program_names.is_empty() {
+                                ui.add(Label::new(RichText::new("programs (their templates are included)").size(13.0).color(text_color)));
+                                ui.horizontal_wrapped(|ui| {
+                                    for name in program_names.iter() {
+                                        let mut selected = self.states.export_programs.contains(name);
+                                        if ui.checkbox(&mut selected, name.as_str()).changed() {
+                                            if selected {
+                                                self.states.export_programs.push(name.clone());
+                                            } else {
+                                                self.states.export_programs.retain(|n| n != name);
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+
+                            let anything = !self.states.export_templates.is_empty() || !self.states.export_programs.is_empty();
+                            if ui.add_enabled(anything, Button::new(RichText::new("export").size(15.0).color(text_color)).fill(other_elements_color).min_size(vec2(120.0, 30.0)).rounding(8)).clicked() {
+                                self.states.sharing_message = match sharing::export_to_file(&self.datas.all_workout_data, &self.states.export_templates, &self.states.export_programs, &self.states.sharing_path) {
+                                    Ok(count) => format!("{} items written to {}", count, self.states.sharing_path.trim()),
+                                    Err(error) => error,
+                                };
+                            }
+
+                            ui.add_space(REMAINDER);
+                            ui.add(Label::new(RichText::new("import").size(16.0).strong().color(text_color)));
+                            if ui.add(Button::new(RichText::new("preview file").size(15.0).color(text_color)).fill(other_elements_color).min_size(vec2(120.0, 30.0)).rounding(8)).clicked() {
+                                match ImportPreview::load(&self.states.sharing_path, &self.datas.all_workout_data) {
+                                    Ok(preview) => {
+                                        self.states.import_preview = Some(preview);
+                                        self.states.sharing_message.clear();
+                                    }
+                                    Err(error) => self.states.sharing_message = error,
+                                }
+                            }
+                            return;
+                        };
+
+                        for template in preview.templates.iter() {
+                            let collides = preview.collisions.contains(&template.workout_name);
+                            ui.horizontal(|ui| {
+                                ui.add(Label::new(RichText::new(&template.workout_name).size(16.0).strong().color(text_color)));
+                                if collides {
+                                    ui.add(Label::new(RichText::new("already exists").size(12.0).color(error_color)));
+                                }
+                            });
+                            if !template.tags.is_empty() {
+                                ui.add(Label::new(RichText::new(template.tags.join(", ")).size(12.0).color(text_color)));
+                            }
+                            ui.horizontal(|ui| {
+                                workout_tracker_widget_front(ctx, ui, Vec2::new(100.0, 226.0), &template.exercises);
+                                workout_tracker_widget_behind(ctx, ui, Vec2::new(100.0, 226.0), &template.exercises);
+                                ui.vertical(|ui| {
+                                    let labels = template.labels();
+                                    for (index, exercise) in template.exercises.iter().enumerate() {
+                                        let prescription = template.prescription(index).map(|prescription| prescription.short()).unwrap_or_default();
+                                        ui.add(Label::new(RichText::new(format!("{} {} {}", labels[index], exercise, prescription)).size(13.0).color(text_color)));
+                                    }
+                                });
+                            });
+                            ui.add_space(REMAINDER);
+                        }
+
+                        for program in preview.programs.iter() {
+                            ui.horizontal(|ui| {
+                                ui.add(Label::new(RichText::new(format!("program {}: {} weeks", program.name, program.weeks.len())).size(15.0).strong().color(text_color)));
+                                if preview.collisions.contains(&program.name) {
+                                    ui.add(Label::new(RichText::new("already exists").size(12.0).color(error_color)));
+                                }
+                            });
+                        }
+
+                        for problem in preview.problems.iter() {
+                            ui.add(Label::new(RichText::new(problem).size(13.0).color(error_color)));
+                        }
+
+                        if !preview.collisions.is_empty() {
+                            ui.horizontal(|ui| {
+                                ui.label("on name clash");
+                                egui::ComboBox::from_id_salt("collision_policy")
+                                    .selected_text(self.states.collision_policy.to_string())
+                                    .show_ui(ui, |ui| {
+                                        for policy in CollisionPolicy::iter() {
+                                            ui.selectable_value(&mut self.states.collision_policy, policy, policy.to_string());
+                                        }
+                                    });
+                            });
+                        }
+
+                        ui.add_space(REMAINDER);
+                        let mut done = false;
+                        ui.horizontal(|ui| {
+                            if ui.add(Button::new(RichText::new("import").size(15.0).color(Color32::WHITE)).fill(Color32::from_rgb(21, 141, 0)).min_size(vec2(120.0, 30.0)).rounding(8)).clicked() {
+                                let count = preview.apply(&mut self.datas.all_workout_data, self.states.collision_policy);
+                                self.states.sharing_message = format!("{} items imported", count);
+                                done = true;
+                            }
+                            if ui.add(Button::new(RichText::new("discard").size(15.0).color(text_color)).fill(other_elements_color).min_size(vec2(120.0, 30.0)).rounding(8)).clicked() {
+                                self.states.sharing_message.clear();
+                                done = true;
+                            }
+                        });
+                        if done {
+                            self.states.import_preview = None;
+                        }
+                    });
+            });
+
+        if !*open {
+            self.states.sharing_window = false;
+            self.states.import_preview = None;
+            self.states.sharing_message.clear();
+        }
+    }
+
+    pub fn draw_schedule_window(&mut self, ctx: &Context, text_color: Color32, open: &mut bool) {
+        let window_size = vec2(320.0, 260.0);
+        let weekdays = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+        let mut created = false;
+
+        Window::new("repeat workout")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add(Label::new(RichText::new(&self.states.schedule_template).size(20.0).strong().color(text_color)));
+                    ui.add(Label::new(RichText::new(format!("starting {}", self.states.selected_day.format("%b %d"))).size(13.0).color(text_color)));
+                });
+
+                ui.add_space(REMAINDER);
+
+                let rule = &mut self.states.schedule_rule;
+                let days = match rule {
+                    RecurrenceRule::Weekdays(days) | RecurrenceRule::EveryOtherWeek(days) => days.clone(),
+                    RecurrenceRule::EveryNDays(_) => vec![self.states.selected_day.weekday().num_days_from_monday() as u8],
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(matches!(rule, RecurrenceRule::Weekdays(_)), "weekdays").clicked() {
+                        *rule = RecurrenceRule::Weekdays(days.clone());
+                    }
+                    if ui.selectable_label(matches!(rule, RecurrenceRule::EveryOtherWeek(_)), "every other week").clicked() {
+                        *rule = RecurrenceRule::EveryOtherWeek(days.clone());
+                    }
+                    if ui.selectable_label(matches!(rule, RecurrenceRule::EveryNDays(_)), "every n days").clicked() {
+                        *rule = RecurrenceRule::EveryNDays(2);
+                    }
+                });
+
+                ui.add_space(REMAINDER);
+
+                match rule {
+                    RecurrenceRule::EveryNDays(n) => {
+                        ui.horizontal(|ui| {
+                            ui.label("every");
+                            ui.add(egui::DragValue::new(n).range(1..=30));
+                            ui.label("days");
+                        });
+                    }
+                    RecurrenceRule::Weekdays(days) | RecurrenceRule::EveryOtherWeek(days) => {
+                        ui.horizontal(|ui| {
+                            for (day, label) in weekdays.iter().enumerate() {
+                                let day = day as u8;
+                                if ui.selectable_label(days.contains(&day), *label).clicked() {
+                                    if days.contains(&day) {
+                                        days.retain(|d| *d != day);
+                                    } else {
+                                        days.push(day);
+                                        days.sort();
+                                    }
+                                }
+                            }
+                        });
+                    }
+                }
+
+                ui.add_space(REMAINDER);
+                ui.add(Label::new(RichText::new(self.states.schedule_rule.to_string()).size(14.0).color(text_color)));
+                ui.add_space(REMAINDER);
+
+                ui.vertical_centered(|ui| {
+                    if ui.add(Button::new(RichText::new("create").size(16.0).strong().color(Color32::WHITE))
+                        .fill(Color32::from_rgb(0, 75, 141))
+                        .min_size(vec2(120.0, 35.0))
+                        .rounding(8)).clicked() {
+                            created = true;
+                        }
+                });
+            });
+
+        if created {
+            let start = self.states.selected_day;
+            let id = self.datas.planned_workout_data.add_schedule(self.states.schedule_template.clone(), self.states.schedule_rule.clone(), start);
+
+            // the workout the series was created from becomes its first occurrence
+            if let Some(workouts) = self.datas.planned_workout_data.workouts.get_mut(&start) {
+                if let Some(planned) = workouts.iter_mut().find(|w| w.schedule.is_none() && w.template.workout_name == self.states.schedule_template) {
+                    if self.datas.planned_workout_data.schedules.last().unwrap().occurs_on(start) {
+                        planned.schedule = Some(id);
+                    }
+                }
+            }
+
+            self.refresh_schedules();
+            self.states.schedule_window = false;
+        }
+
+        if !*open {
+            self.states.schedule_window = false;
+        }
+    }
+
+    fn day_summary(workouts: Option<&Vec<WorkoutPlanned>>) -> String {
+        match workouts {
+            Some(workouts) if !workouts.is_empty() => workouts
+                .iter()
+                .map(|workout| if workout.skipped { format!("{} (skipped)", workout.template.workout_name) } else { workout.template.workout_name.clone() })
+                .collect::<Vec<String>>()
+                .join(" + "),
+            _ => String::from("-"),
+        }
+    }
+
+    pub fn draw_missed_window(&mut self, ctx: &Context, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let Some((date, index)) = self.states.missed_edit else { return; };
+        let today = Local::now().date_naive();
+        let plan = &self.datas.planned_workout_data;
+        let Some(missed) = plan.workouts.get(&date).and_then(|workouts| workouts.get(index)).cloned() else {
+            self.states.missed_edit = None;
+            return;
+        };
+
+        let mut preview = plan.clone();
+        let result = preview.reschedule(date, index, self.states.missed_action, today);
+
+        // every day whose plan changes, from the missed day on
+        let mut days: Vec<NaiveDate> = plan.workouts.keys().chain(preview.workouts.keys()).filter(|day| **day >= date).copied().collect();
+        days.sort();
+        days.dedup();
+        let changes: Vec<(NaiveDate, String, String)> = days
+            .into_iter()
+            .map(|day| (day, Self::day_summary(plan.workouts.get(&day)), Self::day_summary(preview.workouts.get(&day))))
+            .filter(|(_, before, after)| before != after)
+            .collect();
+        let others = plan.missed(today);
+
+        let mut apply = false;
+        let mut cancel = false;
+
+        Window::new("missed workout")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(vec2(360.0, 460.0))
+            .show(ctx, |ui| {
+                ui.add(Label::new(RichText::new(format!("{} on {}", missed.template.workout_name, date.format("%a %b %e"))).size(18.0).strong().color(text_color)));
+                if let Some(slot) = &missed.program {
+                    ui.add(Label::new(RichText::new(format!("{} - week {}", slot.name, slot.week)).size(12.0).color(text_color)));
+                }
+
+                if others.len() > 1 {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(format!("{} missed:", others.len()));
+                        for (other_date, other_index) in others.iter() {
+                            let selected = (*other_date, *other_index) == (date, index);
+                            if ui.selectable_label(selected, other_date.format("%b %e").to_string()).clicked() {
+                                self.states.missed_edit = Some((*other_date, *other_index));
+                            }
+                        }
+                    });
+                }
+                ui.add_space(REMAINDER / 2.0);
+
+                for action in MissedAction::iter() {
+                    ui.radio_value(&mut self.states.missed_action, action, action.to_string());
+                }
+                ui.add_space(REMAINDER / 2.0);
+
+                ui.add(Label::new(RichText::new("preview").size(15.0).strong().color(text_color)));
+                egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                    ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        if let Err(e) = &result {
+                            ui.label(e);
+                        } else if changes.is_empty() {
+                            ui.label("nothing changes");
+                        }
+                        for (day, before, after) in changes.iter() {
+                            ui.horizontal(|ui| {
+                                ui.add(Label::new(RichText::new(day.format("%a %b %e").to_string()).size(13.0).strong().color(text_color)));
+                                ui.add(Label::new(RichText::new(format!("{} -> {}", before, after)).size(13.0).color(text_color)));
+                            });
+                        }
+                    });
+                });
+                ui.add_space(REMAINDER);
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(result.is_ok(), Button::new(RichText::new("apply").size(15.0).strong().color(Color32::WHITE))
+                        .fill(Color32::from_rgb(21, 141, 0))
+                        .min_size(Vec2::new(80.0, 28.0))
+                        .rounding(8)).clicked() {
+                        apply = true;
+                    }
+                    if ui.add(Button::new(RichText::new("cancel").size(15.0).color(Color32::WHITE))
+                        .fill(Color32::from_rgb(141, 0, 19))
+                        .min_size(Vec2::new(80.0, 28.0))
+                        .rounding(8)).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if apply {
+            self.datas.planned_workout_data = preview;
+            self.datas.touch();
+            self.refresh_schedules();
+        }
+        if apply || cancel || !*open {
+            self.states.missed_edit = None;
+        }
+    }
+
+    pub fn draw_generator_window(&mut self, ctx: &Context, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let mut generate = false;
+        let mut save = false;
+        let mut schedule = false;
+
+        Window::new("workout generator")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(vec2(380.0, 620.0))
+            .show(ctx, |ui| {
+                ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |ui| {
+                    let settings = &mut self.states.generator;
+
+                    ui.add(Label::new(RichText::new("tap the muscles to train").size(15.0).strong().color(text_color)));
+                    let shading = MuscleShading::Workout(settings.muscles.clone(), Vec::new());
+                    let clicked = ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 105.0);
+                        let front = body_map_front(ctx, ui, vec2(100.0, 226.0), &shading);
+                        let behind = body_map_behind(ctx, ui, vec2(100.0, 226.0), &shading);
+                        front.or(behind)
+                    }).inner;
+                    if let Some(muscle) = clicked {
+                        if settings.muscles.contains(&muscle) {
+                            settings.muscles.retain(|other| *other != muscle);
+                        } else {
+                            settings.muscles.push(muscle);
+                        }
+                    }
+                    ui.horizontal_wrapped(|ui| {
+                        if settings.muscles.is_empty() {
+                            ui.add(Label::new(RichText::new("nothing selected").size(12.0).color(Color32::GRAY)));
+                        }
+                        for muscle in settings.muscles.clone() {
+                            if ui.small_button(format!("{} x", muscle)).clicked() {
+                                settings.muscles.retain(|other| *other != muscle);
+                            }
+                        }
+                    });
+                    ui.add_space(REMAINDER / 2.0);
+
+                    egui::Grid::new("generator_grid").num_columns(2).spacing(vec2(15.0, 6.0)).show(ui, |ui| {
+                        ui.label("time");
+                        ui.add(egui::DragValue::new(&mut settings.minutes).speed(5).range(15..=150).suffix(" min"));
+                        ui.end_row();
+
+                        ui.label("equipment");
+                        ui.horizontal_wrapped(|ui| {
+                            for equipment in Equipment::iter() {
+                                let mut available = settings.equipment.contains(&equipment);
+                                if ui.checkbox(&mut available, equipment.to_string()).changed() {
+                                    if available {
+                                        settings.equipment.push(equipment);
+                                    } else {
+                                        settings.equipment.retain(|other| *other != equipment);
+                                    }
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("experience");
+                        ui.horizontal(|ui| {
+                            for experience in Experience::iter() {
+                                ui.selectable_value(&mut settings.experience, experience, experience.to_string());
+                            }
+                        });
+                        ui.end_row();
+                    });
+                    ui.add_space(REMAINDER / 2.0);
+
+                    if ui.add_enabled(!settings.muscles.is_empty(), Button::new(RichText::new("generate").size(15.0).strong().color(Color32::WHITE))
+                        .fill(Color32::from_rgb(0, 79, 148))
+                        .min_size(Vec2::new(120.0, 28.0))
+                        .rounding(8)).clicked() {
+                        generate = true;
+                    }
+
+                    if let Some(template) = self.states.generated.as_mut() {
+                        ui.add_space(REMAINDER);
+                        ui.add_sized(vec2(200.0, 24.0), TextEdit::singleline(&mut template.workout_name));
+                        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            if template.exercises.is_empty() {
+                                ui.label("no exercise fits these muscles and equipment");
+                            }
+                            let mut total = 0;
+                            for (index, exercise) in template.exercises.iter().enumerate() {
+                                let prescription = template.prescription(index).unwrap_or_default();
+                                total += estimated_secs(&prescription);
+                                ui.horizontal(|ui| {
+                                    ui.add(Label::new(RichText::new(exercise.to_string()).size(14.0).strong().color(text_color)));
+                                    ui.add(Label::new(RichText::new(prescription.to_string()).size(12.0).color(text_color)));
+                                });
+                            }
+                            ui.add(Label::new(RichText::new(format!("about {} min", total / 60)).size(12.0).color(Color32::GRAY)));
+                        });
+                        ui.add_space(REMAINDER / 2.0);
+
+                        ui.horizontal(|ui| {
+                            let ready = !template.exercises.is_empty() && !template.workout_name.trim().is_empty();
+                            if ui.add_enabled(ready, Button::new(RichText::new("save template").size(14.0).strong().color(Color32::WHITE))
+                                .fill(Color32::from_rgb(21, 141, 0))
+                                .min_size(Vec2::new(110.0, 28.0))
+                                .rounding(8)).clicked() {
+                                save = true;
+                            }
+                            if ui.add_enabled(ready, Button::new(RichText::new(format!("schedule {}", self.states.selected_day.format("%b %e"))).size(14.0).strong().color(Color32::WHITE))
+                                .fill(Color32::from_rgb(91, 0, 113))
+                                .min_size(Vec2::new(110.0, 28.0))
+                                .rounding(8)).clicked() {
+                                schedule = true;
+                            }
+                        });
+                    }
+
+                    if !self.states.generator_message.is_empty() {
+                        ui.add(Label::new(RichText::new(&self.states.generator_message).size(12.0).color(text_color)));
+                    }
+                });
+            });
+
+        if generate {
+            let settings = &self.states.generator;
+            let names: Vec<String> = settings.muscles.iter().take(3).map(|muscle| muscle.to_string()).collect();
+            let name = format!("{} min {}", settings.minutes, names.join(", "));
+            self.states.generated = Some(generator::generate(settings, name, self.datas.all_workout_data.landmarks.secondary_share));
+            self.states.generator_message.clear();
+        }
+
+        if let Some(template) = self.states.generated.clone().filter(|_| save || schedule) {
+            let name = template.workout_name.trim().to_string();
+            if save {
+                let saved = self.datas.all_workout_data.add_template(template.clone());
+                self.states.generator_message = format!("saved as {}", saved);
+            }
+            if schedule {
+                let day = self.states.selected_day;
+                let _ = self.datas.planned_workout_data.add_workout(day, WorkoutPlanned::new(WorkoutTemplate { workout_name: name, ..template }, day));
+                self.datas.touch();
+                self.states.generator_message = format!("scheduled on {}", day.format("%a %b %e"));
+            }
+        }
+
+        if !*open {
+            self.states.generator_window = false;
+        }
+    }
+
+    pub fn draw_history_window(&mut self, ctx: &Context, other_elements_color: Color32, text_color: Color32, open: &mut bool) {
+        let today = Local::now().date_naive();
+        self.states.history_filter.range = match self.states.history_days {
+            Some(days) => DateRange::last_days(today, days),
+            None => DateRange::all(),
+        };
+
+        Window::new("workout history")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(vec2(420.0, 640.0))
+            .show(ctx, |ui| {
+                match self.states.history_selected.filter(|index| *index < self.datas.all_workout_data.workouts.len()) {
+                    Some(index) => self.draw_history_detail(ctx, ui, index, other_elements_color, text_color),
+                    None => self.draw_history_list(ui, other_elements_color, text_color),
+                }
+            });
+
+        if !*open {
+            self.states.history_window = false;
+            self.states.history_selected = None;
+            self.states.history_draft = None;
+            self.states.history_confirm_delete = false;
+        }
+    }
+
+    fn draw_history_list(&mut self, ui: &mut Ui, other_elements_color: Color32, text_color: Color32) {
+        let data = &self.datas.all_workout_data;
+        let filter = &mut self.states.history_filter;
+
+        ui.add(TextEdit::singleline(&mut filter.query).hint_text("search workouts, exercises and notes").desired_width(f32::INFINITY));
+        ui.horizontal(|ui| {
+            for (label, days) in [("all", None), ("7 days", Some(7)), ("30 days", Some(30)), ("90 days", Some(90)), ("year", Some(365))] {
+                ui.selectable_value(&mut self.states.history_days, days, label);
+            }
+        });
+
+        let mut templates: Vec<String> = data.workouts.iter().map(|workout| workout.template.workout_name.clone()).collect();
+        templates.sort();
+        templates.dedup();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("history_template")
+                .width(110.0)
+                .selected_text(filter.template.clone().unwrap_or(String::from("any template")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.template, None, "any template");
+                    for name in templates {
+                        ui.selectable_value(&mut filter.template, Some(name.clone()), name);
+                    }
+                });
+            egui::ComboBox::from_id_salt("history_exercise")
+                .width(110.0)
+                .selected_text(filter.exercise.as_ref().map_or(String::from("any exercise"), |exercise| exercise.to_string()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.exercise, None, "any exercise");
+                    for exercise in Exercises::iter() {
+                        ui.selectable_value(&mut filter.exercise, Some(exercise.clone()), exercise.to_string());
+                    }
+                });
+            egui::ComboBox::from_id_salt("history_muscle")
+                .width(100.0)
+                .selected_text(filter.muscle.as_ref().map_or(String::from("any muscle"), |muscle| muscle.to_string()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.muscle, None, "any muscle");
+                    for muscle in Muscle::iter() {
+                        ui.selectable_value(&mut filter.muscle, Some(muscle.clone()), muscle.to_string());
+                    }
+                });
+        });
+
+        let found = data.history(filter);
+        ui.horizontal(|ui| {
+            ui.add(Label::new(RichText::new(format!("{} sessions", found.len())).size(13.0).color(text_color)));
+            if ui.small_button("clear filters").clicked() {
+                *filter = HistoryFilter::default();
+                self.states.history_days = None;
+            }
+        });
+        ui.add_space(REMAINDER / 2.0);
+
+        ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |ui| {
+            for index in found {
+                let workout = &data.workouts[index];
+                let response = egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(RichText::new(workout.date.format("%a %b %e, %Y").to_string()).size(13.0).color(text_color)).selectable(false));
+                        ui.add(Label::new(RichText::new(&workout.template.workout_name).size(15.0).strong().color(text_color)).selectable(false));
+                        if let Some(rating) = workout.rating {
+                            ui.add(Label::new(RichText::new(format!("{}/5", rating)).size(12.0).color(text_color)).selectable(false));
+                        }
+                    });
+                    let prs = if workout.prs > 0 { format!(", {} pr", workout.prs) } else { String::new() };
+                    ui.add(Label::new(RichText::new(format!("{} exercises, {} kg, {} min{}", workout.exercises.len(), workout.volume, workout.length, prs)).size(12.0).color(text_color)).selectable(false));
+                }).response.interact(Sense::click());
+                if response.clicked() {
+                    self.states.history_selected = Some(index);
+                    self.states.history_draft = None;
+                    self.states.history_confirm_delete = false;
+                }
+                ui.add_space(5.0);
+            }
+        });
+    }
+
+    fn draw_history_detail(&mut self, ctx: &Context, ui: &mut Ui, index: usize, other_elements_color: Color32, text_color: Color32) {
+        let workout = self.datas.all_workout_data.workouts[index].clone();
+        let body_weight = self.datas.user_information.body_weight();
+        let mut back = false;
+        let mut save = false;
+        let mut cancel = false;
+        let mut delete = false;
+
+        ui.horizontal(|ui| {
+            if ui.button("back").clicked() {
+                back = true;
+            }
+            ui.add(Label::new(RichText::new(format!("{} - {}", workout.template.workout_name, workout.date.format("%b %e, %Y"))).size(17.0).strong().color(text_color)));
+        });
+        ui.add_space(REMAINDER / 2.0);
+
+        ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |ui| {
+            match self.states.history_draft.as_mut() {
+                None => {
+                    let sets: usize = workout.exercises.iter().map(|done| done.set_count()).sum();
+                    let prs = self.datas.all_workout_data.prs_in(index, body_weight);
+                    egui::Grid::new("history_summary").num_columns(2).spacing(vec2(15.0, 4.0)).show(ui, |ui| {
+                        let rows = [
+                            ("duration", format!("{} min", workout.length)),
+                            ("volume", format!("{} kg", workout.volume)),
+                            ("working sets", sets.to_string()),
+                            ("prs", if prs.is_empty() { String::from("none") } else { prs.iter().map(|exercise| exercise.to_string()).collect::<Vec<_>>().join(", ") }),
+                            ("rating", workout.rating.map_or(String::from("-"), |rating| format!("{}/5", rating))),
+                            ("energy", workout.energy.map_or(String::from("-"), |energy| format!("{}/5", energy))),
+                            ("rpe", workout.rpe.map_or(String::from("-"), |rpe| rpe.to_string())),
+                        ];
+                        for (name, value) in rows {
+                            ui.add(Label::new(RichText::new(name).size(13.0).color(text_color)));
+                            ui.add(Label::new(RichText::new(value).size(13.0).strong().color(text_color)));
+                            ui.end_row();
+                        }
+                    });
+                    if !workout.notes.is_empty() {
+                        ui.add(Label::new(RichText::new(&workout.notes).size(13.0).italics().color(Color32::GRAY)).wrap());
+                    }
+
+                    let exercises: Vec<Exercises> = workout.exercises.iter().map(|done| done.exercise.clone()).collect();
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 105.0);
+                        workout_tracker_widget_front(ctx, ui, Vec2::new(100.0, 226.0), &exercises);
+                        workout_tracker_widget_behind(ctx, ui, Vec2::new(100.0, 226.0), &exercises);
+                    });
+
+                    for done in workout.exercises.iter() {
+                        let mode = tracking_for(&done.exercise);
+                        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            let pr = if prs.contains(&done.exercise) { "  pr" } else { "" };
+                            ui.add(Label::new(RichText::new(format!("{}{}", done.exercise, pr)).size(15.0).strong().color(text_color)));
+                            for set in done.sets.iter() {
+                                let kind = if set.warmup { "warm-up " } else { "" };
+                                let side = set.side.map(|side| format!(" {}", side)).unwrap_or_default();
+                                let note = if set.note.is_empty() { String::new() } else { format!("  - {}", set.note) };
+                                ui.add(Label::new(RichText::new(format!("{}{}{}{}", kind, set.label(mode), side, note)).size(13.0).color(if set.warmup { Color32::GRAY } else { text_color })));
+                            }
+                            if !done.notes.is_empty() {
+                                ui.add(Label::new(RichText::new(&done.notes).size(12.0).italics().color(Color32::GRAY)).wrap());
+                            }
+                        });
+                        ui.add_space(5.0);
+                    }
+
+                    ui.add_space(REMAINDER / 2.0);
+                    ui.horizontal(|ui| {
+                        if ui.add(Button::new(RichText::new("edit").size(14.0).strong().color(Color32::WHITE))
+                            .fill(Color32::from_rgb(0, 79, 148))
+                            .min_size(Vec2::new(90.0, 28.0))
+                            .rounding(8)).clicked() {
+                            self.states.history_date = workout.date.format("%Y-%m-%d").to_string();
+                            self.states.history_draft = Some(workout.clone());
+                        }
+                        let label = if self.states.history_confirm_delete { "tap again to delete" } else { "delete" };
+                        if ui.add(Button::new(RichText::new(label).size(14.0).strong().color(Color32::WHITE))
+                            .fill(Color32::from_rgb(141, 0, 19))
+                            .min_size(Vec2::new(90.0, 28.0))
+                            .rounding(8)).clicked() {
+                            if self.states.history_confirm_delete {
+                                delete = true;
+                            } else {
+                                self.states.history_confirm_delete = true;
+                            }
+                        }
+                    });
+                }
+                Some(draft) => {
+                    let date = NaiveDate::parse_from_str(self.states.history_date.trim(), "%Y-%m-%d").ok();
+                    egui::Grid::new("history_edit").num_columns(2).spacing(vec2(15.0, 6.0)).show(ui, |ui| {
+                        ui.label("date");
+                        ui.horizontal(|ui| {
+                            ui.add(TextEdit::singleline(&mut self.states.history_date).hint_text("yyyy-mm-dd").desired_width(100.0));
+                            if date.is_none() {
+                                ui.add(Label::new(RichText::new("not a date").size(12.0).color(Color32::from_rgb(200, 30, 30))));
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("duration");
+                        ui.add(egui::DragValue::new(&mut draft.length).range(0..=600).suffix(" min"));
+                        ui.end_row();
+
+                        ui.label("rating");
+                        ui.horizontal(|ui| {
+                            for stars in 1..=5u8 {
+                                if ui.selectable_label(draft.rating.is_some_and(|rating| rating >= stars), stars.to_string()).clicked() {
+                                    draft.rating = if draft.rating == Some(stars) { None } else { Some(stars) };
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    });
+                    ui.add(TextEdit::multiline(&mut draft.notes).hint_text("session notes").desired_rows(2).desired_width(f32::INFINITY));
+                    ui.add_space(REMAINDER / 2.0);
+
+                    for done in draft.exercises.iter_mut() {
+                        let mode = tracking_for(&done.exercise);
+                        egui::Frame::new().fill(other_elements_color).corner_radius(8).inner_margin(8.0).show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.add(Label::new(RichText::new(done.exercise.to_string()).size(15.0).strong().color(text_color)));
+                            let mut removed = None;
+                            for (set_index, set) in done.sets.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if set.warmup {
+                                        ui.add(Label::new(RichText::new("warm-up").size(12.0).color(Color32::GRAY)));
+                                    }
+                                    match mode {
+                                        TrackingMode::WeightReps | TrackingMode::BodyweightPlus => {
+                                            ui.add(egui::DragValue::new(&mut set.weight).speed(0.5).range(0.0..=500.0).suffix(" kg"));
+                                            ui.add(egui::DragValue::new(&mut set.reps).range(0..=100).suffix(" reps"));
+                                        }
+                                        TrackingMode::RepsOnly => {
+                                            ui.add(egui::DragValue::new(&mut set.reps).range(0..=500).suffix(" reps"));
+                                        }
+                                        TrackingMode::TimeOnly | TrackingMode::DistanceTime => {
+                                            if mode == TrackingMode::DistanceTime {
+                                                ui.add(egui::DragValue::new(&mut set.distance_m).speed(1.0).range(0.0..=10000.0).suffix(" m"));
+                                            }
+                                            ui.add(egui::DragValue::new(&mut set.duration_secs).speed(1.0).range(0..=7200).suffix(" s"));
+                                        }
+                                    }
+                                    ui.add(TextEdit::singleline(&mut set.note).hint_text("note").desired_width(80.0));
+                                    if ui.small_button("remove").clicked() {
+                                        removed = Some(set_index);
+                                    }
+                                });
+                            }
+                            if let Some(set_index) = removed {
+                                done.sets.remove(set_index);
+                            }
+                            ui.add(TextEdit::singleline(&mut done.notes).hint_text("exercise note").desired_width(f32::INFINITY));
+                        });
+                        ui.add_space(5.0);
+                    }
+
+                    ui.add_space(REMAINDER / 2.0);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(date.is_some(), Button::new(RichText::new("save").size(14.0).strong().color(Color32::WHITE))
+                            .fill(Color32::from_rgb(21, 141, 0))
+                            .min_size(Vec2::new(90.0, 28.0))
+                            .rounding(8)).clicked() {
+                            if let Some(date) = date {
+                                draft.date = date;
+                            }
+                            save = true;
+                        }
+                        if ui.add(Button::new(RichText::new("cancel").size(14.0).color(Color32::WHITE))
+                            .fill(Color32::from_rgb(96, 96, 96))
+                            .min_size(Vec2::new(90.0, 28.0))
+                            .rounding(8)).clicked() {
+                            cancel = true;
+                        }
+                    });
+                }
+            }
+        });
+
+        if save {
+            if let Some(draft) = self.states.history_draft.take() {
+                self.datas.replace_workout(index, draft);
+            }
+        } else if cancel {
+            self.states.history_draft = None;
+        }
+        if delete {
+            self.datas.delete_workout(index);
+            back = true;
+        }
+        if back {
+            self.states.history_selected = None;
+            self.states.history_draft = None;
+            self.states.history_confirm_delete = false;
+        }
+    }
+
+    pub fn draw_occurrence_window(&mut self, ctx: &Context, text_color: Color32, open: &mut bool) {
+        let Some((date, index)) = self.states.occurrence_edit else { return; };
+        let Some(planned) = self.datas.planned_workout_data.workouts.get(&date).and_then(|w| w.get(index)).cloned() else {
+            self.states.occurrence_edit = None;
+            return;
+        };
+        let Some(id) = planned.schedule else {
+            self.states.occurrence_edit = None;
+            return;
+        };
+
+        let window_size = vec2(320.0, 220.0);
+        let mut template_names: Vec<String> = self.datas.all_workout_data.workout_templates.keys().cloned().collect();
+        template_names.sort();
+
+        if self.states.schedule_template.is_empty() || !template_names.contains(&self.states.schedule_template) {
+            self.states.schedule_template = planned.template.workout_name.clone();
+        }
+
+        let mut done = false;
+
+        Window::new("recurring workout")
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .open(open)
+            .fixed_size(window_size)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add(Label::new(RichText::new(format!("{} on {}", planned.template.workout_name, date.format("%b %d"))).size(18.0).strong().color(text_color)));
+                });
+
+                ui.add_space(REMAINDER);
+
+                ui.horizontal(|ui| {
+                    ui.label("change to");
+                    egui::ComboBox::from_id_salt("occurrence_template")
+                        .selected_text(&self.states.schedule_template)
+                        .show_ui(ui, |ui| {
+                            for name in &template_names {
+                                ui.selectable_value(&mut self.states.schedule_template, name.clone(), name);
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("this occurrence").clicked() {
+                        if let Some(template) = self.datas.all_workout_data.workout_templates.get(&self.states.schedule_template).cloned() {
+                            self.datas.planned_workout_data.change_occurrence(date, index, template);
+                        }
+                        done = true;
+                    }
+                    if ui.button("all future occurrences").clicked() {
+                        let until = Local::now().date_naive().max(date) + Duration::days(28);
+                        self.datas.planned_workout_data.change_future_occurrences(id, date, &self.datas.all_workout_data.workout_templates, self.states.schedule_template.clone(), until);
+                        done = true;
+                    }
+                });
+
+                ui.add_space(REMAINDER);
+                ui.separator();
+                ui.label("remove");
+
+                ui.horizontal(|ui| {
+                    if ui.add(Button::new(RichText::new("this occurrence").color(Color32::WHITE)).fill(Color32::from_rgb(141, 0, 19))).clicked() {
+                        self.datas.planned_workout_data.remove_occurrence(date, index);
+                        done = true;
+                    }
+                    if ui.add(Button::new(RichText::new("all future occurrences").color(Color32::WHITE)).fill(Color32::from_rgb(141, 0, 19))).clicked() {
+                        self.datas.planned_workout_data.remove_future_occurrences(id, date);
+                        done = true;
+                    }
+                });
+            });
+
+        if done {
+            self.datas.touch();
+        }
+        if done || !*open {
+            self.states.occurrence_edit = None;
+            self.states.schedule_template.clear();
+        }
+    }
 }